@@ -0,0 +1,181 @@
+//! Data-source snapshot tool.
+//! Creates, imports and exports chain state snapshots used by `FileDataSource`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+
+use anyhow::{anyhow, Error};
+use clap::Clap;
+use serde_json::{to_string, to_string_pretty};
+
+use data_source::file::{parse_address, FileDataSource, Snapshot};
+use dvm_cli::version;
+use lang::stdlib::{build_external_std, build_std, Stdlib};
+
+#[derive(Clap, Debug)]
+#[clap(name = "ds-snapshot", version = version!())]
+enum Opt {
+    #[clap(about = "Create a snapshot with the standard library.")]
+    Init {
+        /// Path to the snapshot file.
+        #[clap(parse(from_os_str))]
+        snapshot: PathBuf,
+        /// Path to the directory with the standard library sources.
+        /// The built-in standard library is used if not passed.
+        #[clap(long = "stdlib", parse(from_os_str))]
+        stdlib: Option<PathBuf>,
+    },
+    #[clap(about = "Merge json file (snapshot or stdlib-builder output) into the snapshot.")]
+    Import {
+        /// Path to the snapshot file.
+        #[clap(parse(from_os_str))]
+        snapshot: PathBuf,
+        /// Path to the file to import.
+        #[clap(parse(from_os_str))]
+        input: PathBuf,
+    },
+    #[clap(about = "Export snapshot write set in the stdlib-builder (genesis) format.")]
+    Export {
+        /// Path to the snapshot file.
+        #[clap(parse(from_os_str))]
+        snapshot: PathBuf,
+        /// Optional path to the output file.
+        /// If not passed, result will be printed to stdout.
+        #[clap(short, long, parse(from_os_str))]
+        output: Option<PathBuf>,
+        /// Enables pretty printing.
+        #[clap(short)]
+        pretty_print: bool,
+    },
+    #[clap(about = "Set oracle price.")]
+    Price {
+        /// Path to the snapshot file.
+        #[clap(parse(from_os_str))]
+        snapshot: PathBuf,
+        /// First currency ticker.
+        currency_1: String,
+        /// Second currency ticker.
+        currency_2: String,
+        /// Price value.
+        price: u128,
+        /// Unix time of the price update in seconds. Unknown if not passed.
+        #[clap(long)]
+        timestamp: Option<u64>,
+    },
+    #[clap(about = "Set native balance.")]
+    Balance {
+        /// Path to the snapshot file.
+        #[clap(parse(from_os_str))]
+        snapshot: PathBuf,
+        /// Account address.
+        address: String,
+        /// Currency ticker.
+        ticker: String,
+        /// Balance value.
+        value: u128,
+    },
+}
+
+fn main() {
+    let res = match Opt::parse() {
+        Opt::Init { snapshot, stdlib } => init(&snapshot, stdlib),
+        Opt::Import { snapshot, input } => import(&snapshot, &input),
+        Opt::Export {
+            snapshot,
+            output,
+            pretty_print,
+        } => export(&snapshot, output, pretty_print),
+        Opt::Price {
+            snapshot,
+            currency_1,
+            currency_2,
+            price,
+            timestamp,
+        } => FileDataSource::open(&snapshot).and_then(|ds| {
+            let currency_1 = currency_1.to_uppercase();
+            let currency_2 = currency_2.to_uppercase();
+            match timestamp {
+                Some(timestamp) => {
+                    ds.add_price_with_time(&currency_1, &currency_2, price, timestamp)
+                }
+                None => ds.add_price(&currency_1, &currency_2, price),
+            }
+            ds.save()
+        }),
+        Opt::Balance {
+            snapshot,
+            address,
+            ticker,
+            value,
+        } => FileDataSource::open(&snapshot).and_then(|ds| {
+            ds.set_balance(parse_address(&address)?, &ticker.to_uppercase(), value);
+            ds.save()
+        }),
+    };
+
+    if let Err(err) = res {
+        println!("error: {:?}.", err);
+        exit(1);
+    }
+}
+
+fn init(snapshot: &Path, stdlib: Option<PathBuf>) -> Result<(), Error> {
+    if snapshot.exists() {
+        return Err(anyhow!("destination `{:?}` already exists", snapshot));
+    }
+
+    let ws = match stdlib {
+        Some(dir) => build_external_std(Stdlib {
+            modules: load_modules(&dir)?,
+        })?,
+        None => build_std(),
+    };
+
+    let ds = FileDataSource::open(snapshot)?;
+    ds.merge_write_set(ws);
+    ds.save()
+}
+
+fn import(snapshot: &Path, input: &Path) -> Result<(), Error> {
+    let ds = FileDataSource::open(snapshot)?;
+    ds.apply_snapshot(Snapshot::load(input)?)?;
+    ds.save()
+}
+
+fn export(snapshot: &Path, output: Option<PathBuf>, pretty_print: bool) -> Result<(), Error> {
+    let ds = FileDataSource::open(snapshot)?;
+    let ws = Snapshot {
+        write_set: ds.snapshot().write_set,
+        ..Default::default()
+    };
+    let ws = if pretty_print {
+        to_string_pretty(&ws)?
+    } else {
+        to_string(&ws)?
+    };
+
+    if let Some(path) = output {
+        fs::write(&path, &ws)?;
+    } else {
+        println!("{}", ws);
+    }
+    Ok(())
+}
+
+fn load_modules(dir: &Path) -> Result<HashMap<String, String>, Error> {
+    let mut modules = HashMap::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().map(|ext| ext == "move").unwrap_or(false) {
+            let name = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map(|name| name.to_owned())
+                .ok_or_else(|| Error::msg("Expected file name"))?;
+            modules.insert(name, fs::read_to_string(&path)?);
+        }
+    }
+    Ok(modules)
+}
@@ -0,0 +1,132 @@
+//! Local devnet: Definance Virtual Machine
+//! with the embedded file-backed data source.
+//! Run with `cargo run --bin dvm-devnet "http://[::1]:50051" --state ./devnet.json`
+
+#[macro_use]
+extern crate log;
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Clap;
+use futures::future::FutureExt;
+
+use compiler::Compiler;
+use data_source::FileDataSource;
+use dvm_cli::config::*;
+use dvm_cli::{init, version};
+use dvm_net::{api, prelude::*, tonic};
+use api::grpc::dvm_bytecode_metadata_server::DvmBytecodeMetadataServer;
+use dvm_net::compiler_api::dvm_compiler_server::DvmCompilerServer;
+use api::grpc::vm_module_publisher_server::VmModulePublisherServer;
+use api::grpc::vm_script_executor_server::VmScriptExecutorServer;
+use lang::stdlib::build_std;
+use runtime::vm::dvm::Dvm;
+use services::compiler::CompilerService;
+use services::devnet::DevnetService;
+use services::metadata::MetadataService;
+use tonic::transport::Server;
+
+/// Local devnet: Definance Virtual Machine
+///  with the embedded data source persisted to the local state file.
+/// Every execution result is applied to the state. Scripts are executed in the block passed in the request,
+///  block height and time are incremented automatically for requests with zero block height.
+/// Serves the same gRPC services as `dvm`.
+#[derive(Debug, Clone, Clap)]
+#[clap(name = "dvm-devnet", version = version!())]
+#[clap(verbatim_doc_comment)]
+struct Options {
+    /// Address in the form of HOST_ADDRESS:PORT.
+    /// The address will be listen to by DVM and compilation server.
+    /// Listening localhost by default.
+    /// Supports schemes: http, ipc.
+    #[clap(
+        name = "listen address",
+        default_value = "http://[::1]:50051",
+        verbatim_doc_comment
+    )]
+    address: Endpoint,
+
+    /// Path to the state snapshot file.
+    /// New state with the standard library is created if the file does not exist.
+    #[clap(
+        long = "state",
+        default_value = "devnet.json",
+        parse(from_os_str),
+        verbatim_doc_comment
+    )]
+    state: PathBuf,
+
+    /// Enables charging of transaction fees in XFI: gas_used * gas_unit_price.
+    /// The fees are withdrawn from the native balances of the fee payers in the state.
+    #[clap(long = "charge-fee", verbatim_doc_comment)]
+    charge_fee: bool,
+
+    /// Max number of script signers.
+    #[clap(long = "signers-limit", env = DVM_SIGNERS_LIMIT, default_value = "8")]
+    signers_limit: usize,
+
+    #[clap(flatten)]
+    logging: LoggingOptions,
+
+    #[clap(flatten)]
+    integrations: IntegrationsOptions,
+}
+
+fn main() -> Result<()> {
+    remove_empty_env_vars();
+    let options = Options::parse();
+    let _guard = init(&options.logging, &options.integrations);
+    main_internal(options)
+}
+
+#[tokio::main]
+async fn main_internal(options: Options) -> Result<()> {
+    let (serv_term_tx, serv_term_rx) = futures::channel::oneshot::channel();
+    let sigterm = dvm_cli::init_sigterm_handler_fut(move || {
+        // shutdown server
+        match serv_term_tx.send(()) {
+            Ok(_) => info!("shutting down devnet server"),
+            Err(err) => error!("unable to send sig into the server: {:?}", err),
+        }
+    });
+
+    // data-source
+    let is_new = !options.state.exists();
+    let ds = FileDataSource::open(&options.state)?;
+    if is_new {
+        info!("create devnet state {:?}", options.state);
+        ds.merge_write_set(build_std());
+        ds.save()?;
+    }
+
+    // vm services
+    let vm = Dvm::new(ds.clone(), None)
+        .with_fee(options.charge_fee)
+        .with_signers_limit(options.signers_limit);
+    let vm_service = DevnetService::new(vm);
+    // comp services
+    let compiler_service = CompilerService::new(Compiler::new(ds));
+    let metadata_service = MetadataService::default();
+
+    // spawn the signal-router:
+    tokio::spawn(sigterm);
+    // block-on the server:
+    Server::builder()
+        // vm service
+        .add_service(VmScriptExecutorServer::new(vm_service.clone()))
+        .add_service(VmModulePublisherServer::new(vm_service))
+        // comp services
+        .add_service(DvmCompilerServer::new(compiler_service))
+        .add_service(DvmBytecodeMetadataServer::new(metadata_service))
+        // serve
+        .serve_ext_with_shutdown(options.address, serv_term_rx.map(|_| ()))
+        .map(|res| {
+            info!("devnet server is shutted down");
+            res
+        })
+        .await
+        .expect("Devnet internal error");
+
+    Ok(())
+}
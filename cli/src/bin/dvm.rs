@@ -16,13 +16,13 @@ use services::compiler::CompilerService;
 use services::metadata::MetadataService;
 
 use dvm_net::{prelude::*, api, tonic};
-use api::grpc::dvm_compiler_server::DvmCompilerServer;
+use dvm_net::compiler_api::dvm_compiler_server::DvmCompilerServer;
 use api::grpc::dvm_bytecode_metadata_server::DvmBytecodeMetadataServer;
 use dvm_net::api::grpc::{
     vm_script_executor_server::VmScriptExecutorServer,
     vm_module_publisher_server::VmModulePublisherServer,
 };
-use data_source::{GrpcDataSource, ModuleCache, DsMeter};
+use data_source::{GrpcDataSource, ModuleCache, DsMeter, CrossRateDataSource};
 use anyhow::Result;
 use services::vm::VmService;
 use dvm_cli::config::*;
@@ -66,6 +66,30 @@ struct Options {
     )]
     ds: Uri,
 
+    /// Charges transaction fees in XFI: `gas_used * gas_unit_price`.
+    /// Fee payer native XFI balance is checked before and after execution,
+    /// the fee is merged into the XFI balance change of the payer.
+    #[clap(long = "charge-fee", verbatim_doc_comment)]
+    charge_fee: bool,
+
+    /// Re-executes every N-th transaction on a fresh virtual machine
+    /// with the same data source reads and reports divergent results.
+    /// Disabled by default.
+    #[clap(
+        long = "determinism-check-rate",
+        env = DVM_DETERMINISM_CHECK_RATE,
+        default_value = "0",
+        verbatim_doc_comment
+    )]
+    determinism_check_rate: u64,
+
+    /// Max number of script signers.
+    #[clap(long = "signers-limit", env = DVM_SIGNERS_LIMIT, default_value = "8")]
+    signers_limit: usize,
+
+    #[clap(flatten)]
+    oracle: OracleOptions,
+
     #[clap(flatten)]
     logging: LoggingOptions,
 
@@ -103,10 +127,15 @@ async fn main_internal(options: Options) -> Result<()> {
     // data-source client
     let ds = GrpcDataSource::new(options.ds, Some(ds_term_rx))
         .expect("Unable to instantiate GrpcDataSource.");
-    let ds = ModuleCache::new(DsMeter::new(ds), options.memory_config.module_cache());
+    let ds = CrossRateDataSource::new(DsMeter::new(ds), options.oracle.cross_rate()?);
+    let ds = ModuleCache::new(ds, options.memory_config.module_cache());
     let mem_checker = MemoryChecker::new(options.memory_config);
     // vm services
-    let vm_service = VmService::new(Dvm::new(ds.clone(), Some(mem_checker)), hrm);
+    let vm = Dvm::new(ds.clone(), Some(mem_checker))
+        .with_fee(options.charge_fee)
+        .with_determinism_check(options.determinism_check_rate)
+        .with_signers_limit(options.signers_limit);
+    let vm_service = VmService::new(vm, hrm);
     // comp services
     let compiler_service = CompilerService::new(Compiler::new(ds));
     let metadata_service = MetadataService::default();
@@ -0,0 +1,343 @@
+extern crate clap;
+
+use clap::Clap;
+use http::Uri;
+use std::env;
+use compiler::{
+    manifest::{MANIFEST, MoveToml},
+    cmd::*,
+};
+use std::process::exit;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use anyhow::Error;
+use compiler::manifest::read_manifest;
+use compiler::diagnostics::{diagnostics, MessageFormat};
+use dvm_cli::movec;
+use dvm_cli::movec::publish::{DvmSubmitter, Submitter};
+use dvm_net::endpoint::Endpoint;
+
+#[derive(Clap, Debug)]
+#[clap(name = "Move compiler.")]
+struct Opts {
+    #[clap(
+        name = "message format",
+        long = "message-format",
+        default_value = "human",
+        global = true
+    )]
+    /// Diagnostics format: human or json (one diagnostic per line).
+    message_format: MessageFormat,
+    #[clap(name = "profile", long = "profile", global = true)]
+    /// Profile with the named addresses and constants from `Move.toml`, e.g. `testnet`.
+    profile: Option<String>,
+    #[clap(subcommand)]
+    command: Opt,
+}
+
+#[derive(Clap, Debug)]
+enum Opt {
+    #[clap(about = "Init directory as move project.")]
+    Init {
+        /// Project name.
+        project_name: String,
+        #[clap(name = "Blockchain API", long = "repo", short = 'r')]
+        /// Basic uri to blockchain api.
+        repository: Option<Uri>,
+        #[clap(name = "address", long = "address", short = 'a')]
+        /// Account address.
+        address: Option<String>,
+    },
+    #[clap(about = "Create a new move project")]
+    New {
+        /// Project name.
+        project_name: String,
+        #[clap(name = "Blockchain API", long = "repo", short = 'r')]
+        /// Basic uri to blockchain api.
+        repository: Option<Uri>,
+        #[clap(name = "address", long = "address", short = 'a')]
+        /// Account address.
+        address: Option<String>,
+    },
+    #[clap(about = "Reload dependencies")]
+    Update {},
+    #[clap(about = "Build project")]
+    Build {
+        #[clap(
+            name = "incremental",
+            long = "incremental",
+            short = 'i',
+            conflicts_with = "workspace"
+        )]
+        /// Recompile only changed files, reuse other units from the build cache.
+        incremental: bool,
+        #[clap(name = "workspace", long = "workspace")]
+        /// Build each member of the workspace.
+        workspace: bool,
+    },
+    #[clap(about = "Check project")]
+    Check {
+        #[clap(name = "workspace", long = "workspace")]
+        /// Check each member of the workspace.
+        workspace: bool,
+    },
+    #[cfg(feature = "movec-test")]
+    #[clap(about = "Run project tests")]
+    Test {
+        #[clap(name = "workspace", long = "workspace")]
+        /// Test each member of the workspace.
+        workspace: bool,
+    },
+    #[clap(about = "Run script")]
+    Run {
+        /// Script name.
+        script: String,
+        /// Script arguments.
+        args: Vec<String>,
+        #[clap(name = "sender", long = "sender", short = 's', number_of_values = 1)]
+        /// Script sender. Can be repeated for multiple signers.
+        /// The project account address is used by default.
+        senders: Vec<String>,
+        #[clap(
+            name = "type args",
+            long = "type-args",
+            short = 't',
+            number_of_values = 1
+        )]
+        /// Comma separated type arguments, e.g. `0x1::Coins::ETH,0x1::XFI::T`.
+        type_args: Vec<String>,
+        #[clap(name = "gas", long = "gas", default_value = "1000000")]
+        /// Max gas amount.
+        gas: u64,
+        #[clap(
+            name = "state",
+            long = "state",
+            default_value = "state.json",
+            parse(from_os_str)
+        )]
+        /// Path to the local state snapshot, relative to the project directory.
+        /// New state with the standard library is created if the file does not exist.
+        state: PathBuf,
+        #[clap(name = "dvm", long = "dvm")]
+        /// Address of the remote DVM. The script is executed locally if not specified.
+        dvm: Option<Endpoint>,
+        #[clap(name = "save", long = "save", conflicts_with = "dvm")]
+        /// Save the execution result and the project modules to the local state.
+        save: bool,
+    },
+    #[clap(about = "Watch project sources and rerun check, build or test on changes")]
+    Watch {
+        #[clap(name = "action", default_value = "check")]
+        /// Command to rerun: check, build or test.
+        action: movec::watch::Action,
+        #[clap(name = "interval", long = "interval", default_value = "500")]
+        /// Polling interval in milliseconds.
+        interval: u64,
+        #[clap(name = "debounce", long = "debounce", default_value = "300")]
+        /// Time in milliseconds the files must stay unchanged before the rerun.
+        debounce: u64,
+    },
+    #[clap(about = "Build project and package modules into publish transaction")]
+    Publish {
+        #[clap(name = "output", long = "output", short = 'o', parse(from_os_str))]
+        /// Transaction file, relative to the project directory.
+        /// `target/publish.json` or `target/publish.lcs` by default.
+        output: Option<PathBuf>,
+        #[clap(name = "format", long = "format", short = 'f', default_value = "json")]
+        /// Transaction encoding: json or lcs.
+        format: movec::publish::Format,
+        #[clap(name = "submit", long = "submit")]
+        /// Address of the DVM to submit the transaction to, e.g. local `dvm-devnet`.
+        submit: Option<Endpoint>,
+        #[clap(name = "gas", long = "gas", default_value = "1000000")]
+        /// Max gas amount per module.
+        gas: u64,
+    },
+}
+
+fn main() {
+    let project_dir = env::current_dir().unwrap();
+    let opts = Opts::parse();
+    let message_format = opts.message_format;
+    let profile = opts.profile;
+    let res = match opts.command {
+        Opt::New {
+            project_name: source_dir,
+            repository,
+            address,
+        } => new::execute(&project_dir, source_dir, repository, address),
+        Opt::Init {
+            project_name: source_dir,
+            repository,
+            address,
+        } => init::execute(&project_dir, source_dir, repository, address),
+        Opt::Update {} => update::execute(&project_dir, load_manifest(&project_dir, &profile)),
+        Opt::Build {
+            incremental,
+            workspace,
+        } => {
+            if workspace {
+                build::execute_workspace(&project_dir, load_manifest(&project_dir, &profile))
+            } else if incremental {
+                build::execute_incremental(
+                    &project_dir,
+                    load_manifest(&project_dir, &profile),
+                    message_format,
+                )
+            } else {
+                build::execute(
+                    &project_dir,
+                    load_manifest(&project_dir, &profile),
+                    message_format,
+                )
+            }
+        }
+        Opt::Check { workspace } => {
+            if workspace {
+                check::execute_workspace(&project_dir, load_manifest(&project_dir, &profile))
+            } else {
+                check::execute(
+                    &project_dir,
+                    load_manifest(&project_dir, &profile),
+                    message_format,
+                )
+            }
+        }
+        #[cfg(feature = "movec-test")]
+        Opt::Test { workspace } => {
+            let manifest = load_manifest(&project_dir, &profile);
+            if workspace {
+                movec::test::execute_workspace(&project_dir, manifest)
+            } else {
+                movec::test::execute(&project_dir, manifest)
+            }
+            .map(|passed| {
+                if !passed {
+                    exit(1);
+                }
+            })
+        }
+        Opt::Run {
+            script,
+            args,
+            senders,
+            type_args,
+            gas,
+            state,
+            dvm,
+            save,
+        } => {
+            let call = movec::run::Call {
+                script,
+                args,
+                senders,
+                type_args,
+                gas,
+            };
+            let target = match dvm {
+                Some(endpoint) => movec::run::Target::Remote(endpoint),
+                None => movec::run::Target::Local {
+                    state: project_dir.join(state),
+                    save,
+                },
+            };
+            movec::run::execute(
+                &project_dir,
+                load_manifest(&project_dir, &profile),
+                call,
+                target,
+            )
+            .map(|executed| {
+                if !executed {
+                    exit(1);
+                }
+            })
+        }
+        Opt::Watch {
+            action,
+            interval,
+            debounce,
+        } => movec::watch::execute(
+            &project_dir,
+            load_manifest(&project_dir, &profile),
+            action,
+            message_format,
+            profile,
+            Duration::from_millis(interval),
+            Duration::from_millis(debounce),
+        ),
+        Opt::Publish {
+            output,
+            format,
+            submit,
+            gas,
+        } => {
+            let output = project_dir.join(output.unwrap_or_else(|| {
+                Path::new("target")
+                    .join("publish")
+                    .with_extension(format.extension())
+            }));
+            let mut submitter = submit.map(|endpoint| DvmSubmitter::new(endpoint, gas));
+            movec::publish::execute(
+                &project_dir,
+                load_manifest(&project_dir, &profile),
+                &output,
+                format,
+                submitter.as_mut().map(|s| s as &mut dyn Submitter),
+            )
+        }
+    };
+    handle_error(message_format, res);
+}
+
+fn handle_error<T>(format: MessageFormat, res: Result<T, Error>) -> T {
+    match res {
+        Ok(t) => t,
+        Err(err) => {
+            match format {
+                MessageFormat::Human => println!("error: {:?}.", err),
+                MessageFormat::Json => {
+                    for diagnostic in diagnostics(&err) {
+                        match serde_json::to_string(&diagnostic) {
+                            Ok(line) => println!("{}", line),
+                            Err(err) => eprintln!("Failed to serialize diagnostic: {}", err),
+                        }
+                    }
+                }
+            }
+            exit(1);
+        }
+    }
+}
+
+fn load_manifest(project_dir: &Path, profile: &Option<String>) -> MoveToml {
+    let manifest = project_dir.join(MANIFEST);
+    if !manifest.exists() {
+        println!(
+            "error: could not find `{}` in `{:?}`.",
+            MANIFEST, project_dir
+        );
+        exit(1);
+    }
+    match read_manifest(&manifest) {
+        Ok(mut manifest) => {
+            if manifest.layout.is_none() {
+                manifest.layout = Some(Default::default());
+            }
+            if let Some(layout) = manifest.layout.as_mut() {
+                layout.fill();
+            }
+            if let Some(profile) = profile {
+                if let Err(err) = manifest.apply_profile(profile) {
+                    println!("error: {}", err);
+                    exit(1);
+                }
+            }
+            manifest
+        }
+        Err(_) => {
+            println!("error: could not read `{:?}`.", &manifest);
+            exit(1);
+        }
+    }
+}
@@ -1,5 +1,8 @@
+use anyhow::Error;
 use clap::Clap;
 
+use data_source::CrossRateConfig;
+
 // rust env variables
 pub const RUST_LOG: &str = "RUST_LOG";
 pub const RUST_LOG_STYLE: &str = "RUST_LOG_STYLE";
@@ -9,6 +12,10 @@ pub const DVM_LOG_STYLE: &str = "DVM_LOG_COLOR";
 pub const DVM_DATA_SOURCE: &str = "DVM_DATA_SOURCE";
 pub const DVM_SENTRY_DSN: &str = "DVM_SENTRY_DSN";
 pub const DVM_SENTRY_ENV: &str = "DVM_SENTRY_ENVIRONMENT";
+pub const DVM_ORACLE_BASE: &str = "DVM_ORACLE_BASE";
+pub const DVM_ORACLE_DECIMALS: &str = "DVM_ORACLE_DECIMALS";
+pub const DVM_DETERMINISM_CHECK_RATE: &str = "DVM_DETERMINISM_CHECK_RATE";
+pub const DVM_SIGNERS_LIMIT: &str = "DVM_SIGNERS_LIMIT";
 
 pub const MAX_LOG_VERBOSE: u8 = 4;
 
@@ -60,6 +67,45 @@ pub struct IntegrationsOptions {
     pub sentry_env: Option<String>,
 }
 
+#[derive(Debug, Clone, Clap)]
+pub struct OracleOptions {
+    /// Enables cross-rate resolution of oracle prices.
+    /// Missing prices are resolved from the inverse pairs
+    /// and with a single hop via the base currency.
+    /// Changes prices visible to scripts, so must be the same on all nodes of the network.
+    #[clap(long = "oracle-cross-rate", verbatim_doc_comment)]
+    pub cross_rate: bool,
+
+    /// Base currency for cross-rate resolution.
+    #[clap(
+        long = "oracle-base",
+        env = DVM_ORACLE_BASE,
+        default_value = "XFI",
+        verbatim_doc_comment
+    )]
+    pub base: String,
+
+    /// Number of oracle price decimals used in cross-rate resolution.
+    #[clap(
+        long = "oracle-decimals",
+        env = DVM_ORACLE_DECIMALS,
+        default_value = "18",
+        verbatim_doc_comment
+    )]
+    pub decimals: u8,
+}
+
+impl OracleOptions {
+    /// Returns cross-rate config if resolution is enabled.
+    pub fn cross_rate(&self) -> Result<Option<CrossRateConfig>, Error> {
+        if self.cross_rate {
+            CrossRateConfig::new(&self.base, self.decimals).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 pub fn remove_empty_env_vars() {
     use std::env;
     [
@@ -68,6 +114,10 @@ pub fn remove_empty_env_vars() {
         DVM_DATA_SOURCE,
         DVM_SENTRY_DSN,
         DVM_SENTRY_ENV,
+        DVM_ORACLE_BASE,
+        DVM_ORACLE_DECIMALS,
+        DVM_DETERMINISM_CHECK_RATE,
+        DVM_SIGNERS_LIMIT,
     ]
     .iter()
     .for_each(|var| {
@@ -5,6 +5,7 @@ pub extern crate log;
 pub mod config;
 pub mod info_service;
 pub mod logging;
+pub mod movec;
 
 use config::*;
 use futures::future::{lazy, Future, FutureExt};
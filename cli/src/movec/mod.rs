@@ -0,0 +1,131 @@
+//! Movec commands which need the virtual machine.
+
+/// Modules publishing.
+pub mod publish;
+/// Script runner.
+pub mod run;
+/// Project tests runner.
+#[cfg(feature = "movec-test")]
+pub mod test;
+/// Project watcher.
+pub mod watch;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Error};
+use walkdir::WalkDir;
+
+use compiler::bech32::bech32_into_libra;
+use compiler::builder::Builder;
+use compiler::manifest::{Layout, MoveToml};
+use compiler::dependence::loader::make_rest_loader;
+use compiler::dependence::lock::MoveLock;
+use compiler::dependence::resolver::{resolve, Dependencies};
+use compiler::Compiler;
+use data_source::MockDataSource;
+use lang::stdlib::build_std;
+use libra::prelude::*;
+
+/// Returns the project account address from the manifest.
+pub fn account_address(manifest: &MoveToml) -> Result<Option<AccountAddress>, Error> {
+    manifest
+        .package
+        .account_address
+        .as_ref()
+        .map(|addr| parse_address(addr))
+        .transpose()
+}
+
+/// Parses account address in the hex or bech32 form.
+pub fn parse_address(addr: &str) -> Result<AccountAddress, Error> {
+    let addr = if addr.starts_with("0x") {
+        addr.to_owned()
+    } else {
+        format!("0x{}", bech32_into_libra(addr)?)
+    };
+    AccountAddress::from_hex_literal(&addr)
+        .map_err(|err| anyhow!("Invalid account address '{}': {}", addr, err))
+}
+
+/// Reads `.move` files of the directory.
+/// Files are named by the path relative to the directory with the separators replaced by `_`.
+/// Fails if the names of two files collide, e.g. `a/b.move` and `a_b.move`.
+pub fn read_sources(dir: &Path) -> Result<HashMap<String, String>, Error> {
+    let mut sources = HashMap::new();
+    if !dir.exists() {
+        return Ok(sources);
+    }
+
+    for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.extension().map(|ext| ext == "move").unwrap_or(false) {
+            let name = path
+                .strip_prefix(dir)?
+                .with_extension("")
+                .to_string_lossy()
+                .replace(std::path::MAIN_SEPARATOR, "_");
+            if sources.contains_key(&name) {
+                return Err(anyhow!(
+                    "Source file {:?} collides with another file named '{}'.",
+                    path,
+                    name
+                ));
+            }
+            sources.insert(name, fs::read_to_string(path)?);
+        }
+    }
+    Ok(sources)
+}
+
+/// Returns the path of the layout directory.
+pub fn layout_dir<F>(project_dir: &Path, manifest: &MoveToml, dir: F) -> Result<PathBuf, Error>
+where
+    F: FnOnce(&Layout) -> Option<&String>,
+{
+    manifest
+        .layout
+        .as_ref()
+        .and_then(dir)
+        .map(|dir| project_dir.join(dir))
+        .ok_or_else(|| anyhow!("Expected filled project layout."))
+}
+
+/// Resolves declared dependencies of the project.
+pub fn dependencies(project_dir: &Path, manifest: &MoveToml) -> Result<Dependencies, Error> {
+    resolve(project_dir, manifest, &MoveLock::read(project_dir)?)
+}
+
+/// Builds the genesis write set: the standard library with the declared dependencies and the project modules.
+///
+/// Chain modules imported by the project are loaded from the `blockchain_api` the same way as for the build.
+/// Modules of the standard library are not replaced by their chain versions.
+pub fn genesis(project_dir: &Path, manifest: &MoveToml) -> Result<WriteSet, Error> {
+    let ds = MockDataSource::with_write_set(build_std());
+    let dependencies = dependencies(project_dir, manifest)?;
+    for module in dependencies.modules.values() {
+        ds.publish_module(module.to_owned())?;
+    }
+    if let Some(loader) = make_rest_loader(project_dir, manifest)? {
+        let loader = Some(loader.with_modules(dependencies.modules));
+        let builder = Builder::new(project_dir, manifest.clone(), &loader, false, false);
+        builder.init_build_layout()?;
+        let source_map = builder.preprocess_source_map(builder.make_source_map()?)?;
+        for (id, module) in builder.load_dependencies(&source_map)? {
+            if ds.get(&AccessPath::from(&id)).is_none() {
+                ds.publish_module_with_id(id, module)?;
+            }
+        }
+    }
+
+    let module_dir = layout_dir(project_dir, manifest, |l| l.module_dir.as_ref())?;
+    let modules = read_sources(&module_dir)?;
+    if !modules.is_empty() {
+        let compiler = Compiler::new(ds.clone());
+        for (_, module) in compiler.compile_source_map(modules, account_address(manifest)?)? {
+            ds.publish_module(module)?;
+        }
+    }
+    ds.to_write_set()
+}
@@ -0,0 +1,274 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error};
+use serde::Serialize;
+use tokio::runtime::Runtime;
+
+use compiler::cmd::build;
+use compiler::dependence::extractor::extract_from_bytecode;
+use compiler::diagnostics::MessageFormat;
+use compiler::manifest::MoveToml;
+use dvm_net::api::grpc::vm_module_publisher_client::VmModulePublisherClient;
+use dvm_net::api::grpc::VmPublishModule;
+use dvm_net::api::tonic::Request;
+use dvm_net::endpoint::Endpoint;
+use libra::prelude::*;
+
+use crate::movec::run::{format_address, format_status};
+use crate::movec::{account_address, layout_dir};
+
+/// Module publishing transaction payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Transaction {
+    /// Account which publishes the modules.
+    pub sender: AccountAddress,
+    /// Bytecode of the modules in the publishing order:
+    /// each module goes after its dependencies.
+    pub modules: Vec<Vec<u8>>,
+}
+
+/// Transaction payload encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Json with hex encoded sender and modules.
+    Json,
+    /// LCS encoded `Transaction`.
+    Lcs,
+}
+
+impl Format {
+    /// Payload file extension.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Lcs => "lcs",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(Format::Json),
+            "lcs" => Ok(Format::Lcs),
+            _ => Err(anyhow!("Unknown format '{}'. Expected json or lcs.", s)),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonTransaction {
+    sender: String,
+    modules: Vec<JsonModule>,
+}
+
+#[derive(Serialize)]
+struct JsonModule {
+    name: String,
+    code: String,
+}
+
+impl Transaction {
+    /// Makes a transaction with the modules of the sender.
+    /// Modules are sorted in the dependency order.
+    pub fn new(sender: AccountAddress, modules: Vec<Vec<u8>>) -> Result<Transaction, Error> {
+        let mut module_map = BTreeMap::new();
+        for bytecode in modules {
+            let id = module_id(&bytecode)?;
+            if *id.address() != sender {
+                return Err(anyhow!(
+                    "Module {}::{} can't be published by {}.",
+                    format_address(&id.address().to_vec()),
+                    id.name(),
+                    format_address(&sender.to_vec())
+                ));
+            }
+            module_map.insert(id, bytecode);
+        }
+
+        let mut ordered = Vec::with_capacity(module_map.len());
+        let mut visited = HashSet::new();
+        let mut path = HashSet::new();
+        for id in module_map.keys() {
+            sort(id, &module_map, &mut visited, &mut path, &mut ordered)?;
+        }
+
+        Ok(Transaction {
+            sender,
+            modules: ordered,
+        })
+    }
+
+    /// Encodes the transaction.
+    pub fn encode(&self, format: Format) -> Result<Vec<u8>, Error> {
+        match format {
+            Format::Lcs => Ok(lcs::to_bytes(self)?),
+            Format::Json => {
+                let modules = self
+                    .modules
+                    .iter()
+                    .map(|bytecode| {
+                        Ok(JsonModule {
+                            name: module_id(bytecode)?.name().as_str().to_owned(),
+                            code: hex::encode(bytecode),
+                        })
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+                Ok(serde_json::to_vec_pretty(&JsonTransaction {
+                    sender: format_address(&self.sender.to_vec()),
+                    modules,
+                })?)
+            }
+        }
+    }
+}
+
+/// Depth-first topological sort of the modules.
+fn sort(
+    id: &ModuleId,
+    modules: &BTreeMap<ModuleId, Vec<u8>>,
+    visited: &mut HashSet<ModuleId>,
+    path: &mut HashSet<ModuleId>,
+    ordered: &mut Vec<Vec<u8>>,
+) -> Result<(), Error> {
+    if visited.contains(id) {
+        return Ok(());
+    }
+    if !path.insert(id.clone()) {
+        return Err(anyhow!("Cyclic dependency on module {}.", id.name()));
+    }
+
+    let bytecode = &modules[id];
+    let mut deps = extract_from_bytecode(bytecode)?
+        .into_iter()
+        .filter(|dep| modules.contains_key(dep))
+        .collect::<Vec<_>>();
+    deps.sort();
+    for dep in &deps {
+        sort(dep, modules, visited, path, ordered)?;
+    }
+
+    path.remove(id);
+    visited.insert(id.clone());
+    ordered.push(bytecode.to_owned());
+    Ok(())
+}
+
+fn module_id(bytecode: &[u8]) -> Result<ModuleId, Error> {
+    Ok(CompiledModule::deserialize(bytecode)
+        .map_err(|err| err.finish(Location::Undefined).into_vm_status())?
+        .self_id())
+}
+
+/// Transaction submitter.
+pub trait Submitter {
+    /// Submits the transaction.
+    fn submit(&mut self, tx: &Transaction) -> Result<(), Error>;
+}
+
+/// Submits modules one by one to the DVM module publisher service.
+/// Use `dvm-devnet` to keep published modules in the local state.
+#[derive(Debug)]
+pub struct DvmSubmitter {
+    endpoint: Endpoint,
+    gas: u64,
+}
+
+impl DvmSubmitter {
+    /// Creates a new submitter with the DVM address and max gas amount per module.
+    pub fn new(endpoint: Endpoint, gas: u64) -> DvmSubmitter {
+        DvmSubmitter { endpoint, gas }
+    }
+}
+
+impl Submitter for DvmSubmitter {
+    fn submit(&mut self, tx: &Transaction) -> Result<(), Error> {
+        let mut runtime = Runtime::new()?;
+        let endpoint = self.endpoint.clone();
+        let mut client = runtime.block_on(async {
+            endpoint
+                .connect()
+                .await
+                .map(VmModulePublisherClient::new)
+                .map_err(|err| anyhow!("Failed to connect to DVM: {}", err))
+        })?;
+
+        for code in &tx.modules {
+            let name = module_id(code)?.name().as_str().to_owned();
+            let request = Request::new(VmPublishModule {
+                sender: tx.sender.to_vec(),
+                max_gas_amount: self.gas,
+                gas_unit_price: 1,
+                code: code.to_owned(),
+            });
+            let response = runtime
+                .block_on(client.publish_module(request))?
+                .into_inner();
+
+            println!(
+                "module {}: {} (gas used {})",
+                name,
+                format_status(response.status.as_ref()),
+                response.gas_used
+            );
+            let executed = response
+                .status
+                .map(|status| status.error.is_none())
+                .unwrap_or(false);
+            if !executed {
+                return Err(anyhow!("Failed to publish module {}.", name));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Executes publish command.
+/// Builds the project and writes the transaction with the project modules to the `output` file.
+/// Then submits the transaction if the submitter is specified.
+pub fn execute(
+    project_dir: &Path,
+    manifest: MoveToml,
+    output: &Path,
+    format: Format,
+    submitter: Option<&mut dyn Submitter>,
+) -> Result<(), Error> {
+    let sender = account_address(&manifest)?
+        .ok_or_else(|| anyhow!("Expected the project account_address."))?;
+    let modules_dir = layout_dir(project_dir, &manifest, |l| l.module_output.as_ref())?;
+    build::execute(project_dir, manifest, MessageFormat::Human)?;
+
+    let mut modules = Vec::new();
+    if modules_dir.exists() {
+        for entry in fs::read_dir(&modules_dir)? {
+            let path = entry?.path();
+            if path.extension().map(|ext| ext == "mv").unwrap_or(false) {
+                modules.push(fs::read(path)?);
+            }
+        }
+    }
+    if modules.is_empty() {
+        return Err(anyhow!("Project has no modules to publish."));
+    }
+
+    let tx = Transaction::new(sender, modules)?;
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(output, tx.encode(format)?)?;
+    println!(
+        "Transaction with {} modules is written to {:?}.",
+        tx.modules.len(),
+        output
+    );
+
+    if let Some(submitter) = submitter {
+        submitter.submit(&tx)?;
+    }
+    Ok(())
+}
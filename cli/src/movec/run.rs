@@ -0,0 +1,537 @@
+use std::convert::TryFrom;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Error};
+use tokio::runtime::Runtime;
+
+use compiler::dependence::loader::{make_loader, BytecodeLoader, Loader};
+use compiler::builder::Builder;
+use compiler::manifest::MoveToml;
+use compiler::RemoteCacheLoader;
+use data_source::file::BlockEntry;
+use data_source::{FileDataSource, MockDataSource};
+use dvm_net::api::grpc::vm_script_executor_client::VmScriptExecutorClient;
+use dvm_net::api::grpc::vm_module_publisher_server::VmModulePublisher;
+use dvm_net::api::grpc::vm_script_executor_server::VmScriptExecutor;
+use dvm_net::api::grpc::vm_balance_change::Op;
+use dvm_net::api::grpc::*;
+use dvm_net::api::tonic::Request;
+use dvm_net::endpoint::Endpoint;
+use lang::bytecode::metadata::{extract_bytecode_metadata, Metadata};
+use lang::bytecode::resource::ResourceViewer;
+use lang::stdlib::build_std;
+use libra::prelude::*;
+use runtime::vm::dvm::Dvm;
+use services::devnet::DevnetService;
+use services::vm::VmService;
+
+use crate::movec::{account_address, dependencies, parse_address};
+
+/// Script call.
+#[derive(Debug)]
+pub struct Call {
+    /// Script name.
+    pub script: String,
+    /// Script arguments.
+    pub args: Vec<String>,
+    /// Script senders. The project account is used if empty.
+    pub senders: Vec<String>,
+    /// Script type arguments in the `0x1::Module::Struct` form.
+    pub type_args: Vec<String>,
+    /// Max gas amount.
+    pub gas: u64,
+}
+
+/// Virtual machine which executes the script.
+#[derive(Debug)]
+pub enum Target {
+    /// In-process virtual machine over the local state snapshot.
+    Local {
+        /// Path to the state snapshot.
+        state: PathBuf,
+        /// Save the execution result to the snapshot.
+        save: bool,
+    },
+    /// Remote DVM.
+    Remote(Endpoint),
+}
+
+/// Executes run command.
+/// Returns `true` if the script was executed successfully.
+pub fn execute(
+    project_dir: &Path,
+    manifest: MoveToml,
+    call: Call,
+    target: Target,
+) -> Result<bool, Error> {
+    let senders = senders(&manifest, &call.senders)?;
+    let type_params = call
+        .type_args
+        .iter()
+        .flat_map(|args| args.split(','))
+        .map(str::trim)
+        .filter(|arg| !arg.is_empty())
+        .map(parse_type_arg)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut runtime = Runtime::new()?;
+    let response = match target {
+        Target::Local { state, save } => {
+            let ds = open_state(&state)?;
+            for (_, module) in dependencies(project_dir, &manifest)?.modules {
+                ds.publish_module(module)?;
+            }
+            let loader = Some(Loader::new(None, RemoteCacheLoader::new(ds.clone())));
+            let (modules, code) = build(project_dir, manifest, &loader, &call.script)?;
+            let devnet = if save {
+                let devnet = DevnetService::new(Dvm::new(ds.clone(), None));
+                publish_modules(&mut runtime, &devnet, modules, call.gas)?;
+                Some(devnet)
+            } else {
+                for module in modules {
+                    ds.publish_module(module)?;
+                }
+                None
+            };
+
+            let request = Request::new(make_request(
+                code,
+                &call,
+                senders,
+                type_params,
+                ds.block().next(),
+            )?);
+            let response = match devnet {
+                // Devnet service commits the result and saves the state.
+                Some(devnet) => runtime.block_on(devnet.execute_script(request))?,
+                None => {
+                    let service = VmService::new(Dvm::new(ds.clone(), None), None);
+                    runtime.block_on(service.execute_script(request))?
+                }
+            }
+            .into_inner();
+            print_response(&response, &ds);
+            response
+        }
+        Target::Remote(endpoint) => {
+            let dependencies = dependencies(project_dir, &manifest)?;
+            // Resources are decoded by the standard library and the declared dependencies.
+            let modules = MockDataSource::with_write_set(build_std());
+            for module in dependencies.modules.values() {
+                modules.publish_module(module.to_owned())?;
+            }
+            let loader = make_loader(project_dir, &manifest, &dependencies)?;
+            let (_, code) = build(project_dir, manifest, &loader, &call.script)?;
+            // Remote data source knows nothing about the local blocks.
+            let request = Request::new(make_request(
+                code,
+                &call,
+                senders,
+                type_params,
+                BlockEntry::default().next(),
+            )?);
+
+            let response = runtime
+                .block_on(async {
+                    let connection = endpoint
+                        .connect()
+                        .await
+                        .map_err(|err| anyhow!("Failed to connect to DVM: {}", err))?;
+                    VmScriptExecutorClient::new(connection)
+                        .execute_script(request)
+                        .await
+                        .map_err(Error::from)
+                })?
+                .into_inner();
+            print_response(&response, &modules);
+            response
+        }
+    };
+
+    Ok(response
+        .status
+        .map(|status| status.error.is_none())
+        .unwrap_or(false))
+}
+
+/// Opens the state snapshot.
+/// New state with the standard library is created if the file does not exist.
+fn open_state(path: &Path) -> Result<FileDataSource, Error> {
+    let is_new = !path.exists();
+    let ds = FileDataSource::open(path)?;
+    if is_new {
+        ds.merge_write_set(build_std());
+    }
+    Ok(ds)
+}
+
+/// Publishes the project modules through the devnet service which saves them to the state.
+/// Modules which are already published with the same bytecode are skipped.
+fn publish_modules(
+    runtime: &mut Runtime,
+    devnet: &DevnetService,
+    modules: Vec<Vec<u8>>,
+    gas: u64,
+) -> Result<(), Error> {
+    for module in modules {
+        let id = CompiledModule::deserialize(&module)
+            .map_err(|err| err.finish(Location::Undefined).into_vm_status())?
+            .self_id();
+        let published = RemoteCache::get_module(devnet.data_source(), &id)
+            .map_err(|err| err.into_vm_status())?;
+        if published.as_ref() == Some(&module) {
+            continue;
+        }
+
+        let request = Request::new(VmPublishModule {
+            sender: id.address().to_vec(),
+            max_gas_amount: gas,
+            gas_unit_price: 1,
+            code: module,
+        });
+        let response = runtime
+            .block_on(devnet.publish_module(request))?
+            .into_inner();
+        let is_published = response
+            .status
+            .as_ref()
+            .map(|status| status.error.is_none())
+            .unwrap_or(false);
+        if !is_published {
+            return Err(anyhow!(
+                "Failed to publish module {}::{}: {}",
+                format_address(&id.address().to_vec()),
+                id.name(),
+                format_status(response.status.as_ref())
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Builds the project.
+/// Returns bytecode of the project modules and the script with the given name.
+fn build<S: BytecodeLoader>(
+    project_dir: &Path,
+    manifest: MoveToml,
+    loader: &Option<Loader<S>>,
+    script: &str,
+) -> Result<(Vec<Vec<u8>>, Vec<u8>), Error> {
+    let builder = Builder::new(project_dir, manifest, loader, true, true);
+    builder.init_build_layout()?;
+
+    let source_map = builder.make_source_map()?;
+    let pre_processed_source_map = builder.preprocess_source_map(source_map)?;
+
+    let bytecode_map = builder.load_dependencies(&pre_processed_source_map)?;
+    let dep_list = builder.make_dependencies_as_source(bytecode_map)?;
+
+    let (text_source, units) = builder.compile(pre_processed_source_map, dep_list)?;
+    let (modules, scripts) = builder.verify_units(text_source, units)?;
+
+    let script = scripts
+        .into_iter()
+        .find(|unit| unit.name() == script)
+        .ok_or_else(|| anyhow!("Script '{}' not found.", script))?;
+    Ok((
+        modules.into_iter().map(|unit| unit.serialize()).collect(),
+        script.serialize(),
+    ))
+}
+
+/// Returns script senders.
+fn senders(manifest: &MoveToml, senders: &[String]) -> Result<Vec<AccountAddress>, Error> {
+    if senders.is_empty() {
+        Ok(vec![account_address(manifest)?.ok_or_else(|| {
+            anyhow!("Expected script sender. Pass --sender or set the project account_address.")
+        })?])
+    } else {
+        senders.iter().map(|sender| parse_address(sender)).collect()
+    }
+}
+
+/// Makes script execution request.
+fn make_request(
+    code: Vec<u8>,
+    call: &Call,
+    senders: Vec<AccountAddress>,
+    type_params: Vec<StructIdent>,
+    block: BlockEntry,
+) -> Result<VmExecuteScript, Error> {
+    let arguments = match extract_bytecode_metadata(&code)? {
+        Metadata::Script { arguments, .. } => arguments,
+        Metadata::Module { .. } => return Err(anyhow!("Expected script bytecode.")),
+    };
+
+    let arguments = arguments
+        .into_iter()
+        .filter(|arg| match arg {
+            SignatureToken::Signer => false,
+            SignatureToken::Reference(inner) => inner.as_ref() != &SignatureToken::Signer,
+            _ => true,
+        })
+        .collect::<Vec<_>>();
+    if arguments.len() != call.args.len() {
+        return Err(anyhow!(
+            "Script '{}' takes {} arguments but {} were supplied.",
+            call.script,
+            arguments.len(),
+            call.args.len()
+        ));
+    }
+
+    let args = arguments
+        .iter()
+        .zip(&call.args)
+        .map(|(arg_type, value)| parse_arg(arg_type, value))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(VmExecuteScript {
+        senders: senders.into_iter().map(|addr| addr.to_vec()).collect(),
+        max_gas_amount: call.gas,
+        gas_unit_price: 1,
+        block: block.height,
+        timestamp: block.timestamp,
+        code,
+        type_params,
+        args,
+    })
+}
+
+/// Parses script argument by its type.
+fn parse_arg(arg_type: &SignatureToken, value: &str) -> Result<VmArgs, Error> {
+    let invalid = |err: &dyn std::fmt::Display| {
+        anyhow!("Invalid {:?} argument '{}': {}", arg_type, value, err)
+    };
+
+    let (r#type, value) = match arg_type {
+        SignatureToken::Bool => (
+            VmTypeTag::Bool,
+            vec![value.parse::<bool>().map_err(|err| invalid(&err))? as u8],
+        ),
+        SignatureToken::U8 => (
+            VmTypeTag::U8,
+            vec![value.parse::<u8>().map_err(|err| invalid(&err))?],
+        ),
+        SignatureToken::U64 => (
+            VmTypeTag::U64,
+            value
+                .parse::<u64>()
+                .map_err(|err| invalid(&err))?
+                .to_le_bytes()
+                .to_vec(),
+        ),
+        SignatureToken::U128 => (
+            VmTypeTag::U128,
+            value
+                .parse::<u128>()
+                .map_err(|err| invalid(&err))?
+                .to_le_bytes()
+                .to_vec(),
+        ),
+        SignatureToken::Address => (
+            VmTypeTag::Address,
+            parse_address(value).map_err(|err| invalid(&err))?.to_vec(),
+        ),
+        SignatureToken::Vector(inner) if inner.as_ref() == &SignatureToken::U8 => (
+            VmTypeTag::Vector,
+            hex::decode(value.trim_start_matches("0x")).map_err(|err| invalid(&err))?,
+        ),
+        _ => return Err(anyhow!("Unsupported script argument type {:?}.", arg_type)),
+    };
+
+    Ok(VmArgs {
+        r#type: r#type as i32,
+        value,
+    })
+}
+
+/// Parses type argument in the `0x1::Module::Struct` form.
+fn parse_type_arg(arg: &str) -> Result<StructIdent, Error> {
+    let parts = arg.split("::").collect::<Vec<_>>();
+    if parts.len() != 3 || arg.contains('<') {
+        return Err(anyhow!(
+            "Invalid type argument '{}'. Expected ADDRESS::MODULE::STRUCT.",
+            arg
+        ));
+    }
+
+    Ok(StructIdent {
+        address: parse_address(parts[0])?.to_vec(),
+        module: parts[1].to_owned(),
+        name: parts[2].to_owned(),
+        type_params: vec![],
+    })
+}
+
+/// Prints execution result.
+/// Resources of the write set are decoded by the modules of the `modules` cache.
+fn print_response<C: RemoteCache>(response: &VmExecuteResponse, modules: &C) {
+    println!("status: {}", format_status(response.status.as_ref()));
+    println!("gas used: {}", response.gas_used);
+
+    if !response.events.is_empty() {
+        println!("events:");
+        for event in &response.events {
+            println!(
+                "    {} {}: 0x{}",
+                format_address(&event.sender_address),
+                event
+                    .event_type
+                    .as_ref()
+                    .map(format_tag)
+                    .unwrap_or_default(),
+                hex::encode(&event.event_data)
+            );
+        }
+    }
+
+    if !response.write_set.is_empty() {
+        println!("write set:");
+        let viewer = ResourceViewer::new(modules);
+        for value in &response.write_set {
+            let (address, path) = value
+                .path
+                .as_ref()
+                .map(|path| (format_address(&path.address), path.path.as_slice()))
+                .unwrap_or_default();
+            let is_deletion = value.r#type == VmWriteOp::Deletion as i32;
+
+            match CompiledModule::deserialize(&value.value) {
+                Ok(module) => println!("    module {}::{}", address, module.self_id().name()),
+                Err(_) if is_deletion => println!("    {}/{} deleted", address, hex::encode(path)),
+                Err(_) => match view_resource(&viewer, value) {
+                    Some(resource) => println!("    {}: {}", address, resource),
+                    None => println!(
+                        "    {}/{}: 0x{}",
+                        address,
+                        hex::encode(path),
+                        hex::encode(&value.value)
+                    ),
+                },
+            }
+        }
+    }
+
+    if !response.balance_change_set.is_empty() {
+        println!("balance changes:");
+        for change in &response.balance_change_set {
+            let (op, amount) = match &change.op {
+                Some(Op::Deposit(amount)) => ("deposit", u128::from(amount.to_owned())),
+                Some(Op::Withdraw(amount)) => ("withdraw", u128::from(amount.to_owned())),
+                None => continue,
+            };
+            println!(
+                "    {} {} {} {}",
+                format_address(&change.address),
+                op,
+                amount,
+                change.ticker
+            );
+        }
+    }
+}
+
+/// Decodes the resource of the write set value. Returns `None` if the value can't be decoded.
+fn view_resource<C: RemoteCache>(viewer: &ResourceViewer<C>, value: &VmValue) -> Option<String> {
+    let path = value.path.as_ref()?;
+    let address = AccountAddress::try_from(path.address.as_slice()).ok()?;
+    viewer
+        .view(&AccessPath::new(address, path.path.clone()), &value.value)
+        .ok()
+}
+
+/// Formats execution status.
+pub(crate) fn format_status(status: Option<&VmStatus>) -> String {
+    let status = match status {
+        Some(status) => status,
+        None => return "Unknown".to_owned(),
+    };
+
+    let location = |loc: &Option<AbortLocation>| {
+        loc.as_ref()
+            .map(|loc| format!("{}::{}", format_address(&loc.address), loc.module))
+            .unwrap_or_else(|| "script".to_owned())
+    };
+
+    let mut text = match &status.error {
+        None => "Executed".to_owned(),
+        Some(vm_status::Error::MoveError(err)) => format_status_code(err.status_code),
+        Some(vm_status::Error::Abort(abort)) => format!(
+            "Aborted with code {} in {}",
+            abort.abort_code,
+            location(&abort.abort_location)
+        ),
+        Some(vm_status::Error::ExecutionFailure(failure)) => format!(
+            "{} in {}{}",
+            format_status_code(failure.status_code),
+            location(&failure.abort_location),
+            failure
+                .function_loc
+                .as_ref()
+                .map(|loc| format!(" (function {}, offset {})", loc.function, loc.code_offset))
+                .unwrap_or_default()
+        ),
+    };
+    if let Some(message) = &status.message {
+        text = format!("{}: {}", text, message.text);
+    }
+    text
+}
+
+fn format_status_code(code: u64) -> String {
+    use std::convert::TryFrom;
+
+    StatusCode::try_from(code)
+        .map(|code| format!("{:?}", code))
+        .unwrap_or_else(|_| format!("Status code {}", code))
+}
+
+/// Formats address as a hex literal.
+pub(crate) fn format_address(address: &[u8]) -> String {
+    format!("0x{}", hex::encode(address))
+}
+
+fn format_tag(tag: &LcsTag) -> String {
+    match LcsType::from_i32(tag.type_tag) {
+        Some(LcsType::LcsBool) => "bool".to_owned(),
+        Some(LcsType::LcsU8) => "u8".to_owned(),
+        Some(LcsType::LcsU64) => "u64".to_owned(),
+        Some(LcsType::LcsU128) => "u128".to_owned(),
+        Some(LcsType::LcsAddress) => "address".to_owned(),
+        Some(LcsType::LcsSigner) => "signer".to_owned(),
+        Some(LcsType::LcsVector) => format!(
+            "vector<{}>",
+            tag.vector_type
+                .as_ref()
+                .map(|tag| format_tag(tag))
+                .unwrap_or_default()
+        ),
+        Some(LcsType::LcsStruct) => tag
+            .struct_ident
+            .as_ref()
+            .map(format_struct)
+            .unwrap_or_default(),
+        None => "unknown".to_owned(),
+    }
+}
+
+fn format_struct(ident: &StructIdent) -> String {
+    let name = format!(
+        "{}::{}::{}",
+        format_address(&ident.address),
+        ident.module,
+        ident.name
+    );
+    if ident.type_params.is_empty() {
+        name
+    } else {
+        let params = ident
+            .type_params
+            .iter()
+            .map(format_tag)
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("{}<{}>", name, params)
+    }
+}
@@ -0,0 +1,75 @@
+use std::path::Path;
+
+use anyhow::Error;
+
+use compiler::manifest::MoveToml;
+use compiler::workspace;
+use data_source::MockDataSource;
+use lang::stdlib::build_std;
+use libra::prelude::*;
+use test_kit::test_suite::perform_test_suite;
+
+use crate::movec::{dependencies, genesis, layout_dir, read_sources};
+
+/// Runs the project tests.
+///
+/// Every test file in the `tests_dir` of the project layout is performed by the test suite pipeline
+/// over the standard library with the project modules.
+/// Returns `false` if at least one test failed.
+pub fn execute(project_dir: &Path, manifest: MoveToml) -> Result<bool, Error> {
+    let tests_dir = layout_dir(project_dir, &manifest, |l| l.tests_dir.as_ref())?;
+    run_tests(&tests_dir, || genesis(project_dir, &manifest))
+}
+
+/// Runs tests of each workspace member.
+///
+/// Members are built in the dependency order. Tests are performed over the standard library
+/// with the declared dependencies and the modules of all members.
+/// Returns `false` if at least one test failed.
+pub fn execute_workspace(workspace_dir: &Path, manifest: MoveToml) -> Result<bool, Error> {
+    let members = workspace::build(workspace_dir, &manifest, false)?;
+    let ds = MockDataSource::with_write_set(build_std());
+    for built in &members {
+        for (_, module) in dependencies(&built.member.dir, &built.member.manifest)?.modules {
+            ds.publish_module(module)?;
+        }
+        for module in built.modules.values() {
+            ds.publish_module(module.to_owned())?;
+        }
+    }
+    let genesis = ds.to_write_set()?;
+
+    let mut success = true;
+    for built in &members {
+        println!("Testing {}", built.member.name);
+        let tests_dir = layout_dir(&built.member.dir, &built.member.manifest, |l| {
+            l.tests_dir.as_ref()
+        })?;
+        success &= run_tests(&tests_dir, || Ok(genesis.clone()))?;
+    }
+    Ok(success)
+}
+
+fn run_tests<G>(tests_dir: &Path, genesis: G) -> Result<bool, Error>
+where
+    G: FnOnce() -> Result<WriteSet, Error>,
+{
+    let tests = read_sources(tests_dir)?;
+    if tests.is_empty() {
+        println!("No tests found in {:?}.", tests_dir);
+        return Ok(true);
+    }
+
+    let report = perform_test_suite(genesis()?, tests);
+    println!();
+    println!(
+        "test result: {}. {} passed; {} failed.",
+        if report.is_success() { "ok" } else { "FAILED" },
+        report.passed.len(),
+        report.failed.len()
+    );
+    for (name, _) in &report.failed {
+        println!("    {}", name);
+    }
+    Ok(report.is_success())
+}
@@ -0,0 +1,511 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::{anyhow, Error};
+use serde::Serialize;
+use walkdir::WalkDir;
+
+use compiler::builder::Builder;
+use compiler::dependence::loader::{make_loader, Loader};
+use compiler::dependence::lock::MoveLock;
+use compiler::dependence::resolver::{resolve, Dependencies};
+use compiler::diagnostics::{diagnostics, Diagnostic, MessageFormat};
+use compiler::manifest::{read_manifest, MoveToml, MANIFEST};
+#[cfg(feature = "movec-test")]
+use data_source::MockDataSource;
+#[cfg(feature = "movec-test")]
+use lang::stdlib::build_std;
+use libra::prelude::*;
+#[cfg(feature = "movec-test")]
+use test_kit::test_suite::perform_test;
+
+use crate::movec::layout_dir;
+#[cfg(feature = "movec-test")]
+use crate::movec::read_sources;
+
+/// Command which is rerun on changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Compile the project without storing the artifacts.
+    Check,
+    /// Compile the project and store the artifacts.
+    Build,
+    /// Compile the project and run its tests.
+    #[cfg(feature = "movec-test")]
+    Test,
+}
+
+impl Action {
+    /// Action name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Check => "check",
+            Action::Build => "build",
+            #[cfg(feature = "movec-test")]
+            Action::Test => "test",
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "check" => Ok(Action::Check),
+            "build" => Ok(Action::Build),
+            #[cfg(feature = "movec-test")]
+            "test" => Ok(Action::Test),
+            _ => Err(anyhow!(
+                "Unknown action '{}'. Expected check, build or test.",
+                s
+            )),
+        }
+    }
+}
+
+/// Watcher event in the json stream.
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Event<'a> {
+    Started {
+        action: &'static str,
+        changed: &'a [String],
+    },
+    Finished {
+        action: &'static str,
+        success: bool,
+        duration_ms: u128,
+        diagnostics: &'a [Diagnostic],
+    },
+}
+
+/// File modification time and length.
+type Snapshot = BTreeMap<PathBuf, (SystemTime, u64)>;
+
+/// Project watcher.
+///
+/// Reruns the action when the sources, the tests, the manifest or the sources of the local packages change.
+/// Dependency modules are loaded once and kept in memory between runs.
+pub struct Watcher {
+    project_dir: PathBuf,
+    manifest: MoveToml,
+    action: Action,
+    format: MessageFormat,
+    /// Profile applied to the reloaded manifest.
+    profile: Option<String>,
+    /// Resolved declared dependencies. Reset when the manifest changes.
+    dependencies: Option<Dependencies>,
+    /// Loaded dependency modules.
+    modules: HashMap<ModuleId, Vec<u8>>,
+}
+
+impl Watcher {
+    /// Creates a new watcher of the project.
+    pub fn new(
+        project_dir: &Path,
+        manifest: MoveToml,
+        action: Action,
+        format: MessageFormat,
+        profile: Option<String>,
+    ) -> Watcher {
+        Watcher {
+            project_dir: project_dir.to_owned(),
+            manifest,
+            action,
+            format,
+            profile,
+            dependencies: None,
+            modules: HashMap::new(),
+        }
+    }
+
+    /// Runs the action and then reruns it on each change.
+    /// Changes are polled with the `interval` and the run starts when the files
+    /// have not changed for the `debounce` time.
+    pub fn watch(&mut self, interval: Duration, debounce: Duration) -> Result<(), Error> {
+        let mut snapshot = self.snapshot()?;
+        self.report(&[]);
+        loop {
+            thread::sleep(interval);
+            let mut current = self.snapshot()?;
+            if current == snapshot {
+                continue;
+            }
+
+            let mut stable_since = Instant::now();
+            while stable_since.elapsed() < debounce {
+                thread::sleep(interval);
+                let next = self.snapshot()?;
+                if next != current {
+                    current = next;
+                    stable_since = Instant::now();
+                }
+            }
+
+            let changed = changed_files(&snapshot, &current);
+            snapshot = current;
+            if changed
+                .iter()
+                .any(|path| path == &self.project_dir.join(MANIFEST))
+            {
+                self.dependencies = None;
+                self.modules.clear();
+            } else if self.packages_changed(&changed) {
+                // Local packages are rebuilt, their stale modules must not shadow the new ones.
+                if let Some(dependencies) = self.dependencies.take() {
+                    for id in &dependencies.unlocked {
+                        self.modules.remove(id);
+                    }
+                }
+            }
+            let changed = changed
+                .iter()
+                .map(|path| {
+                    path.strip_prefix(&self.project_dir)
+                        .unwrap_or(path)
+                        .to_string_lossy()
+                        .to_string()
+                })
+                .collect::<Vec<_>>();
+            self.report(&changed);
+        }
+    }
+
+    /// Runs the action once.
+    /// Returns diagnostics of the run, empty if the run succeeded.
+    pub fn run(&mut self) -> Vec<Diagnostic> {
+        match self.run_action() {
+            Ok(diagnostics) => diagnostics,
+            Err(err) => diagnostics(&err),
+        }
+    }
+
+    /// Runs the action once.
+    /// Compilation failures are returned as errors, test failures as diagnostics.
+    fn run_action(&mut self) -> Result<Vec<Diagnostic>, Error> {
+        match self.action {
+            Action::Check => self.compile(false).map(|_| vec![]),
+            Action::Build => self.compile(true).map(|_| vec![]),
+            #[cfg(feature = "movec-test")]
+            Action::Test => self.compile(false).and_then(|modules| self.test(modules)),
+        }
+    }
+
+    fn report(&mut self, changed: &[String]) {
+        let action = self.action.name();
+        match self.format {
+            MessageFormat::Human => {
+                for path in changed {
+                    println!("[{}] changed {}", action, path);
+                }
+            }
+            MessageFormat::Json => print_event(&Event::Started { action, changed }),
+        }
+
+        let started = Instant::now();
+        let result = self.run_action();
+        let duration = started.elapsed();
+        match self.format {
+            MessageFormat::Human => {
+                let success = match result {
+                    Ok(diagnostics) => {
+                        for diagnostic in &diagnostics {
+                            println!("{}", diagnostic.message.trim_end());
+                        }
+                        diagnostics.is_empty()
+                    }
+                    Err(err) => {
+                        println!("{}", err.to_string().trim_end());
+                        false
+                    }
+                };
+                println!(
+                    "[{}] {} in {:.2}s",
+                    action,
+                    if success { "ok" } else { "failed" },
+                    duration.as_secs_f32()
+                );
+            }
+            MessageFormat::Json => {
+                let diagnostics = result
+                    .unwrap_or_else(|err| diagnostics(&err))
+                    .into_iter()
+                    .map(|mut diagnostic| {
+                        diagnostic.message = strip_ansi(&diagnostic.message);
+                        diagnostic
+                    })
+                    .collect::<Vec<_>>();
+                print_event(&Event::Finished {
+                    action,
+                    success: diagnostics.is_empty(),
+                    duration_ms: duration.as_millis(),
+                    diagnostics: &diagnostics,
+                });
+            }
+        }
+    }
+
+    /// Compiles the project. Stores the compiled units if `store` is true.
+    /// Returns the compiled modules.
+    fn compile(&mut self, store: bool) -> Result<Vec<Vec<u8>>, Error> {
+        self.manifest = load_manifest(&self.project_dir, self.profile.as_deref())?;
+        let lock = MoveLock::read(&self.project_dir)?;
+        if self.dependencies.is_none() {
+            self.dependencies = Some(resolve(&self.project_dir, &self.manifest, &lock)?);
+        }
+        let dependencies = self
+            .dependencies
+            .as_ref()
+            .ok_or_else(|| anyhow!("Expected resolved dependencies."))?;
+
+        let modules = self.modules.clone();
+        let loader = match make_loader(&self.project_dir, &self.manifest, dependencies)? {
+            Some(loader) => Some(loader.with_modules(modules)),
+            None if !modules.is_empty() => Some(Loader::with_modules_only(modules)),
+            None => None,
+        };
+        let builder = Builder::new(
+            &self.project_dir,
+            self.manifest.clone(),
+            &loader,
+            false,
+            false,
+        );
+        builder.init_build_layout()?;
+
+        let sources = builder.preprocess_source_map(builder.make_source_map()?)?;
+        let bytecode_map = builder.load_dependencies(&sources)?;
+        dependencies.lock(&lock, &bytecode_map)?;
+        self.modules.extend(bytecode_map.clone());
+        let dep_list = builder.make_dependencies_as_source(bytecode_map)?;
+
+        let (text_source, units) = builder.compile(sources, dep_list)?;
+        let (modules, scripts) = builder.verify_units(text_source, units)?;
+        fn serialize(units: Vec<CompiledUnit>) -> Vec<(String, Vec<u8>)> {
+            units
+                .into_iter()
+                .map(|unit| (unit.name(), unit.serialize()))
+                .collect()
+        }
+        let modules = serialize(modules);
+        if store {
+            builder.store(modules.clone(), serialize(scripts))?;
+        }
+        Ok(modules.into_iter().map(|(_, module)| module).collect())
+    }
+
+    /// Runs the project tests.
+    /// The genesis is built from the standard library, the loaded dependency modules and the project `modules`.
+    #[cfg(feature = "movec-test")]
+    fn test(&mut self, modules: Vec<Vec<u8>>) -> Result<Vec<Diagnostic>, Error> {
+        let tests_dir = layout_dir(&self.project_dir, &self.manifest, |l| l.tests_dir.as_ref())?;
+        let ds = MockDataSource::with_write_set(build_std());
+        if let Some(dependencies) = &self.dependencies {
+            for module in dependencies.modules.values() {
+                ds.publish_module(module.to_owned())?;
+            }
+        }
+        for (id, module) in &self.modules {
+            if ds.get(&AccessPath::from(id)).is_none() {
+                ds.publish_module_with_id(id.to_owned(), module.to_owned())?;
+            }
+        }
+        for module in modules {
+            ds.publish_module(module)?;
+        }
+        let genesis = ds.to_write_set()?;
+        let tests = read_sources(&tests_dir)?
+            .into_iter()
+            .collect::<BTreeMap<_, _>>();
+
+        let mut diagnostics = vec![];
+        for (name, content) in tests {
+            if let Err(err) = perform_test(genesis.clone(), name.clone(), content) {
+                diagnostics.push(Diagnostic::error(format!("test {} failed: {}", name, err)));
+            }
+        }
+        Ok(diagnostics)
+    }
+
+    /// Returns true if the sources of the local packages changed.
+    fn packages_changed(&self, changed: &BTreeSet<PathBuf>) -> bool {
+        self.dependencies
+            .as_ref()
+            .map(|dependencies| {
+                changed.iter().any(|path| {
+                    dependencies
+                        .packages
+                        .iter()
+                        .any(|package| path.starts_with(package))
+                })
+            })
+            .unwrap_or(false)
+    }
+
+    /// Returns the state of the watched files.
+    fn snapshot(&self) -> Result<Snapshot, Error> {
+        let mut dirs = vec![
+            layout_dir(&self.project_dir, &self.manifest, |l| l.module_dir.as_ref())?,
+            layout_dir(&self.project_dir, &self.manifest, |l| l.script_dir.as_ref())?,
+        ];
+        #[cfg(feature = "movec-test")]
+        if self.action == Action::Test {
+            dirs.push(layout_dir(&self.project_dir, &self.manifest, |l| {
+                l.tests_dir.as_ref()
+            })?);
+        }
+
+        let mut manifests = vec![self.project_dir.join(MANIFEST)];
+        if let Some(dependencies) = &self.dependencies {
+            for package in &dependencies.packages {
+                manifests.push(package.join(MANIFEST));
+                if let Ok(manifest) = load_manifest(package, None) {
+                    dirs.push(layout_dir(package, &manifest, |l| l.module_dir.as_ref())?);
+                }
+            }
+        }
+
+        // Files removed during the walk are skipped.
+        let mut snapshot = Snapshot::new();
+        for manifest in manifests {
+            if let Some(state) = file_state(fs::metadata(&manifest)) {
+                snapshot.insert(manifest, state);
+            }
+        }
+        for dir in dirs {
+            for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+                let path = entry.path();
+                if path.extension().map(|ext| ext == "move").unwrap_or(false) {
+                    if let Some(state) = file_state(entry.metadata()) {
+                        snapshot.insert(path.to_owned(), state);
+                    }
+                }
+            }
+        }
+        Ok(snapshot)
+    }
+}
+
+/// Reads the project manifest with the filled layout and the applied profile.
+fn load_manifest(project_dir: &Path, profile: Option<&str>) -> Result<MoveToml, Error> {
+    let mut manifest = read_manifest(&project_dir.join(MANIFEST))?;
+    manifest.layout.get_or_insert_with(Default::default).fill();
+    if let Some(profile) = profile {
+        manifest.apply_profile(profile)?;
+    }
+    Ok(manifest)
+}
+
+/// Returns the modification time and the length of the file if it is available.
+fn file_state<E>(metadata: Result<fs::Metadata, E>) -> Option<(SystemTime, u64)> {
+    let metadata = metadata.ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Returns files which are added, changed or removed.
+fn changed_files(old: &Snapshot, new: &Snapshot) -> BTreeSet<PathBuf> {
+    old.iter()
+        .filter(|(path, state)| new.get(*path) != Some(state))
+        .map(|(path, _)| path.to_owned())
+        .chain(new.keys().filter(|path| !old.contains_key(*path)).cloned())
+        .collect()
+}
+
+fn print_event(event: &Event) {
+    match serde_json::to_string(event) {
+        Ok(line) => println!("{}", line),
+        Err(err) => eprintln!("Failed to serialize watcher event: {}", err),
+    }
+}
+
+/// Removes ANSI color codes from the message.
+fn strip_ansi(message: &str) -> String {
+    let mut result = String::with_capacity(message.len());
+    let mut chars = message.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\u{1b}' {
+            for ch in &mut chars {
+                if ch.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            result.push(ch);
+        }
+    }
+    result
+}
+
+/// Watches the project and reruns the action on changes.
+pub fn execute(
+    project_dir: &Path,
+    manifest: MoveToml,
+    action: Action,
+    format: MessageFormat,
+    profile: Option<String>,
+    interval: Duration,
+    debounce: Duration,
+) -> Result<(), Error> {
+    Watcher::new(project_dir, manifest, action, format, profile).watch(interval, debounce)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(secs: u64, len: u64) -> (SystemTime, u64) {
+        (SystemTime::UNIX_EPOCH + Duration::from_secs(secs), len)
+    }
+
+    #[test]
+    fn test_changed_files() {
+        let mut old = Snapshot::new();
+        old.insert(PathBuf::from("same.move"), state(1, 10));
+        old.insert(PathBuf::from("modified.move"), state(1, 10));
+        old.insert(PathBuf::from("resized.move"), state(1, 10));
+        old.insert(PathBuf::from("removed.move"), state(1, 10));
+
+        let mut new = old.clone();
+        new.insert(PathBuf::from("modified.move"), state(2, 10));
+        new.insert(PathBuf::from("resized.move"), state(1, 11));
+        new.remove(Path::new("removed.move"));
+        new.insert(PathBuf::from("added.move"), state(1, 10));
+
+        let changed = changed_files(&old, &new)
+            .into_iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changed,
+            vec![
+                "added.move",
+                "modified.move",
+                "removed.move",
+                "resized.move"
+            ]
+        );
+        assert!(changed_files(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_removed_file_state() {
+        assert!(file_state(fs::metadata("removed.move")).is_none());
+        let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+        assert!(file_state(fs::metadata(manifest)).is_some());
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("plain text"), "plain text");
+        assert_eq!(
+            strip_ansi("\u{1b}[1m\u{1b}[31merror\u{1b}[0m: unbound module"),
+            "error: unbound module"
+        );
+        assert_eq!(strip_ansi("\u{1b}[38;5;12m┌─\u{1b}[0m m.move"), "┌─ m.move");
+    }
+}
@@ -0,0 +1,50 @@
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::Path;
+
+use compiler::manifest::{Layout, MoveToml};
+use compiler::TempDir;
+
+/// Movec project in a temporary directory.
+pub struct Project {
+    dir: TempDir,
+    pub manifest: MoveToml,
+}
+
+impl Project {
+    /// Creates an empty project of the account.
+    pub fn new(address: &str) -> Project {
+        let mut manifest = MoveToml::default();
+        manifest.package.account_address = Some(address.to_owned());
+        let mut layout = Layout::default();
+        layout.fill();
+        manifest.layout = Some(layout);
+        Project {
+            dir: TempDir::new().unwrap(),
+            manifest,
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        self.dir.path()
+    }
+
+    pub fn module(&self, file: &str, code: &str) {
+        self.write("src/modules", file, code);
+    }
+
+    pub fn script(&self, file: &str, code: &str) {
+        self.write("src/scripts", file, code);
+    }
+
+    pub fn test(&self, file: &str, code: &str) {
+        self.write("tests", file, code);
+    }
+
+    fn write(&self, dir: &str, file: &str, code: &str) {
+        let path = self.dir().join(dir).join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, code).unwrap();
+    }
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use std::collections::BTreeMap;
+use std::fs;
+
+use compiler::manifest::Dependency;
+use compiler::Compiler;
+use data_source::MockDataSource;
+use dvm_cli::movec::genesis;
+#[cfg(feature = "movec-test")]
+use dvm_cli::movec::test;
+use lang::stdlib::build_std;
+use libra::prelude::*;
+
+use common::Project;
+
+fn module_id(address: &str, name: &str) -> ModuleId {
+    ModuleId::new(
+        AccountAddress::from_hex_literal(address).unwrap(),
+        Identifier::new(name).unwrap(),
+    )
+}
+
+#[test]
+fn test_genesis_with_project_modules() {
+    let project = Project::new("0x2");
+    project.module(
+        "store.move",
+        "
+        module Store {
+            use 0x3::Coins;
+
+            public fun value(): u64 { Coins::value() + 1 }
+        }
+        ",
+    );
+
+    // vendored dependency.
+    let compiler = Compiler::new(MockDataSource::with_write_set(build_std()));
+    let coins = compiler
+        .compile(
+            "module Coins { public fun value(): u64 { 41 } }",
+            Some(AccountAddress::from_hex_literal("0x3").unwrap()),
+        )
+        .unwrap();
+    fs::create_dir_all(project.dir().join("vendor")).unwrap();
+    fs::write(project.dir().join("vendor").join("coins.mv"), &coins).unwrap();
+    let mut manifest = project.manifest.clone();
+    let mut dependencies = BTreeMap::new();
+    dependencies.insert(
+        "Coins".to_owned(),
+        Dependency {
+            bytecode: Some("vendor".to_owned()),
+            ..Default::default()
+        },
+    );
+    manifest.dependencies = Some(dependencies);
+
+    let ds = MockDataSource::with_write_set(genesis(project.dir(), &manifest).unwrap());
+    assert!(ds
+        .get(&AccessPath::from(&module_id("0x2", "Store")))
+        .is_some());
+    assert_eq!(
+        ds.get(&AccessPath::from(&module_id("0x3", "Coins"))),
+        Some(coins)
+    );
+    assert!(ds
+        .get(&AccessPath::from(&module_id("0x1", "Account")))
+        .is_some());
+}
+
+#[test]
+fn test_colliding_source_names() {
+    let project = Project::new("0x2");
+    project.module("store/a.move", "module A {}");
+    project.module("store_a.move", "module B {}");
+    let err = genesis(project.dir(), &project.manifest).unwrap_err();
+    assert!(err.to_string().contains("store_a"));
+}
+
+#[cfg(feature = "movec-test")]
+#[test]
+fn test_project_tests() {
+    let project = Project::new("0x2");
+    project.module(
+        "store.move",
+        "module Store { public fun value(): u64 { 42 } }",
+    );
+    project.test(
+        "store.move",
+        "
+        script {
+            use 0x2::Store;
+
+            fun main() {
+                assert(Store::value() == 42, 1);
+            }
+        }
+        ",
+    );
+    assert!(test::execute(project.dir(), project.manifest.clone()).unwrap());
+
+    project.test(
+        "failed.move",
+        "
+        script {
+            use 0x2::Store;
+
+            fun main() {
+                assert(Store::value() == 0, 1);
+            }
+        }
+        ",
+    );
+    assert!(!test::execute(project.dir(), project.manifest.clone()).unwrap());
+}
@@ -0,0 +1,104 @@
+use std::str::FromStr;
+
+use compiler::Compiler;
+use data_source::MockDataSource;
+use dvm_cli::movec::publish::{Format, Transaction};
+use lang::stdlib::build_std;
+use libra::prelude::*;
+
+fn address(address: &str) -> AccountAddress {
+    AccountAddress::from_hex_literal(address).unwrap()
+}
+
+/// Compiles the module against the standard library and the `deps` modules.
+fn compile(code: &str, address: AccountAddress, deps: &[&[u8]]) -> Vec<u8> {
+    let ds = MockDataSource::with_write_set(build_std());
+    for dep in deps {
+        ds.publish_module(dep.to_vec()).unwrap();
+    }
+    Compiler::new(ds).compile(code, Some(address)).unwrap()
+}
+
+fn name(bytecode: &[u8]) -> String {
+    CompiledModule::deserialize(bytecode)
+        .unwrap()
+        .self_id()
+        .name()
+        .to_string()
+}
+
+#[test]
+fn test_dependency_order() {
+    let sender = address("0x2");
+    let a = compile("module A { public fun a(): u64 { 1 } }", sender, &[]);
+    let b = compile(
+        "module B { use 0x2::A; public fun b(): u64 { A::a() } }",
+        sender,
+        &[&a],
+    );
+    let c = compile(
+        "module C { use 0x2::B; public fun c(): u64 { B::b() } }",
+        sender,
+        &[&a, &b],
+    );
+    let d = compile("module D {}", sender, &[]);
+
+    let tx = Transaction::new(sender, vec![c.clone(), d.clone(), b.clone(), a.clone()]).unwrap();
+    assert_eq!(tx.sender, sender);
+    let names = tx.modules.iter().map(|m| name(m)).collect::<Vec<_>>();
+    assert_eq!(names, vec!["A", "B", "C", "D"]);
+}
+
+#[test]
+fn test_cyclic_dependency() {
+    let sender = address("0x2");
+    // each module is compiled against the independent version of the other one.
+    let a = compile(
+        "module A { use 0x2::B; public fun a(): u64 { B::b() } }",
+        sender,
+        &[&compile(
+            "module B { public fun b(): u64 { 1 } }",
+            sender,
+            &[],
+        )],
+    );
+    let b = compile(
+        "module B { use 0x2::A; public fun b(): u64 { A::a() } }",
+        sender,
+        &[&compile(
+            "module A { public fun a(): u64 { 1 } }",
+            sender,
+            &[],
+        )],
+    );
+
+    let err = Transaction::new(sender, vec![a, b]).unwrap_err();
+    assert!(err.to_string().contains("Cyclic dependency"));
+}
+
+#[test]
+fn test_foreign_module() {
+    let module = compile("module A {}", address("0x3"), &[]);
+    assert!(Transaction::new(address("0x2"), vec![module]).is_err());
+}
+
+#[test]
+fn test_encode() {
+    let sender = address("0x2");
+    let a = compile("module A {}", sender, &[]);
+    let tx = Transaction::new(sender, vec![a.clone()]).unwrap();
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&tx.encode(Format::Json).unwrap()).unwrap();
+    assert_eq!(json["sender"], format!("0x{}", hex::encode(sender)));
+    assert_eq!(json["modules"][0]["name"], "A");
+    assert_eq!(json["modules"][0]["code"], hex::encode(&a));
+
+    let lcs_tx: (AccountAddress, Vec<Vec<u8>>) =
+        lcs::from_bytes(&tx.encode(Format::Lcs).unwrap()).unwrap();
+    assert_eq!(lcs_tx, (sender, vec![a]));
+
+    assert_eq!(Format::from_str("JSON").unwrap(), Format::Json);
+    assert_eq!(Format::from_str("lcs").unwrap(), Format::Lcs);
+    assert!(Format::from_str("toml").is_err());
+}
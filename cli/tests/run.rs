@@ -0,0 +1,146 @@
+mod common;
+
+use data_source::FileDataSource;
+use dvm_cli::movec::run::{execute, Call, Target};
+use libra::prelude::*;
+
+use common::Project;
+
+fn project() -> Project {
+    let project = Project::new("0x2");
+    project.module(
+        "store.move",
+        "
+        module Store {
+            resource struct Value { value: u64 }
+
+            public fun store(account: &signer, value: u64) {
+                move_to(account, Value { value })
+            }
+        }
+        ",
+    );
+    project.script(
+        "store.move",
+        "
+        script {
+            use 0x2::Store;
+
+            fun store_value(account: &signer, value: u64) {
+                Store::store(account, value)
+            }
+        }
+        ",
+    );
+    project
+}
+
+fn call(script: &str, args: &[&str], type_args: &[&str]) -> Call {
+    Call {
+        script: script.to_owned(),
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+        senders: vec![],
+        type_args: type_args.iter().map(|arg| arg.to_string()).collect(),
+        gas: 1_000_000,
+    }
+}
+
+fn address() -> AccountAddress {
+    AccountAddress::from_hex_literal("0x2").unwrap()
+}
+
+fn store_module() -> AccessPath {
+    AccessPath::from(&ModuleId::new(address(), Identifier::new("Store").unwrap()))
+}
+
+fn value_resource() -> AccessPath {
+    let tag = StructTag {
+        address: address(),
+        module: Identifier::new("Store").unwrap(),
+        name: Identifier::new("Value").unwrap(),
+        type_params: vec![],
+    };
+    AccessPath::new(address(), tag.access_vector())
+}
+
+#[test]
+fn test_run_local() {
+    let project = project();
+    let state = project.dir().join("state.json");
+    let local = |save| Target::Local {
+        state: state.clone(),
+        save,
+    };
+
+    let executed = execute(
+        project.dir(),
+        project.manifest.clone(),
+        call("store_value", &["42"], &[]),
+        local(false),
+    )
+    .unwrap();
+    assert!(executed);
+    let ds = FileDataSource::open(&state).unwrap();
+    assert_eq!(ds.get(&store_module()), None);
+    assert_eq!(ds.get(&value_resource()), None);
+
+    let executed = execute(
+        project.dir(),
+        project.manifest.clone(),
+        call("store_value", &["42"], &[]),
+        local(true),
+    )
+    .unwrap();
+    assert!(executed);
+    let ds = FileDataSource::open(&state).unwrap();
+    assert!(ds.get(&store_module()).is_some());
+    assert_eq!(
+        ds.get(&value_resource()),
+        Some(lcs::to_bytes(&42u64).unwrap())
+    );
+
+    // the saved module is not published again, the resource already exists.
+    let executed = execute(
+        project.dir(),
+        project.manifest.clone(),
+        call("store_value", &["43"], &[]),
+        local(true),
+    )
+    .unwrap();
+    assert!(!executed);
+    let ds = FileDataSource::open(&state).unwrap();
+    assert_eq!(
+        ds.get(&value_resource()),
+        Some(lcs::to_bytes(&42u64).unwrap())
+    );
+}
+
+#[test]
+fn test_invalid_call() {
+    let project = project();
+    let state = project.dir().join("state.json");
+    let run = |call| {
+        execute(
+            project.dir(),
+            project.manifest.clone(),
+            call,
+            Target::Local {
+                state: state.clone(),
+                save: false,
+            },
+        )
+    };
+
+    assert!(run(call("store_value", &[], &[])).is_err());
+    assert!(run(call("store_value", &["1", "2"], &[])).is_err());
+    assert!(run(call("store_value", &["-1"], &[])).is_err());
+    assert!(run(call("store_value", &["value"], &[])).is_err());
+    assert!(run(call("unknown", &["1"], &[])).is_err());
+    assert!(run(call("store_value", &["1"], &["0x1::XFI"])).is_err());
+    assert!(run(call(
+        "store_value",
+        &["1"],
+        &["0x1::Coins::Price<0x1::XFI::T>"]
+    ))
+    .is_err());
+}
@@ -2,20 +2,61 @@ use anyhow::Result;
 use std::path::Path;
 use crate::manifest::MoveToml;
 use crate::mv::builder::Builder;
-use crate::mv::dependence::loader::make_rest_loader;
+use crate::mv::diagnostics::MessageFormat;
+use crate::mv::dependence::loader::make_loader;
+use crate::mv::dependence::lock::MoveLock;
+use crate::mv::dependence::resolver::resolve;
+use crate::mv::incremental;
+use crate::mv::workspace;
 
 /// Execute build command.
-pub fn execute(project_dir: &Path, manifest: MoveToml) -> Result<()> {
-    let loader = make_rest_loader(&project_dir, &manifest)?;
-    let builder = Builder::new(project_dir, manifest, &loader, true, true);
+/// Compilation errors are printed in the human format or returned with the diagnostics in the json format.
+pub fn execute(project_dir: &Path, manifest: MoveToml, format: MessageFormat) -> Result<()> {
+    build(project_dir, manifest, false, format)
+}
+
+/// Execute build command in the incremental mode.
+/// Only files whose inputs changed are recompiled, other units are taken from the build cache.
+pub fn execute_incremental(
+    project_dir: &Path,
+    manifest: MoveToml,
+    format: MessageFormat,
+) -> Result<()> {
+    build(project_dir, manifest, true, format)
+}
+
+fn build(
+    project_dir: &Path,
+    manifest: MoveToml,
+    incremental: bool,
+    format: MessageFormat,
+) -> Result<()> {
+    let lock = MoveLock::read(project_dir)?;
+    let dependencies = resolve(project_dir, &manifest, &lock)?;
+    let loader = make_loader(project_dir, &manifest, &dependencies)?;
+    let print_err = format == MessageFormat::Human;
+    let builder = Builder::new(project_dir, manifest, &loader, print_err, print_err);
     builder.init_build_layout()?;
 
     let source_map = builder.make_source_map()?;
     let pre_processed_source_map = builder.preprocess_source_map(source_map)?;
 
     let bytecode_map = builder.load_dependencies(&pre_processed_source_map)?;
+    let new_lock = dependencies.lock(&lock, &bytecode_map)?;
+    if new_lock != lock {
+        new_lock.store(project_dir)?;
+    }
+    if incremental {
+        let compiled = incremental::compile(&builder, pre_processed_source_map, bytecode_map)?;
+        return builder.store(compiled.modules, compiled.scripts);
+    }
     let dep_list = builder.make_dependencies_as_source(bytecode_map)?;
 
     let (text_source, units) = builder.compile(pre_processed_source_map, dep_list)?;
     builder.verify_and_store(text_source, units)
 }
+
+/// Execute build command for each member of the workspace.
+pub fn execute_workspace(workspace_dir: &Path, manifest: MoveToml) -> Result<()> {
+    workspace::build(workspace_dir, &manifest, true).map(|_| ())
+}
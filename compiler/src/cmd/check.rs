@@ -2,19 +2,34 @@ use anyhow::Result;
 use std::path::Path;
 use crate::manifest::MoveToml;
 use crate::mv::builder::Builder;
-use crate::mv::dependence::loader::make_rest_loader;
+use crate::mv::diagnostics::MessageFormat;
+use crate::mv::dependence::loader::make_loader;
+use crate::mv::dependence::lock::MoveLock;
+use crate::mv::dependence::resolver::resolve;
+use crate::mv::workspace;
 
 /// Execute check command.
-pub fn execute(project_dir: &Path, manifest: MoveToml) -> Result<()> {
-    let loader = make_rest_loader(project_dir, &manifest)?;
-    let builder = Builder::new(project_dir, manifest, &loader, true, true);
+/// Compilation errors are printed in the human format or returned with the diagnostics in the json format.
+/// The lock file is verified against the resolved dependencies, but never written.
+pub fn execute(project_dir: &Path, manifest: MoveToml, format: MessageFormat) -> Result<()> {
+    let lock = MoveLock::read(project_dir)?;
+    let dependencies = resolve(project_dir, &manifest, &lock)?;
+    let loader = make_loader(project_dir, &manifest, &dependencies)?;
+    let print_err = format == MessageFormat::Human;
+    let builder = Builder::new(project_dir, manifest, &loader, print_err, print_err);
     builder.init_build_layout()?;
 
     let source_map = builder.make_source_map()?;
     let pre_processed_source_map = builder.preprocess_source_map(source_map)?;
 
     let bytecode_map = builder.load_dependencies(&pre_processed_source_map)?;
+    lock.verify(project_dir, &dependencies.lock(&lock, &bytecode_map)?)?;
     let dep_list = builder.make_dependencies_as_source(bytecode_map)?;
 
     builder.check(pre_processed_source_map, dep_list)
 }
+
+/// Execute check command for each member of the workspace.
+pub fn execute_workspace(workspace_dir: &Path, manifest: MoveToml) -> Result<()> {
+    workspace::build(workspace_dir, &manifest, false).map(|_| ())
+}
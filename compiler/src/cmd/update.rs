@@ -1,9 +1,14 @@
 use anyhow::Error;
 use std::path::Path;
 use crate::manifest::MoveToml;
+use crate::mv::builder::Builder;
+use crate::mv::dependence::loader::make_loader;
+use crate::mv::dependence::lock::MoveLock;
+use crate::mv::dependence::resolver::resolve;
 use std::fs;
 
 /// Execute update dependencies command.
+/// Reloads dependencies and rewrites the lock file.
 pub fn execute(project_dir: &Path, manifest: MoveToml) -> Result<(), Error> {
     let cache_path = manifest
         .layout
@@ -15,5 +20,14 @@ pub fn execute(project_dir: &Path, manifest: MoveToml) -> Result<(), Error> {
         fs::remove_dir_all(&cache_path)?;
         fs::create_dir_all(&cache_path)?;
     }
-    Ok(())
+
+    let lock = MoveLock::default();
+    let dependencies = resolve(project_dir, &manifest, &lock)?;
+    let loader = make_loader(project_dir, &manifest, &dependencies)?;
+    let builder = Builder::new(project_dir, manifest, &loader, true, true);
+    builder.init_build_layout()?;
+
+    let source_map = builder.preprocess_source_map(builder.make_source_map()?)?;
+    let bytecode_map = builder.load_dependencies(&source_map)?;
+    dependencies.lock(&lock, &bytecode_map)?.store(project_dir)
 }
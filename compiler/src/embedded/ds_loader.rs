@@ -2,6 +2,7 @@ use crate::mv::dependence::loader::BytecodeLoader;
 use anyhow::Result;
 use libra::prelude::*;
 
+/// Bytecode loader which loads modules from the `RemoteCache`.
 #[derive(Clone)]
 pub struct RemoteCacheLoader<C: RemoteCache + Clone> {
     cache: C,
@@ -11,6 +12,7 @@ impl<C> RemoteCacheLoader<C>
 where
     C: RemoteCache + Clone,
 {
+    /// Creates a new loader over the `view`.
     pub fn new(view: C) -> RemoteCacheLoader<C> {
         RemoteCacheLoader { cache: view }
     }
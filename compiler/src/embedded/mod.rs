@@ -3,7 +3,7 @@ pub mod ds_loader;
 pub use libra::prelude::*;
 use crate::mv::dependence::loader::Loader;
 use crate::embedded::ds_loader::RemoteCacheLoader;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use anyhow::Result;
 use std::{env, fs};
 use std::path::{PathBuf, Path};
@@ -35,6 +35,16 @@ where
         &self,
         source_map: HashMap<String, String>,
         address: Option<AccountAddress>,
+    ) -> Result<HashMap<String, Vec<u8>>> {
+        self.compile_source_map_with_addresses(source_map, address, BTreeMap::new())
+    }
+
+    /// Compile multiple sources with the named addresses referenced as `@Name`.
+    pub fn compile_source_map_with_addresses(
+        &self,
+        source_map: HashMap<String, String>,
+        address: Option<AccountAddress>,
+        addresses: BTreeMap<String, String>,
     ) -> Result<HashMap<String, Vec<u8>>> {
         let dir = TempDir::new()?;
         let mut cmove = MoveToml::default();
@@ -51,19 +61,23 @@ where
             fs::create_dir_all(&module_dir)?;
         }
 
+        let mut names = HashMap::with_capacity(source_map.len());
         for (name, source) in source_map {
-            let mut source_path = module_dir.join(name);
+            let mut source_path = module_dir.join(&name);
             source_path.set_extension("move");
             let mut f = OpenOptions::new()
                 .create(true)
                 .write(true)
-                .open(&module_dir.join(source_path))?;
+                .open(&module_dir.join(&source_path))?;
             f.write_all(source.as_bytes())?;
+            names.insert(source_path.to_string_lossy().to_string(), name);
         }
         cmove.package.account_address = address.map(|addr| format!("0x{}", addr));
+        cmove.addresses = Some(addresses);
         cmove.layout = Some(layout);
 
-        let builder = Builder::new(dir.path(), cmove, &self.loader, false, false);
+        let builder =
+            Builder::new(dir.path(), cmove, &self.loader, false, false).with_source_names(names);
         builder.init_build_layout()?;
         let source_map = builder.preprocess_source_map(builder.make_source_map()?)?;
         let dep_list =
@@ -17,7 +17,8 @@ pub mod manifest;
 mod mv;
 
 pub use mv::*;
-pub use embedded::Compiler;
+pub use embedded::{Compiler, TempDir};
+pub use embedded::ds_loader::RemoteCacheLoader;
 pub use embedded::compile;
 #[cfg(test)]
 pub use disassembler;
@@ -1,5 +1,6 @@
 use serde_derive::{Serialize, Deserialize};
 use anyhow::Error;
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::fs;
 use std::fs::OpenOptions;
@@ -13,9 +14,62 @@ pub const MANIFEST: &str = "Move.toml";
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct MoveToml {
     /// Project info.
+    #[serde(default)]
     pub package: Package,
     /// Project layout.
     pub layout: Option<Layout>,
+    /// Declared dependencies by name.
+    pub dependencies: Option<BTreeMap<String, Dependency>>,
+    /// Workspace with member packages.
+    pub workspace: Option<Workspace>,
+    /// Named addresses referenced in the sources as `@Name`.
+    pub addresses: Option<BTreeMap<String, String>>,
+    /// Named compile-time constants referenced in the sources as `@Name`.
+    pub constants: Option<BTreeMap<String, String>>,
+    /// Profiles with the named addresses and constants, e.g. `testnet` or `mainnet`.
+    pub profile: Option<BTreeMap<String, Profile>>,
+}
+
+impl MoveToml {
+    /// Applies the profile.
+    /// Named addresses and constants of the profile override the manifest ones.
+    pub fn apply_profile(&mut self, name: &str) -> Result<(), Error> {
+        let profile = self
+            .profile
+            .as_ref()
+            .and_then(|profiles| profiles.get(name))
+            .cloned()
+            .ok_or_else(|| anyhow!("Profile '{}' is not declared in {}.", name, MANIFEST))?;
+        if let Some(addresses) = profile.addresses {
+            self.addresses
+                .get_or_insert_with(Default::default)
+                .extend(addresses);
+        }
+        if let Some(constants) = profile.constants {
+            self.constants
+                .get_or_insert_with(Default::default)
+                .extend(constants);
+        }
+        Ok(())
+    }
+}
+
+/// Profile of the named addresses and constants.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct Profile {
+    /// Named addresses of the profile.
+    pub addresses: Option<BTreeMap<String, String>>,
+    /// Named constants of the profile.
+    pub constants: Option<BTreeMap<String, String>>,
+}
+
+/// Workspace info.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct Workspace {
+    /// Paths to the member packages relative to the workspace directory.
+    pub members: Vec<String>,
+    /// Target directory shared by the members. Default is `target`.
+    pub target_dir: Option<String>,
 }
 
 /// Project info.
@@ -31,6 +85,29 @@ pub struct Package {
     pub blockchain_api: Option<String>,
 }
 
+/// Declared dependency.
+///
+/// Exactly one source must be specified:
+/// * `path` - local source package (directory with `Move.toml`);
+/// * `git` with `rev` - source package from the git repository revision;
+/// * `bytecode` - vendored bytecode: `.mv` file or directory with `.mv` files;
+/// * `module` - chain module (`0x1::Module`), optionally pinned by `hash`.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dependency {
+    /// Path to the local source package.
+    pub path: Option<String>,
+    /// Git repository url of the source package.
+    pub git: Option<String>,
+    /// Git revision: commit, tag or branch.
+    pub rev: Option<String>,
+    /// Path to the vendored bytecode.
+    pub bytecode: Option<String>,
+    /// Chain module id.
+    pub module: Option<String>,
+    /// Expected sha3-256 hash of the chain module bytecode.
+    pub hash: Option<String>,
+}
+
 /// Project layout.
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct Layout {
@@ -46,6 +123,10 @@ pub struct Layout {
     pub script_output: Option<String>,
     /// Processing directory.
     pub temp_dir: Option<String>,
+    /// Directory with test scripts.
+    pub tests_dir: Option<String>,
+    /// Incremental compilation cache.
+    pub build_cache: Option<String>,
 }
 
 impl Layout {
@@ -58,6 +139,8 @@ impl Layout {
             module_output: None,
             script_output: None,
             temp_dir: None,
+            tests_dir: None,
+            build_cache: None,
         }
     }
 
@@ -75,6 +158,9 @@ impl Layout {
             .get_or_insert_with(|| "target/artifacts/scripts".to_owned());
         self.temp_dir
             .get_or_insert_with(|| "target/build".to_owned());
+        self.tests_dir.get_or_insert_with(|| "tests".to_owned());
+        self.build_cache
+            .get_or_insert_with(|| "target/cache".to_owned());
     }
 }
 
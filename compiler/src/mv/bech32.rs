@@ -5,6 +5,10 @@ use bech32::u5;
 use lazy_static::lazy_static;
 use regex::Regex;
 
+use std::ops::Range;
+
+use crate::mv::preprocessor::{replace, OffsetMap};
+
 /// Fixed prefix of all dfinance bech32 addresses.
 pub static HRP: &str = "wallet";
 
@@ -41,17 +45,28 @@ pub fn libra_into_bech32(libra_address: &str) -> Result<String> {
 
 /// Replace all occurrences of bech32 addresses in the `source` string.
 pub fn replace_bech32_addresses(source: &str) -> String {
-    let mut transformed_source = source.to_string();
-    for mat in BECH32_REGEX.captures_iter(source).into_iter() {
-        let address = mat.get(1).unwrap().as_str();
-        if address.starts_with("0x") {
+    replace_bech32_addresses_with_map(source).0
+}
+
+/// Replace all occurrences of bech32 addresses in the `source` string.
+/// Returns the new string with the map of its offsets to the `source` offsets.
+pub fn replace_bech32_addresses_with_map(source: &str) -> (String, OffsetMap) {
+    replace(source, find_bech32_addresses(source))
+}
+
+/// Finds bech32 addresses in the `source` string.
+/// Returns ranges of the addresses with their hex literals.
+pub fn find_bech32_addresses(source: &str) -> Vec<(Range<usize>, String)> {
+    let mut addresses = Vec::new();
+    for mat in BECH32_REGEX.captures_iter(source) {
+        let address = mat.get(1).unwrap();
+        if address.as_str().starts_with("0x") {
             // libra match, don't replace
             continue;
         }
-        if let Ok(libra_address) = bech32_into_libra(address) {
-            transformed_source =
-                transformed_source.replace(address, &format!("0x{}", libra_address));
+        if let Ok(libra_address) = bech32_into_libra(address.as_str()) {
+            addresses.push((address.range(), format!("0x{}", libra_address)));
         }
     }
-    transformed_source
+    addresses
 }
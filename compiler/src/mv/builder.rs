@@ -1,19 +1,21 @@
 use std::path::{Path, PathBuf};
-use crate::manifest::MoveToml;
+use crate::manifest::{MoveToml, MANIFEST};
 use std::fs;
 use walkdir::WalkDir;
 use std::fs::{File, OpenOptions};
 use crate::mv::bech32::bech32_into_libra;
 use std::io::Write;
-use crate::mv::{preprocessor};
+use crate::mv::preprocessor::{self, Names, UnknownName};
 use anyhow::{Result, Error};
 use libra::{prelude::*, compiler::*};
-use crate::mv::dependence::extractor::{extract_from_source, extract_from_bytecode};
+use crate::mv::dependence::extractor::{extract_from_bytecode, parse_definitions};
 use crate::mv::dependence::loader::{BytecodeLoader, Loader};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use termcolor::{StandardStream, ColorChoice, Buffer};
 use crate::mv::disassembler::unit::{CompiledUnit as Unit};
 use crate::mv::disassembler::{Config, Disassembler};
+use crate::mv::diagnostics::{from_errors, to_original, CompilationError, SourceOrigin};
+use std::cell::RefCell;
 
 /// Move builder.
 pub struct Builder<'a, S: BytecodeLoader> {
@@ -27,6 +29,11 @@ pub struct Builder<'a, S: BytecodeLoader> {
     print_err: bool,
     /// Shutdown on error flag. If true, the process will exit with error code on compilation error.
     shutdown_on_err: bool,
+    /// Original sources of the preprocessed files.
+    /// Compiler errors are reported in the original sources.
+    origins: RefCell<HashMap<String, SourceOrigin>>,
+    /// Names of the source files reported instead of their paths.
+    source_names: HashMap<String, String>,
     /// Static name pool.
     _name_pool: ConstPool,
 }
@@ -49,10 +56,18 @@ where
             loader,
             print_err,
             shutdown_on_err,
+            origins: Default::default(),
+            source_names: Default::default(),
             _name_pool: Default::default(),
         }
     }
 
+    /// Sets names of the source files which are reported instead of their paths.
+    pub fn with_source_names(mut self, source_names: HashMap<String, String>) -> Self {
+        self.source_names = source_names;
+        self
+    }
+
     /// Initializes directory layout.
     pub fn init_build_layout(&self) -> Result<()> {
         let temp_dir = self.temp_dir()?;
@@ -89,8 +104,7 @@ where
         let address = self
             .address()?
             .map(|addr| AccountAddress::new(addr.to_u8()));
-        let source_imports =
-            extract_from_source(sources, address, self.print_err, self.shutdown_on_err)?;
+        let (_, source_imports) = self.parse_definitions(sources, address)?;
         let mut deps = HashMap::new();
 
         let mut dep_list = HashSet::new();
@@ -105,6 +119,39 @@ where
         Ok(deps)
     }
 
+    /// Returns modules defined in the source files and their imports of other modules.
+    pub fn definitions(
+        &self,
+        sources: &[PathBuf],
+    ) -> Result<(HashSet<ModuleId>, HashSet<ModuleId>)> {
+        let address = self
+            .address()?
+            .map(|addr| AccountAddress::new(addr.to_u8()));
+        self.parse_definitions(sources, address)
+    }
+
+    /// Parses source files and extracts module definitions and their imports.
+    fn parse_definitions(
+        &self,
+        sources: &[PathBuf],
+        address: Option<AccountAddress>,
+    ) -> Result<(HashSet<ModuleId>, HashSet<ModuleId>)> {
+        match parse_definitions(sources, address)? {
+            Ok(definitions) => Ok(definitions),
+            Err((files, errors)) => {
+                if self.print_err {
+                    self.report_errors(files, errors);
+                    if self.shutdown_on_err {
+                        std::process::exit(1);
+                    }
+                    Ok((HashSet::new(), HashSet::new()))
+                } else {
+                    Err(self.compilation_error(files, errors)?.into())
+                }
+            }
+        }
+    }
+
     /// Load dependencies tree.
     fn load_bytecode_tree(
         &self,
@@ -191,6 +238,9 @@ where
 
         let temp_modules = temp_src.join("modules");
         let temp_scripts = temp_src.join("scripts");
+        let names = self.names()?;
+        let mut unknown_files = FilesSourceText::new();
+        let mut unknown_errors = Errors::new();
         let mut sources = Vec::with_capacity(source_map.len());
         for src in source_map {
             let new_path = if src.starts_with(&module_source) {
@@ -219,7 +269,27 @@ where
                 )
             };
 
-            let source = preprocessor::pre_processing(&fs::read_to_string(&src)?);
+            let text = fs::read_to_string(&src)?;
+            let (source, offsets, unknown) = preprocessor::preprocess_with_names(&text, &names);
+            let path = src.to_string_lossy().to_string();
+            let path = self.source_names.get(&path).cloned().unwrap_or(path);
+            if !unknown.is_empty() {
+                let file = ConstPool::push(&path);
+                unknown_errors.extend(
+                    unknown
+                        .into_iter()
+                        .map(|name| unknown_name_error(file, name)),
+                );
+                unknown_files.insert(file, text.clone());
+            }
+            self.origins.borrow_mut().insert(
+                new_path.to_string_lossy().to_string(),
+                SourceOrigin {
+                    path,
+                    text,
+                    offsets,
+                },
+            );
             let mut f = OpenOptions::new()
                 .create(true)
                 .write(true)
@@ -227,7 +297,12 @@ where
             f.write_all(source.as_bytes())?;
             sources.push(new_path);
         }
-        Ok(sources)
+
+        if unknown_errors.is_empty() {
+            Ok(sources)
+        } else {
+            self.fail(unknown_files, unknown_errors)
+        }
     }
 
     /// Compile source list with dependencies.
@@ -243,31 +318,40 @@ where
         let (files, pprog_and_comments_res) = parse_program(&source_list, &dep_list)?;
         let pprog_res = pprog_and_comments_res.map(|(pprog, _)| pprog);
         match compile_program(pprog_res, addr) {
-            Err(errors) => {
-                if self.print_err {
-                    let mut writer = StandardStream::stderr(ColorChoice::Auto);
-                    output_errors(&mut writer, files, errors);
-                    if self.shutdown_on_err {
-                        std::process::exit(1)
-                    } else {
-                        Err(Error::msg("Unexpected errors."))
-                    }
-                } else {
-                    let mut writer = Buffer::ansi();
-                    output_errors(&mut writer, files, errors);
-                    Err(Error::msg(String::from_utf8(writer.into_inner())?))
-                }
-            }
+            Err(errors) => self.fail(files, errors),
             Ok(compiled_units) => Ok((files, compiled_units)),
         }
     }
 
+    /// Reports compilation errors.
+    /// Prints them and shuts down the process according to the flags
+    /// or returns them as the compilation error.
+    fn fail<T>(&self, files: FilesSourceText, errors: Errors) -> Result<T> {
+        if self.print_err {
+            self.report_errors(files, errors);
+            if self.shutdown_on_err {
+                std::process::exit(1)
+            } else {
+                Err(Error::msg("Unexpected errors."))
+            }
+        } else {
+            Err(self.compilation_error(files, errors)?.into())
+        }
+    }
+
     /// Check source files.
+    /// Errors are reported in the original sources the same way as the compilation errors.
     pub fn check(&self, source_list: Vec<PathBuf>, dep_list: Vec<PathBuf>) -> Result<()> {
         let source_list = convert_path(&source_list)?;
         let dep_list = convert_path(&dep_list)?;
         let addr = self.address()?;
-        move_check(&source_list, &dep_list, addr, None)
+
+        let (files, errors) = move_check_no_report(&source_list, &dep_list, addr, None)?;
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            self.fail(files, errors)
+        }
     }
 
     /// Verify and store compiled units.
@@ -281,11 +365,37 @@ where
             .into_iter()
             .partition(|u| matches!(u, CompiledUnit::Module { .. }));
 
-        fn store(units: Vec<CompiledUnit>, base_dir: &Path) -> Result<()> {
-            for (idx, unit) in units.into_iter().enumerate() {
-                let mut path = base_dir.join(format!("{}_{}", idx, unit.name()));
+        fn serialize(units: Vec<CompiledUnit>) -> Vec<(String, Vec<u8>)> {
+            units
+                .into_iter()
+                .map(|unit| (unit.name(), unit.serialize()))
+                .collect()
+        }
+        self.store(serialize(modules), serialize(scripts))?;
+
+        if !ice_errors.is_empty() {
+            if !self.print_err {
+                return Err(self.compilation_error(files, ice_errors)?.into());
+            }
+            self.report_errors(files, ice_errors);
+            if self.shutdown_on_err {
+                std::process::exit(1);
+            }
+        }
+        Ok(())
+    }
+
+    /// Stores serialized modules and scripts to the output directories.
+    pub fn store(
+        &self,
+        modules: Vec<(String, Vec<u8>)>,
+        scripts: Vec<(String, Vec<u8>)>,
+    ) -> Result<()> {
+        fn store(units: Vec<(String, Vec<u8>)>, base_dir: &Path) -> Result<()> {
+            for (idx, (name, bytecode)) in units.into_iter().enumerate() {
+                let mut path = base_dir.join(format!("{}_{}", idx, name));
                 path.set_extension("mv");
-                File::create(&path)?.write_all(&unit.serialize())?
+                File::create(&path)?.write_all(&bytecode)?
             }
             Ok(())
         }
@@ -308,16 +418,6 @@ where
 
             store(scripts, &scripts_dir)?;
         }
-
-        if !ice_errors.is_empty() {
-            if self.print_err {
-                let mut writer = StandardStream::stderr(ColorChoice::Auto);
-                output_errors(&mut writer, files, ice_errors);
-            }
-            if self.shutdown_on_err {
-                std::process::exit(1);
-            }
-        }
         Ok(())
     }
 
@@ -327,10 +427,7 @@ where
         files: FilesSourceText,
         compiled_units: Vec<CompiledUnit>,
     ) -> Result<HashMap<String, Vec<u8>>> {
-        let (compiled_units, ice_errors) = compiled_unit::verify_units(compiled_units);
-        let (modules, scripts): (Vec<_>, Vec<_>) = compiled_units
-            .into_iter()
-            .partition(|u| matches!(u, CompiledUnit::Module { .. }));
+        let (modules, scripts) = self.verify_units(files, compiled_units)?;
 
         let mut bytecode_map = HashMap::new();
 
@@ -342,17 +439,59 @@ where
             bytecode_map.insert(script.name(), script.serialize());
         }
 
+        Ok(bytecode_map)
+    }
+
+    /// Verifies compiled units.
+    /// Returns verified modules and scripts.
+    pub fn verify_units(
+        &self,
+        files: FilesSourceText,
+        compiled_units: Vec<CompiledUnit>,
+    ) -> Result<(Vec<CompiledUnit>, Vec<CompiledUnit>)> {
+        let (compiled_units, ice_errors) = compiled_unit::verify_units(compiled_units);
         if ice_errors.is_empty() {
-            Ok(bytecode_map)
+            Ok(compiled_units
+                .into_iter()
+                .partition(|u| matches!(u, CompiledUnit::Module { .. })))
         } else {
-            let mut writer = Buffer::ansi();
-            output_errors(&mut writer, files, ice_errors);
-            Err(Error::msg(String::from_utf8(writer.into_inner())?))
+            Err(self.compilation_error(files, ice_errors)?.into())
         }
     }
 
+    /// Makes the compilation error with the diagnostics and the rendered errors.
+    fn compilation_error(
+        &self,
+        files: FilesSourceText,
+        errors: Errors,
+    ) -> Result<CompilationError> {
+        let (files, errors) = to_original(files, errors, &self.origins.borrow());
+        let diagnostics = from_errors(&files, &errors);
+        let mut writer = Buffer::ansi();
+        output_errors(&mut writer, files, errors);
+        Ok(CompilationError {
+            diagnostics,
+            rendered: String::from_utf8(writer.into_inner())?,
+        })
+    }
+
+    /// Prints errors in the original sources to stderr.
+    fn report_errors(&self, files: FilesSourceText, errors: Errors) {
+        let (files, errors) = to_original(files, errors, &self.origins.borrow());
+        report_errors(files, errors);
+    }
+
+    /// Returns named addresses and constants from movec manifest.
+    fn names(&self) -> Result<Names> {
+        let empty = BTreeMap::new();
+        Names::new(
+            self.manifest.addresses.as_ref().unwrap_or(&empty),
+            self.manifest.constants.as_ref().unwrap_or(&empty),
+        )
+    }
+
     /// Returns the account address from movec manifest.
-    fn address(&self) -> Result<Option<Address>> {
+    pub(crate) fn address(&self) -> Result<Option<Address>> {
         let package = &self.manifest.package;
         match package.account_address.as_ref().map(|addr| {
             if addr.starts_with("0x") {
@@ -368,7 +507,7 @@ where
     }
 
     /// Temporary directory path.
-    fn temp_dir(&self) -> Result<PathBuf> {
+    pub(crate) fn temp_dir(&self) -> Result<PathBuf> {
         self.manifest
             .layout
             .as_ref()
@@ -377,6 +516,16 @@ where
             .ok_or_else(|| anyhow!("Expected temp_dir"))
     }
 
+    /// Incremental compilation cache path.
+    pub(crate) fn build_cache_dir(&self) -> Result<PathBuf> {
+        self.manifest
+            .layout
+            .as_ref()
+            .and_then(|l| l.build_cache.as_ref())
+            .map(|t| self.project_dir.join(t))
+            .ok_or_else(|| anyhow!("Expected build_cache"))
+    }
+
     /// Dependencies directory path.
     fn deps_dir(&self) -> Result<PathBuf> {
         self.manifest
@@ -434,6 +583,21 @@ pub fn report_errors(files: FilesSourceText, errors: Errors) {
     errors::output_errors(&mut writer, files, errors);
 }
 
+/// Makes the compiler error of the unknown name reference.
+fn unknown_name_error(file: &'static str, name: UnknownName) -> Vec<(Loc, String)> {
+    let span = Span::new(
+        ByteIndex(name.range.start as u32),
+        ByteIndex(name.range.end as u32),
+    );
+    vec![(
+        Loc::new(file, span),
+        format!(
+            "Unknown name '@{}'. Declare it in the [addresses] or [constants] table of {}.",
+            name.name, MANIFEST
+        ),
+    )]
+}
+
 /// Converts paths buffers into strings.
 pub fn convert_path(path_list: &[PathBuf]) -> Result<Vec<String>> {
     path_list
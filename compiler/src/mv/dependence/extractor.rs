@@ -15,15 +15,21 @@ pub fn extract_from_source(
     print_err: bool,
     shutdown_on_err: bool,
 ) -> Result<HashSet<ModuleId>> {
-    let mut extractor = DefinitionUses::with_address(address);
-    let (files, pprog_and_comments_res) = parse_program(&convert_path(targets)?, &[])?;
-    match pprog_and_comments_res {
-        Ok((program, _)) => {
-            for def in program.source_definitions {
-                extractor.extract(&def)?;
-            }
-        }
-        Err(errs) => {
+    extract_definitions_from_source(targets, address, print_err, shutdown_on_err)
+        .map(|(_, imports)| imports)
+}
+
+/// Extract module definitions and dependencies from source code.
+/// Returns modules defined in the sources and their imports of other modules.
+pub fn extract_definitions_from_source(
+    targets: &[PathBuf],
+    address: Option<AccountAddress>,
+    print_err: bool,
+    shutdown_on_err: bool,
+) -> Result<(HashSet<ModuleId>, HashSet<ModuleId>)> {
+    match parse_definitions(targets, address)? {
+        Ok(definitions) => Ok(definitions),
+        Err((files, errs)) => {
             if print_err {
                 let mut writer = StandardStream::stderr(ColorChoice::Auto);
                 errors::output_errors(&mut writer, files, errs);
@@ -31,10 +37,29 @@ pub fn extract_from_source(
             if shutdown_on_err {
                 exit(1);
             }
+            Ok((HashSet::new(), HashSet::new()))
         }
     }
+}
 
-    Ok(extractor.imports())
+/// Parses source code and extracts module definitions and their imports.
+/// Returns the parser errors with the parsed files if the source code is not valid.
+pub fn parse_definitions(
+    targets: &[PathBuf],
+    address: Option<AccountAddress>,
+) -> Result<Result<(HashSet<ModuleId>, HashSet<ModuleId>), (FilesSourceText, Errors)>> {
+    let mut extractor = DefinitionUses::with_address(address);
+    let (files, pprog_and_comments_res) = parse_program(&convert_path(targets)?, &[])?;
+    match pprog_and_comments_res {
+        Ok((program, _)) => {
+            for def in program.source_definitions {
+                extractor.extract(&def)?;
+            }
+            let modules = extractor.modules().clone();
+            Ok(Ok((modules, extractor.imports())))
+        }
+        Err(errs) => Ok(Err((files, errs))),
+    }
 }
 
 /// Extract dependencies from bytecode.
@@ -403,6 +428,11 @@ impl DefinitionUses {
         Ok(())
     }
 
+    /// Returns defined modules.
+    pub fn modules(&self) -> &HashSet<ModuleId> {
+        &self.modules
+    }
+
     /// Returns imports.
     pub fn imports(mut self) -> HashSet<ModuleId> {
         for module_id in self.modules {
@@ -10,6 +10,8 @@ use http::Uri;
 use crate::manifest::MoveToml;
 use std::fs;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::mv::dependence::resolver::Dependencies;
 
 /// Module loader.
 pub trait BytecodeLoader: Clone {
@@ -90,7 +92,8 @@ pub struct LoaderErrorResponse {
 #[derive(Clone)]
 pub struct Loader<S: BytecodeLoader> {
     cache_path: Option<PathBuf>,
-    source: S,
+    source: Option<S>,
+    modules: HashMap<ModuleId, Vec<u8>>,
 }
 
 impl<S> Loader<S>
@@ -99,13 +102,44 @@ where
 {
     /// Create a new module loader with cache path and external module source.
     pub fn new(cache_path: Option<PathBuf>, source: S) -> Loader<S> {
-        Loader { cache_path, source }
+        Loader {
+            cache_path,
+            source: Some(source),
+            modules: Default::default(),
+        }
+    }
+
+    /// Create a module loader without external module source which loads only the given modules.
+    pub fn with_modules_only(modules: HashMap<ModuleId, Vec<u8>>) -> Loader<S> {
+        Loader {
+            cache_path: None,
+            source: None,
+            modules,
+        }
+    }
+
+    /// Adds local modules to the loader. Local modules are loaded before the cached and external ones.
+    pub fn with_modules(mut self, modules: HashMap<ModuleId, Vec<u8>>) -> Loader<S> {
+        self.modules.extend(modules);
+        self
     }
 
     /// Loads module by module id.
-    /// Tries to load the module from the local cache.
+    /// Tries to load the module from the local modules and cache.
     ///  Then tries to load the module from the external module source if the module doesn't exist in cache.
     pub fn get(&self, module_id: &ModuleId) -> Result<Vec<u8>> {
+        if let Some(bytecode) = self.modules.get(module_id) {
+            return Ok(bytecode.to_owned());
+        }
+
+        let source = self.source.as_ref().ok_or_else(|| {
+            anyhow!(
+                "Module '0x{}::{}' not found",
+                module_id.address(),
+                module_id.name()
+            )
+        })?;
+
         let name = self.make_local_name(&module_id);
 
         if let Some(cache_path) = &self.cache_path {
@@ -116,7 +150,7 @@ where
                 f.read_to_end(&mut bytecode)?;
                 Ok(bytecode)
             } else {
-                let bytecode = self.source.load(module_id)?;
+                let bytecode = source.load(module_id)?;
                 let mut f = OpenOptions::new()
                     .create(true)
                     .write(true)
@@ -125,7 +159,7 @@ where
                 Ok(bytecode)
             }
         } else {
-            self.source.load(module_id)
+            source.load(module_id)
         }
     }
 
@@ -139,6 +173,22 @@ where
     }
 }
 
+/// Makes a loader of the project dependencies.
+/// Modules of the resolved declared dependencies are loaded first,
+/// other modules are loaded by the RestBytecodeLoader if the project has `blockchain_api`.
+pub fn make_loader(
+    project_dir: &Path,
+    cmove: &MoveToml,
+    dependencies: &Dependencies,
+) -> Result<Option<Loader<RestBytecodeLoader>>> {
+    let modules = dependencies.modules.clone();
+    Ok(match make_rest_loader(project_dir, cmove)? {
+        Some(loader) => Some(loader.with_modules(modules)),
+        None if !modules.is_empty() => Some(Loader::with_modules_only(modules)),
+        None => None,
+    })
+}
+
 /// Makes a RestBytecodeLoader with project path and project manifest.
 pub fn make_rest_loader(
     project_dir: &Path,
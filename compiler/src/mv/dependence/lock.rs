@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use libra::prelude::*;
+use serde_derive::{Deserialize, Serialize};
+use tiny_keccak::{Hasher, Sha3};
+
+/// Lock file name.
+pub const LOCK: &str = "Move.lock";
+
+/// Dependencies lock.
+/// Records resolved git revisions and hashes of the dependency modules.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct MoveLock {
+    /// Resolved git packages.
+    #[serde(default)]
+    pub git: Vec<LockedGit>,
+    /// Dependency modules.
+    #[serde(default)]
+    pub module: Vec<LockedModule>,
+}
+
+/// Resolved git package.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct LockedGit {
+    /// Dependency name.
+    pub name: String,
+    /// Repository url.
+    pub url: String,
+    /// Declared revision.
+    #[serde(default)]
+    pub rev: String,
+    /// Commit hash of the revision.
+    pub commit: String,
+}
+
+/// Locked module.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct LockedModule {
+    /// Module id in the `0xADDRESS::Name` form.
+    pub id: String,
+    /// sha3-256 hash of the module bytecode.
+    pub hash: String,
+}
+
+impl MoveLock {
+    /// Makes a lock with the git packages and the modules.
+    pub fn new(mut git: Vec<LockedGit>, modules: &HashMap<ModuleId, Vec<u8>>) -> MoveLock {
+        git.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut module = modules
+            .iter()
+            .map(|(id, bytecode)| LockedModule {
+                id: module_name(id),
+                hash: bytecode_hash(bytecode),
+            })
+            .collect::<Vec<_>>();
+        module.sort_by(|a, b| a.id.cmp(&b.id));
+        MoveLock { git, module }
+    }
+
+    /// Reads the lock of the project.
+    /// Returns an empty lock if the project has no lock file.
+    pub fn read(project_dir: &Path) -> Result<MoveLock> {
+        let path = project_dir.join(LOCK);
+        if path.exists() {
+            Ok(toml::from_str(&fs::read_to_string(path)?)?)
+        } else {
+            Ok(MoveLock::default())
+        }
+    }
+
+    /// Stores the lock to the project directory.
+    pub fn store(&self, project_dir: &Path) -> Result<()> {
+        fs::write(project_dir.join(LOCK), toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Checks that the stored lock of the project is up to date with the `actual` one.
+    /// Projects without the lock file are not checked.
+    pub fn verify(&self, project_dir: &Path, actual: &MoveLock) -> Result<()> {
+        if self == actual || !project_dir.join(LOCK).exists() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "{} is out of date. Run `movec build` or `movec update` to update it.",
+                LOCK
+            ))
+        }
+    }
+
+    /// Returns the locked commit of the git package.
+    /// Returns `None` if the package is not locked with the same url and revision.
+    pub fn commit(&self, name: &str, url: &str, rev: &str) -> Option<&str> {
+        self.git
+            .iter()
+            .find(|git| git.name == name && git.url == url && git.rev == rev)
+            .map(|git| git.commit.as_str())
+    }
+
+    /// Checks the bytecode of the modules against the lock.
+    /// Modules which are not locked are skipped.
+    pub fn check(&self, modules: &HashMap<ModuleId, Vec<u8>>) -> Result<()> {
+        let locked = self
+            .module
+            .iter()
+            .map(|module| (module.id.as_str(), module.hash.as_str()))
+            .collect::<HashMap<_, _>>();
+
+        for (id, bytecode) in modules {
+            let name = module_name(id);
+            if let Some(expected) = locked.get(name.as_str()) {
+                let actual = bytecode_hash(bytecode);
+                if *expected != actual {
+                    return Err(anyhow!(
+                        "Module {} does not match {}: expected hash {}, found {}. \
+                         Run `movec update` if the change is expected.",
+                        name,
+                        LOCK,
+                        expected,
+                        actual
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the module id in the `0xADDRESS::Name` form.
+pub fn module_name(id: &ModuleId) -> String {
+    format!("0x{}::{}", hex::encode(id.address()), id.name())
+}
+
+/// Returns hex encoded sha3-256 hash of the bytecode.
+pub fn bytecode_hash(bytecode: &[u8]) -> String {
+    let mut digest = Sha3::v256();
+    digest.update(bytecode);
+    let mut output = [0; 32];
+    digest.finalize(&mut output);
+    hex::encode(&output)
+}
@@ -2,3 +2,7 @@
 pub mod extractor;
 /// Dependencies loader.
 pub mod loader;
+/// Dependencies lock file.
+pub mod lock;
+/// Declared dependencies resolver.
+pub mod resolver;
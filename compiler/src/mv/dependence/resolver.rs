@@ -0,0 +1,319 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::Result;
+use libra::prelude::*;
+
+use crate::manifest::{read_manifest, Dependency, MoveToml, MANIFEST};
+use crate::mv::bech32::bech32_into_libra;
+use crate::mv::builder::Builder;
+use crate::mv::dependence::loader::{make_loader, make_rest_loader};
+use crate::mv::dependence::lock::{bytecode_hash, module_name, LockedGit, MoveLock};
+
+/// Resolved declared dependencies.
+#[derive(Debug, Default)]
+pub struct Dependencies {
+    /// Modules of the source packages, vendored bytecode and declared chain modules.
+    pub modules: HashMap<ModuleId, Vec<u8>>,
+    /// Modules of the local source packages. They change with the sources and are not locked.
+    pub unlocked: HashSet<ModuleId>,
+    /// Expected hashes of the pinned chain modules.
+    pub pins: HashMap<ModuleId, String>,
+    /// Resolved git packages.
+    pub git: Vec<LockedGit>,
+    /// Directories of the local source packages.
+    pub packages: Vec<PathBuf>,
+}
+
+impl Dependencies {
+    /// Checks the loaded dependency modules against the pinned hashes and the `lock`.
+    /// Returns the new lock with the loaded modules.
+    pub fn lock(&self, lock: &MoveLock, loaded: &HashMap<ModuleId, Vec<u8>>) -> Result<MoveLock> {
+        for (id, expected) in &self.pins {
+            if let Some(bytecode) = loaded.get(id) {
+                check_pin(id, expected, bytecode)?;
+            }
+        }
+
+        let locked = loaded
+            .iter()
+            .filter(|(id, _)| !self.unlocked.contains(id))
+            .map(|(id, bytecode)| (id.clone(), bytecode.clone()))
+            .collect();
+        lock.check(&locked)?;
+        Ok(MoveLock::new(self.git.clone(), &locked))
+    }
+
+    fn extend(&mut self, other: Dependencies) {
+        self.modules.extend(other.modules);
+        self.unlocked.extend(other.unlocked);
+        self.pins.extend(other.pins);
+        self.git.extend(other.git);
+        self.packages.extend(other.packages);
+    }
+}
+
+/// Resolves declared dependencies of the project.
+/// Source packages are built, git packages are checked out at the locked commit
+/// or at the declared revision if they are not locked yet or the revision has changed.
+/// Chain modules are loaded from the `blockchain_api` and checked against their pinned hashes.
+pub fn resolve(project_dir: &Path, manifest: &MoveToml, lock: &MoveLock) -> Result<Dependencies> {
+    let mut visited = HashSet::new();
+    visited.insert(project_dir.canonicalize()?);
+    resolve_package(project_dir, manifest, lock, &mut visited)
+}
+
+fn resolve_package(
+    project_dir: &Path,
+    manifest: &MoveToml,
+    lock: &MoveLock,
+    visited: &mut HashSet<PathBuf>,
+) -> Result<Dependencies> {
+    let mut deps = Dependencies::default();
+    let declared = match &manifest.dependencies {
+        Some(declared) => declared,
+        None => return Ok(deps),
+    };
+
+    for (name, dep) in declared {
+        match dep {
+            Dependency {
+                path: Some(path),
+                git: None,
+                bytecode: None,
+                module: None,
+                ..
+            } => {
+                let package_dir = project_dir.join(path);
+                let package = build_package(&package_dir, lock, visited)?;
+                deps.unlocked.extend(package.modules.keys().cloned());
+                deps.extend(package);
+                deps.packages.push(package_dir.canonicalize()?);
+            }
+            Dependency {
+                path: None,
+                git: Some(url),
+                bytecode: None,
+                module: None,
+                ..
+            } => {
+                let rev = dep
+                    .rev
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("Dependency '{}': expected git `rev`.", name))?;
+                check_git_arg(name, "url", url)?;
+                let locked_rev = lock.commit(name, url, rev).unwrap_or(rev);
+                check_git_arg(name, "rev", locked_rev)?;
+                let dir = cache_dir(project_dir, manifest)?.join("git").join(name);
+                let commit = checkout(&dir, url, locked_rev)?;
+                deps.extend(build_package(&dir, lock, visited)?);
+                deps.git.push(LockedGit {
+                    name: name.to_owned(),
+                    url: url.to_owned(),
+                    rev: rev.to_owned(),
+                    commit,
+                });
+            }
+            Dependency {
+                path: None,
+                git: None,
+                bytecode: Some(path),
+                module: None,
+                ..
+            } => {
+                for bytecode in read_bytecode(&project_dir.join(path))? {
+                    deps.modules.insert(module_id(&bytecode)?, bytecode);
+                }
+            }
+            Dependency {
+                path: None,
+                git: None,
+                bytecode: None,
+                module: Some(module),
+                hash,
+                ..
+            } => {
+                let id = parse_module_id(module)?;
+                let loader = make_rest_loader(project_dir, manifest)?.ok_or_else(|| {
+                    anyhow!(
+                        "Dependency '{}': expected `blockchain_api` to load the chain module.",
+                        name
+                    )
+                })?;
+                let bytecode = loader.get(&id)?;
+                if let Some(hash) = hash {
+                    check_pin(&id, hash, &bytecode)?;
+                    deps.pins.insert(id.clone(), hash.to_owned());
+                }
+                deps.modules.insert(id, bytecode);
+            }
+            _ => {
+                return Err(anyhow!(
+                    "Dependency '{}': expected one of `path`, `git`, `bytecode` or `module`.",
+                    name
+                ))
+            }
+        }
+    }
+    Ok(deps)
+}
+
+/// Builds modules of the source package with its dependencies.
+fn build_package(
+    package_dir: &Path,
+    lock: &MoveLock,
+    visited: &mut HashSet<PathBuf>,
+) -> Result<Dependencies> {
+    let package_dir = package_dir
+        .canonicalize()
+        .map_err(|err| anyhow!("Package {:?} not found: {}", package_dir, err))?;
+    if !visited.insert(package_dir.clone()) {
+        return Err(anyhow!(
+            "Cyclic dependency on the package {:?}.",
+            package_dir
+        ));
+    }
+
+    let mut manifest = read_manifest(&package_dir.join(MANIFEST))?;
+    manifest.layout.get_or_insert_with(Default::default).fill();
+
+    let mut deps = resolve_package(&package_dir, &manifest, lock, visited)?;
+    let loader = make_loader(&package_dir, &manifest, &deps)?;
+    let builder = Builder::new(&package_dir, manifest, &loader, true, true);
+    builder.init_build_layout()?;
+
+    let source_map = builder.preprocess_source_map(builder.make_source_map()?)?;
+    let dep_list = builder.make_dependencies_as_source(builder.load_dependencies(&source_map)?)?;
+    let (text_source, units) = builder.compile(source_map, dep_list)?;
+    let (modules, _) = builder.verify_units(text_source, units)?;
+    for unit in modules {
+        let bytecode = unit.serialize();
+        deps.modules.insert(module_id(&bytecode)?, bytecode);
+    }
+
+    visited.remove(&package_dir);
+    Ok(deps)
+}
+
+/// Clones the repository to `dir` and checks out the revision.
+/// Returns the commit hash.
+fn checkout(dir: &Path, url: &str, rev: &str) -> Result<String> {
+    if !dir.exists() {
+        fs::create_dir_all(dir)?;
+        if let Err(err) = git(dir, &["clone", "--quiet", "--", url, "."]) {
+            fs::remove_dir_all(dir)?;
+            return Err(err);
+        }
+    }
+    if git(dir, &["checkout", "--quiet", rev, "--"]).is_err() {
+        git(dir, &["fetch", "--quiet", "origin"])?;
+        git(dir, &["checkout", "--quiet", rev, "--"])?;
+    }
+    git(dir, &["rev-parse", "HEAD"])
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git").args(args).current_dir(dir).output()?;
+    if output.status.success() {
+        Ok(String::from_utf8(output.stdout)?.trim().to_owned())
+    } else {
+        Err(anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Rejects git arguments which can be taken for options or split by the shell.
+fn check_git_arg(name: &str, arg: &str, value: &str) -> Result<()> {
+    let is_valid = !value.is_empty()
+        && !value.starts_with('-')
+        && !value
+            .chars()
+            .any(|ch| ch.is_whitespace() || ch.is_control());
+    if is_valid {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "Dependency '{}': invalid git {} '{}'.",
+            name,
+            arg,
+            value
+        ))
+    }
+}
+
+/// Checks the module bytecode against the pinned hash.
+fn check_pin(id: &ModuleId, expected: &str, bytecode: &[u8]) -> Result<()> {
+    let actual = bytecode_hash(bytecode);
+    if expected.eq_ignore_ascii_case(&actual) {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "Module {} does not match the pinned hash: expected {}, found {}.",
+            module_name(id),
+            expected,
+            actual
+        ))
+    }
+}
+
+fn cache_dir(project_dir: &Path, manifest: &MoveToml) -> Result<PathBuf> {
+    manifest
+        .layout
+        .as_ref()
+        .and_then(|l| l.bytecode_cache.as_ref())
+        .map(|cache| project_dir.join(cache))
+        .ok_or_else(|| anyhow!("Expected bytecode_cache"))
+}
+
+/// Reads the `.mv` file or `.mv` files of the directory.
+fn read_bytecode(path: &Path) -> Result<Vec<Vec<u8>>> {
+    if path.is_dir() {
+        let mut files = fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        files.sort();
+        files
+            .into_iter()
+            .filter(|path| path.extension().map(|ext| ext == "mv").unwrap_or(false))
+            .map(|path| Ok(fs::read(path)?))
+            .collect()
+    } else {
+        Ok(vec![fs::read(path).map_err(|err| {
+            anyhow!("Failed to read bytecode {:?}: {}", path, err)
+        })?])
+    }
+}
+
+/// Returns id of the module bytecode.
+pub(crate) fn module_id(bytecode: &[u8]) -> Result<ModuleId> {
+    Ok(CompiledModule::deserialize(bytecode)
+        .map_err(|e| e.finish(Location::Undefined).into_vm_status())?
+        .self_id())
+}
+
+/// Parses module id in the `ADDRESS::Name` form. Address is a hex or bech32 literal.
+pub fn parse_module_id(id: &str) -> Result<ModuleId> {
+    let mut parts = id.split("::");
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(address), Some(name), None) => {
+            let address = if address.starts_with("0x") {
+                address.to_owned()
+            } else {
+                format!("0x{}", bech32_into_libra(address)?)
+            };
+            Ok(ModuleId::new(
+                AccountAddress::from_hex_literal(&address)?,
+                Identifier::new(name)?,
+            ))
+        }
+        _ => Err(anyhow!(
+            "Invalid module id '{}'. Expected ADDRESS::NAME.",
+            id
+        )),
+    }
+}
@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::Error;
+use libra::compiler::{ByteIndex, ConstPool, Errors, FilesSourceText, Loc, Span as MoveSpan};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::mv::preprocessor::OffsetMap;
+
+/// Diagnostics output format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageFormat {
+    /// Human readable messages.
+    Human,
+    /// Json diagnostics, one per line.
+    Json,
+}
+
+impl FromStr for MessageFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "human" => Ok(MessageFormat::Human),
+            "json" => Ok(MessageFormat::Json),
+            _ => Err(anyhow!(
+                "Unknown message format '{}'. Expected human or json.",
+                s
+            )),
+        }
+    }
+}
+
+/// Diagnostic severity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Compilation error.
+    Error,
+    /// Warning.
+    Warning,
+}
+
+/// Position in the source file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    /// Byte offset.
+    pub byte: usize,
+    /// Line number starting from 1.
+    pub line: usize,
+    /// Column number in characters starting from 1.
+    pub column: usize,
+}
+
+/// Source file span.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    /// Start position.
+    pub start: Position,
+    /// End position, exclusive.
+    pub end: Position,
+}
+
+/// Secondary diagnostic label.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Label {
+    /// Source file.
+    pub file: String,
+    /// Labeled span.
+    pub span: Span,
+    /// Label message.
+    pub message: String,
+}
+
+/// Compiler diagnostic.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// Diagnostic severity.
+    pub severity: Severity,
+    /// Primary message.
+    pub message: String,
+    /// Source file of the primary span. None for errors without location.
+    pub file: Option<String>,
+    /// Primary span.
+    pub span: Option<Span>,
+    /// Secondary labels.
+    pub labels: Vec<Label>,
+}
+
+impl Diagnostic {
+    /// Creates an error diagnostic without location.
+    pub fn error(message: String) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Error,
+            message,
+            file: None,
+            span: None,
+            labels: vec![],
+        }
+    }
+}
+
+/// Original source of the preprocessed file.
+#[derive(Debug, Clone)]
+pub struct SourceOrigin {
+    /// Original file name.
+    pub path: String,
+    /// Original source code.
+    pub text: String,
+    /// Map of the preprocessed offsets to the original ones.
+    pub offsets: OffsetMap,
+}
+
+/// Compilation failure with the structured diagnostics.
+/// Displayed as the rendered compiler errors.
+#[derive(Debug)]
+pub struct CompilationError {
+    /// Compiler diagnostics.
+    pub diagnostics: Vec<Diagnostic>,
+    /// Human readable errors.
+    pub rendered: String,
+}
+
+impl fmt::Display for CompilationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.rendered)
+    }
+}
+
+impl std::error::Error for CompilationError {}
+
+impl From<Error> for CompilationError {
+    fn from(err: Error) -> CompilationError {
+        match err.downcast::<CompilationError>() {
+            Ok(err) => err,
+            Err(err) => CompilationError {
+                diagnostics: vec![Diagnostic::error(err.to_string())],
+                rendered: err.to_string(),
+            },
+        }
+    }
+}
+
+/// Returns diagnostics of the error.
+/// Errors which are not compilation errors are returned as a single diagnostic without location.
+pub fn diagnostics(err: &Error) -> Vec<Diagnostic> {
+    match err.downcast_ref::<CompilationError>() {
+        Some(err) => err.diagnostics.clone(),
+        None => vec![Diagnostic::error(err.to_string())],
+    }
+}
+
+/// Converts compiler errors to diagnostics.
+pub fn from_errors(files: &FilesSourceText, errors: &Errors) -> Vec<Diagnostic> {
+    errors
+        .iter()
+        .filter_map(|error| {
+            let mut parts = error.iter();
+            let (loc, message) = parts.next()?;
+            let labels = parts
+                .map(|(loc, message)| Label {
+                    file: loc.file().to_owned(),
+                    span: span(files, loc),
+                    message: message.to_owned(),
+                })
+                .collect();
+            Some(Diagnostic {
+                severity: Severity::Error,
+                message: message.to_owned(),
+                file: Some(loc.file().to_owned()),
+                span: Some(span(files, loc)),
+                labels,
+            })
+        })
+        .collect()
+}
+
+fn span(files: &FilesSourceText, loc: &Loc) -> Span {
+    let text = files
+        .get(loc.file())
+        .map(|text| text.as_str())
+        .unwrap_or("");
+    Span {
+        start: position(text, loc.span().start().to_usize()),
+        end: position(text, loc.span().end().to_usize()),
+    }
+}
+
+/// Maps compiler errors in the preprocessed files to the original sources.
+/// Files without origin are left as is.
+pub fn to_original(
+    files: FilesSourceText,
+    errors: Errors,
+    origins: &HashMap<String, SourceOrigin>,
+) -> (FilesSourceText, Errors) {
+    let files = files
+        .into_iter()
+        .map(|(file, text)| match origins.get(file) {
+            Some(origin) => (ConstPool::push(&origin.path), origin.text.to_owned()),
+            None => (file, text),
+        })
+        .collect();
+    let errors = errors
+        .into_iter()
+        .map(|error| {
+            error
+                .into_iter()
+                .map(|(loc, message)| match origins.get(loc.file()) {
+                    Some(origin) => {
+                        let start = origin.offsets.to_original(loc.span().start().to_usize());
+                        let end = origin.offsets.to_original(loc.span().end().to_usize());
+                        let span = MoveSpan::new(ByteIndex(start as u32), ByteIndex(end as u32));
+                        (Loc::new(ConstPool::push(&origin.path), span), message)
+                    }
+                    None => (loc, message),
+                })
+                .collect()
+        })
+        .collect();
+    (files, errors)
+}
+
+/// Returns the position of the byte offset in the text.
+pub fn position(text: &str, byte: usize) -> Position {
+    let byte = byte.min(text.len());
+    let prefix = text.get(..byte).unwrap_or(text);
+    let line_start = prefix.rfind('\n').map(|pos| pos + 1).unwrap_or(0);
+    Position {
+        byte,
+        line: prefix.matches('\n').count() + 1,
+        column: prefix[line_start..].chars().count() + 1,
+    }
+}
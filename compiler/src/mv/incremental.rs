@@ -0,0 +1,509 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use libra::prelude::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::mv::builder::Builder;
+use crate::mv::dependence::loader::BytecodeLoader;
+use crate::mv::dependence::lock::{bytecode_hash, module_name};
+use crate::mv::dependence::resolver::module_id;
+use crate::mv::disassembler::{disasm_str, Config};
+
+/// Fingerprints file name.
+pub const FINGERPRINTS: &str = "fingerprints.toml";
+
+/// Fingerprints of the compiled sources.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Fingerprints {
+    /// Hex encoded account address the sources are compiled with.
+    pub address: Option<String>,
+    /// Dependency modules.
+    #[serde(default)]
+    pub dependency: Vec<DependencyFingerprint>,
+    /// Preprocessed source files.
+    #[serde(default)]
+    pub file: Vec<FileFingerprint>,
+}
+
+/// Dependency module fingerprint.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct DependencyFingerprint {
+    /// Module id in the `0xADDRESS::Name` form.
+    pub id: String,
+    /// sha3-256 hash of the module interface.
+    pub interface: String,
+}
+
+/// Source file fingerprint.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct FileFingerprint {
+    /// Path relative to the preprocessed sources directory.
+    pub path: String,
+    /// sha3-256 hash of the preprocessed source.
+    pub hash: String,
+    /// Dependency modules imported by the file.
+    #[serde(default)]
+    pub dependency: Vec<String>,
+    /// Units compiled from the file.
+    #[serde(default)]
+    pub unit: Vec<UnitFingerprint>,
+}
+
+/// Compiled unit fingerprint.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct UnitFingerprint {
+    /// Unit name.
+    pub name: String,
+    /// sha3-256 hash of the bytecode. The bytecode is stored as `units/<hash>.mv`.
+    pub bytecode: String,
+    /// sha3-256 hash of the module interface. None for scripts.
+    pub interface: Option<String>,
+}
+
+impl Fingerprints {
+    /// Reads fingerprints from the cache directory.
+    /// Returns empty fingerprints if the cache is empty.
+    pub fn read(cache_dir: &Path) -> Result<Fingerprints> {
+        let path = cache_dir.join(FINGERPRINTS);
+        if path.exists() {
+            Ok(toml::from_str(&fs::read_to_string(path)?)?)
+        } else {
+            Ok(Fingerprints::default())
+        }
+    }
+
+    /// Stores fingerprints to the cache directory.
+    pub fn store(&self, cache_dir: &Path) -> Result<()> {
+        fs::create_dir_all(cache_dir)?;
+        fs::write(cache_dir.join(FINGERPRINTS), toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    fn file(&self, path: &str) -> Option<&FileFingerprint> {
+        self.file.iter().find(|file| file.path == path)
+    }
+
+    fn dependency(&self, id: &str) -> Option<&str> {
+        self.dependency
+            .iter()
+            .find(|dep| dep.id == id)
+            .map(|dep| dep.interface.as_str())
+    }
+}
+
+/// Incremental compilation result.
+#[derive(Debug, Default)]
+pub struct Compiled {
+    /// Serialized modules with names.
+    pub modules: Vec<(String, Vec<u8>)>,
+    /// Serialized scripts with names.
+    pub scripts: Vec<(String, Vec<u8>)>,
+    /// Recompiled files relative to the preprocessed sources directory.
+    pub recompiled: Vec<String>,
+}
+
+/// Compiles preprocessed sources reusing the units cached by the previous compilation.
+///
+/// A file is recompiled if its preprocessed source, the account address or the set of the imported
+/// dependency modules changed, or if the interface of any module it uses directly or transitively changed.
+/// Files to recompile are compiled together until a reused file needs the interfaces of their modules.
+/// Dependencies are the loaded dependency modules.
+pub fn compile<S>(
+    builder: &Builder<S>,
+    sources: Vec<PathBuf>,
+    dependencies: HashMap<ModuleId, Vec<u8>>,
+) -> Result<Compiled>
+where
+    S: BytecodeLoader,
+{
+    let cache_dir = builder.build_cache_dir()?;
+    let units_dir = cache_dir.join("units");
+    fs::create_dir_all(&units_dir)?;
+    let source_dir = builder.temp_dir()?.join("src");
+
+    let cached = Fingerprints::read(&cache_dir)?;
+    let mut fingerprints = Fingerprints {
+        address: builder.address()?.map(|addr| hex::encode(addr.to_u8())),
+        ..Default::default()
+    };
+    let address_changed = fingerprints.address != cached.address;
+
+    // Modules with changed interfaces and modules which use them.
+    let mut affected = HashSet::new();
+    let mut dep_list = Vec::with_capacity(dependencies.len());
+    let mut ids = dependencies.keys().cloned().collect::<Vec<_>>();
+    ids.sort();
+    for id in ids {
+        let bytecode = &dependencies[&id];
+        let name = module_name(&id);
+        let interface = interface_hash(bytecode)?;
+        if cached.dependency(&name) != Some(interface.as_str()) {
+            affected.insert(id.clone());
+        }
+        fingerprints.dependency.push(DependencyFingerprint {
+            id: name,
+            interface,
+        });
+        dep_list.extend(builder.make_dependencies_as_source(single(id, bytecode.to_owned()))?);
+    }
+
+    let files = sort(builder, sources)?;
+    let mut session = Session {
+        builder,
+        units_dir,
+        dep_list,
+        affected,
+        interfaces: HashMap::new(),
+        fingerprints,
+        compiled: Compiled::default(),
+    };
+    // Files to recompile in one compilation.
+    let mut batch = Vec::new();
+    for (idx, file) in files.iter().enumerate() {
+        let path = file
+            .path
+            .strip_prefix(&source_dir)?
+            .to_string_lossy()
+            .to_string();
+        let hash = bytecode_hash(&fs::read(&file.path)?);
+        let mut dependency = file
+            .imports
+            .iter()
+            .filter(|import| dependencies.contains_key(import))
+            .map(module_name)
+            .collect::<Vec<_>>();
+        dependency.sort();
+        let fingerprint = FileFingerprint {
+            path,
+            hash,
+            dependency,
+            unit: vec![],
+        };
+
+        let old = cached.file(&fingerprint.path).filter(|old| {
+            old.hash == fingerprint.hash
+                && old.dependency == fingerprint.dependency
+                && old
+                    .unit
+                    .iter()
+                    .all(|unit| unit_path(&session.units_dir, &unit.bytecode).exists())
+        });
+        match old {
+            Some(old) if !address_changed => {
+                // Interfaces of the pending files must be known to reuse the file.
+                if file
+                    .deps
+                    .iter()
+                    .any(|dep| batch.iter().any(|(pending, _)| pending == dep))
+                {
+                    session.compile_batch(&files, &cached, batch)?;
+                    batch = Vec::new();
+                }
+                if file
+                    .imports
+                    .iter()
+                    .any(|import| session.affected.contains(import))
+                {
+                    batch.push((idx, fingerprint));
+                } else {
+                    let units = old
+                        .unit
+                        .iter()
+                        .map(|unit| {
+                            let bytecode = fs::read(unit_path(&session.units_dir, &unit.bytecode))?;
+                            Ok((unit.clone(), bytecode))
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    session.add(file, Some(old), fingerprint, units)?;
+                }
+            }
+            _ => batch.push((idx, fingerprint)),
+        }
+    }
+    session.compile_batch(&files, &cached, batch)?;
+
+    let Session {
+        units_dir,
+        mut fingerprints,
+        compiled,
+        ..
+    } = session;
+    fingerprints.file.sort_by(|a, b| a.path.cmp(&b.path));
+    fingerprints.store(&cache_dir)?;
+    remove_unused_units(&units_dir, &fingerprints)?;
+    Ok(compiled)
+}
+
+/// State of the incremental compilation.
+struct Session<'a, 'b, S: BytecodeLoader> {
+    builder: &'a Builder<'b, S>,
+    units_dir: PathBuf,
+    /// Interfaces of the dependency modules.
+    dep_list: Vec<PathBuf>,
+    /// Modules with changed interfaces and modules which use them.
+    affected: HashSet<ModuleId>,
+    /// Interfaces of the project modules.
+    interfaces: HashMap<ModuleId, PathBuf>,
+    fingerprints: Fingerprints,
+    compiled: Compiled,
+}
+
+impl<'a, 'b, S> Session<'a, 'b, S>
+where
+    S: BytecodeLoader,
+{
+    /// Compiles the files in one compilation with the interfaces of the modules they use.
+    fn compile_batch(
+        &mut self,
+        files: &[SourceFile],
+        cached: &Fingerprints,
+        batch: Vec<(usize, FileFingerprint)>,
+    ) -> Result<()> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let pending = batch.iter().map(|(idx, _)| *idx).collect::<HashSet<_>>();
+        let mut dep_list = self.dep_list.clone();
+        let mut used = HashSet::new();
+        for idx in &pending {
+            used.extend(closure(*idx, files));
+        }
+        for dep in used.difference(&pending) {
+            for module in &files[*dep].modules {
+                if let Some(interface) = self.interfaces.get(module) {
+                    dep_list.push(interface.to_owned());
+                }
+            }
+        }
+
+        let source_list = batch
+            .iter()
+            .map(|(idx, _)| files[*idx].path.clone())
+            .collect();
+        let (text_source, units) = self.builder.compile(source_list, dep_list)?;
+        let (modules, scripts) = self.builder.verify_units(text_source, units)?;
+
+        let mut file_units = vec![Vec::new(); batch.len()];
+        for unit in modules.into_iter().chain(scripts) {
+            let script_file = match &unit {
+                CompiledUnit::Module { .. } => None,
+                CompiledUnit::Script { loc, .. } => Some(loc.file()),
+            };
+            let name = unit.name();
+            let bytecode = unit.serialize();
+            let (pos, interface) = match script_file {
+                None => {
+                    let id = module_id(&bytecode)?;
+                    let pos = batch
+                        .iter()
+                        .position(|(idx, _)| files[*idx].modules.contains(&id));
+                    (pos, Some(interface_hash(&bytecode)?))
+                }
+                Some(script_file) => {
+                    let pos = batch
+                        .iter()
+                        .position(|(idx, _)| files[*idx].path.to_str() == Some(script_file));
+                    (pos, None)
+                }
+            };
+            let pos = pos.ok_or_else(|| anyhow!("Unexpected compiled unit {}.", name))?;
+            let fingerprint = UnitFingerprint {
+                name,
+                bytecode: bytecode_hash(&bytecode),
+                interface,
+            };
+            fs::write(unit_path(&self.units_dir, &fingerprint.bytecode), &bytecode)?;
+            file_units[pos].push((fingerprint, bytecode));
+        }
+
+        for ((idx, fingerprint), units) in batch.into_iter().zip(file_units) {
+            let previous = cached.file(&fingerprint.path);
+            self.compiled.recompiled.push(fingerprint.path.clone());
+            self.add(&files[idx], previous, fingerprint, units)?;
+        }
+        Ok(())
+    }
+
+    /// Adds units of the file to the compilation result.
+    /// Marks modules with changed interfaces as affected.
+    fn add(
+        &mut self,
+        file: &SourceFile,
+        previous: Option<&FileFingerprint>,
+        mut fingerprint: FileFingerprint,
+        units: Vec<(UnitFingerprint, Vec<u8>)>,
+    ) -> Result<()> {
+        let uses_affected = file
+            .imports
+            .iter()
+            .any(|import| self.affected.contains(import));
+        for (unit, bytecode) in &units {
+            if let Some(interface) = &unit.interface {
+                let id = module_id(bytecode)?;
+                let changed = previous
+                    .and_then(|old| old.unit.iter().find(|old| old.name == unit.name))
+                    .map(|old| old.interface.as_ref() != Some(interface))
+                    .unwrap_or(true);
+                if changed || uses_affected {
+                    self.affected.insert(id.clone());
+                }
+                self.interfaces.insert(
+                    id.clone(),
+                    self.builder
+                        .make_dependencies_as_source(single(id, bytecode.to_owned()))?
+                        .remove(0),
+                );
+                self.compiled
+                    .modules
+                    .push((unit.name.clone(), bytecode.to_owned()));
+            } else {
+                self.compiled
+                    .scripts
+                    .push((unit.name.clone(), bytecode.to_owned()));
+            }
+        }
+
+        fingerprint.unit = units.into_iter().map(|(unit, _)| unit).collect();
+        self.fingerprints.file.push(fingerprint);
+        Ok(())
+    }
+}
+
+/// Preprocessed source file with its definitions.
+struct SourceFile {
+    path: PathBuf,
+    modules: HashSet<ModuleId>,
+    imports: HashSet<ModuleId>,
+    /// Indexes of the files with the imported modules.
+    deps: Vec<usize>,
+}
+
+/// Sorts source files in the dependency order.
+fn sort<S>(builder: &Builder<S>, sources: Vec<PathBuf>) -> Result<Vec<SourceFile>>
+where
+    S: BytecodeLoader,
+{
+    let mut files = Vec::with_capacity(sources.len());
+    let mut owners = HashMap::new();
+    for (idx, path) in sources.into_iter().enumerate() {
+        let (modules, imports) = builder.definitions(&[path.clone()])?;
+        for module in &modules {
+            owners.insert(module.clone(), idx);
+        }
+        files.push(SourceFile {
+            path,
+            modules,
+            imports,
+            deps: vec![],
+        });
+    }
+    for file in files.iter_mut() {
+        file.deps = file
+            .imports
+            .iter()
+            .filter_map(|import| owners.get(import).copied())
+            .collect();
+        file.deps.sort();
+        file.deps.dedup();
+    }
+
+    let mut order = Vec::with_capacity(files.len());
+    let mut visited = HashSet::new();
+    let mut path = HashSet::new();
+    for idx in 0..files.len() {
+        visit(idx, &files, &mut visited, &mut path, &mut order)?;
+    }
+
+    let mut files = files.into_iter().map(Some).collect::<Vec<_>>();
+    let position = order
+        .iter()
+        .enumerate()
+        .map(|(pos, idx)| (*idx, pos))
+        .collect::<HashMap<_, _>>();
+    Ok(order
+        .iter()
+        .filter_map(|idx| files[*idx].take())
+        .map(|mut file| {
+            file.deps = file.deps.iter().map(|dep| position[dep]).collect();
+            file
+        })
+        .collect())
+}
+
+/// Depth-first topological sort of the source files.
+fn visit(
+    idx: usize,
+    files: &[SourceFile],
+    visited: &mut HashSet<usize>,
+    path: &mut HashSet<usize>,
+    order: &mut Vec<usize>,
+) -> Result<()> {
+    if visited.contains(&idx) {
+        return Ok(());
+    }
+    if !path.insert(idx) {
+        return Err(anyhow!("Cyclic dependency in {:?}.", files[idx].path));
+    }
+    for dep in &files[idx].deps {
+        if *dep != idx {
+            visit(*dep, files, visited, path, order)?;
+        }
+    }
+    path.remove(&idx);
+    visited.insert(idx);
+    order.push(idx);
+    Ok(())
+}
+
+/// Returns indexes of the files used by the file directly or transitively.
+fn closure(idx: usize, files: &[SourceFile]) -> HashSet<usize> {
+    let mut deps = HashSet::new();
+    let mut stack = files[idx].deps.clone();
+    while let Some(dep) = stack.pop() {
+        if dep != idx && deps.insert(dep) {
+            stack.extend(files[dep].deps.iter().copied());
+        }
+    }
+    deps
+}
+
+/// Returns hex encoded sha3-256 hash of the module interface.
+fn interface_hash(bytecode: &[u8]) -> Result<String> {
+    let interface = disasm_str(
+        bytecode,
+        Config {
+            light_version: true,
+        },
+    )?;
+    Ok(bytecode_hash(interface.as_bytes()))
+}
+
+fn single(id: ModuleId, bytecode: Vec<u8>) -> HashMap<ModuleId, Vec<u8>> {
+    let mut map = HashMap::with_capacity(1);
+    map.insert(id, bytecode);
+    map
+}
+
+fn unit_path(units_dir: &Path, hash: &str) -> PathBuf {
+    units_dir.join(hash).with_extension("mv")
+}
+
+fn remove_unused_units(units_dir: &Path, fingerprints: &Fingerprints) -> Result<()> {
+    let used = fingerprints
+        .file
+        .iter()
+        .flat_map(|file| file.unit.iter())
+        .map(|unit| unit_path(units_dir, &unit.bytecode))
+        .collect::<HashSet<_>>();
+    for entry in fs::read_dir(units_dir)? {
+        let path = entry?.path();
+        if !used.contains(&path) {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
@@ -4,7 +4,13 @@ pub mod bech32;
 pub mod builder;
 /// Dependencies loader.
 pub mod dependence;
+/// Structured compiler diagnostics.
+pub mod diagnostics;
 /// Move lang disassembler.
 pub mod disassembler;
+/// Incremental compilation cache.
+pub mod incremental;
 /// Move source preprocessor.
 pub mod preprocessor;
+/// Multi-package workspace builder.
+pub mod workspace;
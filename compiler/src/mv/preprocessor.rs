@@ -1,6 +1,248 @@
-use crate::mv::bech32::replace_bech32_addresses;
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use anyhow::Result;
+use libra::compiler::Address;
+
+use crate::mv::bech32::{bech32_into_libra, find_bech32_addresses};
 
 /// Preprocess move code.
 pub fn pre_processing(code: &str) -> String {
-    replace_bech32_addresses(code)
+    preprocess(code).0
+}
+
+/// Preprocess move code.
+/// Returns the preprocessed code with the map of its positions to the original code.
+pub fn preprocess(code: &str) -> (String, OffsetMap) {
+    let (code, offsets, _) = preprocess_with_names(code, &Names::default());
+    (code, offsets)
+}
+
+/// Preprocess move code substituting the named addresses and constants.
+/// Returns the preprocessed code with the map of its positions to the original code
+/// and the references to the unknown names, which are left as is.
+pub fn preprocess_with_names(code: &str, names: &Names) -> (String, OffsetMap, Vec<UnknownName>) {
+    let mut replacements = find_bech32_addresses(code);
+    let mut unknown = Vec::new();
+    for (range, name) in find_name_references(code) {
+        match names.get(name) {
+            Some(value) => replacements.push((range, value.to_owned())),
+            None => unknown.push(UnknownName {
+                name: name.to_owned(),
+                range,
+            }),
+        }
+    }
+    replacements.sort_by_key(|(range, _)| range.start);
+    let (code, offsets) = replace(code, replacements);
+    (code, offsets, unknown)
+}
+
+/// Replaces the ranges of the source with the new fragments.
+/// Ranges must be sorted by start, ranges overlapping the previous ones are skipped.
+/// Returns the new string with the map of its offsets to the `source` offsets.
+pub fn replace(source: &str, replacements: Vec<(Range<usize>, String)>) -> (String, OffsetMap) {
+    let mut result = String::with_capacity(source.len());
+    let mut offsets = OffsetMap::default();
+    let mut last = 0;
+    for (range, fragment) in replacements {
+        if range.start < last {
+            continue;
+        }
+        result.push_str(&source[last..range.start]);
+        offsets.replace(
+            range.start,
+            range.end - range.start,
+            result.len(),
+            fragment.len(),
+        );
+        result.push_str(&fragment);
+        last = range.end;
+    }
+    result.push_str(&source[last..]);
+    (result, offsets)
+}
+
+/// Named addresses and constants.
+/// Names are referenced in the code as `@Name`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Names {
+    values: BTreeMap<String, String>,
+}
+
+impl Names {
+    /// Creates names from the named addresses and constants.
+    /// Addresses are hex or bech32 literals, constants are substituted as is.
+    pub fn new(
+        addresses: &BTreeMap<String, String>,
+        constants: &BTreeMap<String, String>,
+    ) -> Result<Names> {
+        let mut values = BTreeMap::new();
+        for (name, address) in addresses {
+            ensure!(is_identifier(name), "Invalid address name '{}'.", name);
+            let literal = address_literal(address)
+                .map_err(|err| anyhow!("Invalid address '{}' of '{}': {}", address, name, err))?;
+            values.insert(name.to_owned(), literal);
+        }
+        for (name, value) in constants {
+            ensure!(is_identifier(name), "Invalid constant name '{}'.", name);
+            ensure!(
+                !values.contains_key(name),
+                "'{}' is declared as both address and constant.",
+                name
+            );
+            values.insert(name.to_owned(), value.to_owned());
+        }
+        Ok(Names { values })
+    }
+
+    /// Returns the substituted value of the name.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(|value| value.as_str())
+    }
+
+    /// Returns `true` if no names are declared.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+/// Reference to the name which is not declared.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownName {
+    /// Referenced name.
+    pub name: String,
+    /// Range of the reference in the original code, including `@`.
+    pub range: Range<usize>,
+}
+
+/// Converts the hex or bech32 address to the hex literal.
+fn address_literal(address: &str) -> Result<String> {
+    if address.starts_with("0x") {
+        Address::parse_str(address).map_err(|err| anyhow!(err))?;
+        Ok(address.to_owned())
+    } else {
+        Ok(format!("0x{}", bech32_into_libra(address)?))
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() || first == '_' => {
+            chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+        }
+        _ => false,
+    }
+}
+
+/// Finds `@Name` references outside of comments and string literals.
+fn find_name_references(code: &str) -> Vec<(Range<usize>, &str)> {
+    let bytes = code.as_bytes();
+    let is_ident = |idx: usize| {
+        bytes
+            .get(idx)
+            .map(|ch| ch.is_ascii_alphanumeric() || *ch == b'_')
+            .unwrap_or(false)
+    };
+
+    let mut references = Vec::new();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'/' if bytes.get(idx + 1) == Some(&b'/') => {
+                idx = code[idx..]
+                    .find('\n')
+                    .map(|pos| idx + pos)
+                    .unwrap_or_else(|| bytes.len());
+            }
+            b'/' if bytes.get(idx + 1) == Some(&b'*') => {
+                idx = code[idx + 2..]
+                    .find("*/")
+                    .map(|pos| idx + pos + 4)
+                    .unwrap_or_else(|| bytes.len());
+            }
+            b'"' => {
+                idx += 1;
+                while idx < bytes.len() && bytes[idx] != b'"' {
+                    if bytes[idx] == b'\\' {
+                        idx += 1;
+                    }
+                    idx += 1;
+                }
+                idx += 1;
+            }
+            b'@' => {
+                let start = idx;
+                idx += 1;
+                while is_ident(idx) {
+                    idx += 1;
+                }
+                if idx > start + 1 && !bytes[start + 1].is_ascii_digit() {
+                    references.push((start..idx, &code[start + 1..idx]));
+                }
+            }
+            _ => idx += 1,
+        }
+    }
+    references
+}
+
+/// Replaced fragment of the source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Replacement {
+    original_start: usize,
+    original_len: usize,
+    new_start: usize,
+    new_len: usize,
+}
+
+/// Map of the preprocessed code byte offsets to the original code byte offsets.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OffsetMap {
+    replacements: Vec<Replacement>,
+}
+
+impl OffsetMap {
+    /// Records the replacement of `original_len` bytes at `original_start` of the original code
+    /// with `new_len` bytes at `new_start` of the preprocessed code.
+    /// Replacements must be recorded in the source order.
+    pub fn replace(
+        &mut self,
+        original_start: usize,
+        original_len: usize,
+        new_start: usize,
+        new_len: usize,
+    ) {
+        self.replacements.push(Replacement {
+            original_start,
+            original_len,
+            new_start,
+            new_len,
+        });
+    }
+
+    /// Returns `true` if the code was not changed.
+    pub fn is_empty(&self) -> bool {
+        self.replacements.is_empty()
+    }
+
+    /// Maps the preprocessed code offset to the original code offset.
+    /// Offsets inside a replaced fragment are mapped into the original fragment.
+    pub fn to_original(&self, offset: usize) -> usize {
+        let mut delta = 0isize;
+        for replacement in &self.replacements {
+            if offset < replacement.new_start {
+                break;
+            }
+            let new_end = replacement.new_start + replacement.new_len;
+            if offset < new_end {
+                return replacement.original_start
+                    + (offset - replacement.new_start).min(replacement.original_len);
+            }
+            delta =
+                (replacement.original_start + replacement.original_len) as isize - new_end as isize;
+        }
+        (offset as isize + delta) as usize
+    }
 }
@@ -0,0 +1,228 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use libra::prelude::*;
+
+use crate::manifest::{read_manifest, MoveToml, MANIFEST};
+use crate::mv::builder::Builder;
+use crate::mv::dependence::loader::{make_loader, Loader, RestBytecodeLoader};
+use crate::mv::dependence::lock::{module_name, MoveLock};
+use crate::mv::dependence::resolver::{module_id, resolve};
+
+/// Default target directory of the workspace.
+pub const TARGET_DIR: &str = "target";
+
+/// Workspace member package.
+#[derive(Debug, Clone)]
+pub struct Member {
+    /// Package name or directory name if the package has no name.
+    pub name: String,
+    /// Package directory.
+    pub dir: PathBuf,
+    /// Package manifest with the layout in the shared target directory.
+    pub manifest: MoveToml,
+}
+
+/// Built workspace member.
+#[derive(Debug)]
+pub struct BuiltMember {
+    /// Workspace member.
+    pub member: Member,
+    /// Compiled modules of the member.
+    pub modules: HashMap<ModuleId, Vec<u8>>,
+}
+
+/// Loads members of the workspace.
+/// Members are sorted in the build order: each member goes after the members whose modules it uses.
+pub fn members(workspace_dir: &Path, manifest: &MoveToml) -> Result<Vec<Member>> {
+    let workspace = manifest
+        .workspace
+        .as_ref()
+        .ok_or_else(|| anyhow!("Expected [workspace] section in {}.", MANIFEST))?;
+    let target_dir = workspace_dir.join(workspace.target_dir.as_deref().unwrap_or(TARGET_DIR));
+
+    let mut members: Vec<Member> = Vec::with_capacity(workspace.members.len());
+    for path in &workspace.members {
+        let dir = workspace_dir
+            .join(path)
+            .canonicalize()
+            .map_err(|err| anyhow!("Workspace member {:?} not found: {}", path, err))?;
+        let mut manifest = read_manifest(&dir.join(MANIFEST))?;
+        let name = manifest
+            .package
+            .name
+            .clone()
+            .or_else(|| {
+                dir.file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+            })
+            .ok_or_else(|| anyhow!("Failed to get name of the workspace member {:?}.", path))?;
+        if members.iter().any(|member| member.name == name) {
+            return Err(anyhow!("Duplicate workspace member '{}'.", name));
+        }
+
+        let layout = manifest.layout.get_or_insert_with(Default::default);
+        let dir_in_target =
+            |path: PathBuf| Some(target_dir.join(path).to_string_lossy().to_string());
+        layout.bytecode_cache = dir_in_target(PathBuf::from("deps"));
+        layout.temp_dir = dir_in_target(PathBuf::from("build").join(&name));
+        layout.module_output = dir_in_target(["artifacts", &name, "modules"].iter().collect());
+        layout.script_output = dir_in_target(["artifacts", &name, "scripts"].iter().collect());
+        layout.build_cache = dir_in_target(PathBuf::from("cache").join(&name));
+        layout.fill();
+
+        members.push(Member {
+            name,
+            dir,
+            manifest,
+        });
+    }
+
+    sort(members)
+}
+
+/// Builds members of the workspace in the dependency order.
+/// Modules of the built members are available to the next members without publishing.
+/// Compiled units and the updated lock files are stored to the members if `store` is true,
+/// otherwise the lock files are only verified.
+pub fn build(workspace_dir: &Path, manifest: &MoveToml, store: bool) -> Result<Vec<BuiltMember>> {
+    let mut workspace_modules = HashMap::new();
+    let mut built = Vec::new();
+    for member in members(workspace_dir, manifest)? {
+        let modules = build_member(&member, &workspace_modules, store)?;
+        workspace_modules.extend(modules.clone());
+        built.push(BuiltMember { member, modules });
+    }
+    Ok(built)
+}
+
+fn build_member(
+    member: &Member,
+    workspace_modules: &HashMap<ModuleId, Vec<u8>>,
+    store: bool,
+) -> Result<HashMap<ModuleId, Vec<u8>>> {
+    let lock = MoveLock::read(&member.dir)?;
+    let mut dependencies = resolve(&member.dir, &member.manifest, &lock)?;
+    dependencies
+        .unlocked
+        .extend(workspace_modules.keys().cloned());
+    dependencies.modules.extend(workspace_modules.clone());
+
+    let loader = make_loader(&member.dir, &member.manifest, &dependencies)?;
+    let builder = Builder::new(&member.dir, member.manifest.clone(), &loader, true, true);
+    builder.init_build_layout()?;
+
+    let source_map = builder.preprocess_source_map(builder.make_source_map()?)?;
+    let bytecode_map = builder.load_dependencies(&source_map)?;
+    let new_lock = dependencies.lock(&lock, &bytecode_map)?;
+    if !store {
+        lock.verify(&member.dir, &new_lock)?;
+    } else if new_lock != lock {
+        new_lock.store(&member.dir)?;
+    }
+    let dep_list = builder.make_dependencies_as_source(bytecode_map)?;
+
+    let (text_source, units) = builder.compile(source_map, dep_list)?;
+    let (modules, scripts) = builder.verify_units(text_source, units)?;
+    let modules = modules
+        .into_iter()
+        .map(|unit| (unit.name(), unit.serialize()))
+        .collect::<Vec<_>>();
+    if store {
+        let scripts = scripts
+            .into_iter()
+            .map(|unit| (unit.name(), unit.serialize()))
+            .collect();
+        builder.store(modules.clone(), scripts)?;
+    }
+
+    modules
+        .into_iter()
+        .map(|(_, bytecode)| Ok((module_id(&bytecode)?, bytecode)))
+        .collect()
+}
+
+/// Returns modules defined by the member and its imports.
+fn definitions(member: &Member) -> Result<(HashSet<ModuleId>, HashSet<ModuleId>)> {
+    let loader: Option<Loader<RestBytecodeLoader>> = None;
+    let builder = Builder::new(&member.dir, member.manifest.clone(), &loader, true, true);
+    builder.init_build_layout()?;
+    let source_map = builder.preprocess_source_map(builder.make_source_map()?)?;
+    builder.definitions(&source_map)
+}
+
+/// Sorts members in the dependency order.
+fn sort(members: Vec<Member>) -> Result<Vec<Member>> {
+    let mut owners = HashMap::new();
+    let mut imports = Vec::with_capacity(members.len());
+    for (idx, member) in members.iter().enumerate() {
+        let (modules, member_imports) = definitions(member)?;
+        for module in modules {
+            if let Some(owner) = owners.insert(module.clone(), idx) {
+                return Err(anyhow!(
+                    "Module {} is defined in both '{}' and '{}' workspace members.",
+                    module_name(&module),
+                    members[owner].name,
+                    member.name
+                ));
+            }
+        }
+        imports.push(member_imports);
+    }
+
+    let deps = imports
+        .iter()
+        .map(|imports| {
+            let mut deps = imports
+                .iter()
+                .filter_map(|import| owners.get(import).copied())
+                .collect::<Vec<_>>();
+            deps.sort();
+            deps.dedup();
+            deps
+        })
+        .collect::<Vec<_>>();
+
+    let mut order = Vec::with_capacity(members.len());
+    let mut visited = HashSet::new();
+    let mut path = HashSet::new();
+    for idx in 0..members.len() {
+        visit(idx, &members, &deps, &mut visited, &mut path, &mut order)?;
+    }
+
+    let mut members = members.into_iter().map(Some).collect::<Vec<_>>();
+    Ok(order
+        .into_iter()
+        .filter_map(|idx| members[idx].take())
+        .collect())
+}
+
+/// Depth-first topological sort of the members.
+fn visit(
+    idx: usize,
+    members: &[Member],
+    deps: &[Vec<usize>],
+    visited: &mut HashSet<usize>,
+    path: &mut HashSet<usize>,
+    order: &mut Vec<usize>,
+) -> Result<()> {
+    if visited.contains(&idx) {
+        return Ok(());
+    }
+    if !path.insert(idx) {
+        return Err(anyhow!(
+            "Cyclic dependency on the workspace member '{}'.",
+            members[idx].name
+        ));
+    }
+    for dep in &deps[idx] {
+        if *dep != idx {
+            visit(*dep, members, deps, visited, path, order)?;
+        }
+    }
+    path.remove(&idx);
+    visited.insert(idx);
+    order.push(idx);
+    Ok(())
+}
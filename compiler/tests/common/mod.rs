@@ -0,0 +1,6 @@
+use dvm_compiler::TempDir;
+
+/// Creates a temporary directory which is removed when the returned guard is dropped.
+pub fn temp_dir() -> TempDir {
+    TempDir::new().unwrap()
+}
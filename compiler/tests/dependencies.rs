@@ -0,0 +1,316 @@
+mod common;
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use libra::prelude::*;
+use tiny_keccak::{Hasher, Sha3};
+use dvm_compiler::compile;
+use dvm_compiler::manifest::{store_manifest, Dependency, Layout, MoveToml, MANIFEST};
+use dvm_compiler::dependence::lock::{bytecode_hash, MoveLock};
+use dvm_compiler::dependence::resolver::{parse_module_id, resolve, Dependencies};
+
+use common::temp_dir;
+
+fn manifest(address: &str, dependencies: Vec<(&str, Dependency)>) -> MoveToml {
+    let mut manifest = MoveToml::default();
+    manifest.package.account_address = Some(address.to_owned());
+    let mut layout = Layout::default();
+    layout.fill();
+    manifest.layout = Some(layout);
+    manifest.dependencies = Some(
+        dependencies
+            .into_iter()
+            .map(|(name, dep)| (name.to_owned(), dep))
+            .collect::<BTreeMap<_, _>>(),
+    );
+    manifest
+}
+
+fn module(address: &AccountAddress, code: &str) -> (ModuleId, Vec<u8>) {
+    let bytecode = compile(code, Some(*address)).unwrap();
+    let id = CompiledModule::deserialize(&bytecode).unwrap().self_id();
+    (id, bytecode)
+}
+
+#[test]
+fn test_lock_modules() {
+    let address = AccountAddress::random();
+    let (id, bytecode) = module(&address, "module M {}");
+    let mut loaded = HashMap::new();
+    loaded.insert(id.clone(), bytecode.clone());
+
+    let dependencies = Dependencies::default();
+    let lock = dependencies.lock(&MoveLock::default(), &loaded).unwrap();
+    assert_eq!(lock.module.len(), 1);
+    assert_eq!(lock.module[0].hash, bytecode_hash(&bytecode));
+    assert_eq!(dependencies.lock(&lock, &loaded).unwrap(), lock);
+
+    let (_, changed) = module(&address, "module M { public fun f() {} }");
+    loaded.insert(id, changed);
+    let err = dependencies.lock(&lock, &loaded).unwrap_err();
+    assert!(err.to_string().contains("does not match Move.lock"));
+}
+
+#[test]
+fn test_pinned_module() {
+    let (id, bytecode) = module(&AccountAddress::random(), "module M {}");
+    let mut loaded = HashMap::new();
+    loaded.insert(id.clone(), bytecode.clone());
+
+    let mut dependencies = Dependencies::default();
+    dependencies
+        .pins
+        .insert(id.clone(), bytecode_hash(&bytecode));
+    assert!(dependencies.lock(&MoveLock::default(), &loaded).is_ok());
+
+    dependencies.pins.insert(id, "00".to_owned());
+    assert!(dependencies.lock(&MoveLock::default(), &loaded).is_err());
+}
+
+#[test]
+fn test_unlocked_local_modules() {
+    let (id, bytecode) = module(&AccountAddress::random(), "module M {}");
+    let mut loaded = HashMap::new();
+    loaded.insert(id.clone(), bytecode);
+
+    let mut dependencies = Dependencies::default();
+    dependencies.unlocked.insert(id);
+    let lock = dependencies.lock(&MoveLock::default(), &loaded).unwrap();
+    assert!(lock.module.is_empty());
+}
+
+#[test]
+fn test_lock_file() {
+    let project = temp_dir();
+    let project_dir = project.path();
+    assert_eq!(MoveLock::read(project_dir).unwrap(), MoveLock::default());
+
+    let (id, bytecode) = module(&AccountAddress::random(), "module M {}");
+    let mut loaded = HashMap::new();
+    loaded.insert(id, bytecode);
+    let lock = MoveLock::new(vec![], &loaded);
+    lock.store(project_dir).unwrap();
+    assert_eq!(MoveLock::read(project_dir).unwrap(), lock);
+}
+
+#[test]
+fn test_verify_lock() {
+    let project = temp_dir();
+    let project_dir = project.path();
+    let (id, bytecode) = module(&AccountAddress::random(), "module M {}");
+    let mut loaded = HashMap::new();
+    loaded.insert(id, bytecode);
+    let lock = MoveLock::new(vec![], &loaded);
+
+    // projects without the lock file are not checked.
+    assert!(MoveLock::default().verify(project_dir, &lock).is_ok());
+
+    MoveLock::default().store(project_dir).unwrap();
+    let err = MoveLock::default().verify(project_dir, &lock).unwrap_err();
+    assert!(err.to_string().contains("Move.lock is out of date"));
+
+    lock.store(project_dir).unwrap();
+    assert!(MoveLock::read(project_dir)
+        .unwrap()
+        .verify(project_dir, &lock)
+        .is_ok());
+}
+
+#[test]
+fn test_resolve_vendored_bytecode() {
+    let project = temp_dir();
+    let project_dir = project.path();
+    let (id, bytecode) = module(&AccountAddress::random(), "module M {}");
+    fs::create_dir_all(project_dir.join("vendor")).unwrap();
+    fs::write(project_dir.join("vendor").join("m.mv"), &bytecode).unwrap();
+
+    let dep = Dependency {
+        bytecode: Some("vendor".to_owned()),
+        ..Default::default()
+    };
+    let manifest = manifest("0x1", vec![("M", dep)]);
+    let dependencies = resolve(project_dir, &manifest, &MoveLock::default()).unwrap();
+    assert_eq!(dependencies.modules.get(&id), Some(&bytecode));
+    assert!(dependencies.unlocked.is_empty());
+}
+
+#[test]
+fn test_resolve_local_package() {
+    let package = temp_dir();
+    let package_dir = package.path();
+    store_manifest(&package_dir.join(MANIFEST), manifest("0x2", vec![])).unwrap();
+    let module_dir = package_dir.join("src").join("modules");
+    fs::create_dir_all(&module_dir).unwrap();
+    fs::write(
+        module_dir.join("a.move"),
+        "module A { public fun a(): u64 { 1 } }",
+    )
+    .unwrap();
+
+    let project = temp_dir();
+    let project_dir = project.path();
+    let dep = Dependency {
+        path: Some(package_dir.to_string_lossy().to_string()),
+        ..Default::default()
+    };
+    let manifest = manifest("0x1", vec![("A", dep)]);
+    let dependencies = resolve(project_dir, &manifest, &MoveLock::default()).unwrap();
+
+    let id = parse_module_id("0x2::A").unwrap();
+    assert!(dependencies.modules.contains_key(&id));
+    assert!(dependencies.unlocked.contains(&id));
+}
+
+#[test]
+fn test_invalid_dependency() {
+    let project = temp_dir();
+    let project_dir = project.path();
+    let dep = Dependency {
+        path: Some("a".to_owned()),
+        bytecode: Some("b".to_owned()),
+        ..Default::default()
+    };
+    let manifest = manifest("0x1", vec![("A", dep)]);
+    assert!(resolve(project_dir, &manifest, &MoveLock::default()).is_err());
+}
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(&["-c", "user.name=test", "-c", "user.email=test@test"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap().trim().to_owned()
+}
+
+#[test]
+fn test_resolve_git_package() {
+    let repo = temp_dir();
+    let repo_dir = repo.path();
+    store_manifest(&repo_dir.join(MANIFEST), manifest("0x2", vec![])).unwrap();
+    let module_dir = repo_dir.join("src").join("modules");
+    fs::create_dir_all(&module_dir).unwrap();
+    git(repo_dir, &["init", "--quiet"]);
+    for (tag, code) in &[
+        ("v1", "module A { public fun a(): u64 { 1 } }"),
+        ("v2", "module A { public fun a(): u64 { 2 } }"),
+    ] {
+        fs::write(module_dir.join("a.move"), code).unwrap();
+        git(repo_dir, &["add", "."]);
+        git(repo_dir, &["commit", "--quiet", "-m", tag]);
+        git(repo_dir, &["tag", tag]);
+    }
+
+    let project = temp_dir();
+    let project_dir = project.path();
+    let git_manifest = |rev: &str| {
+        let dep = Dependency {
+            git: Some(repo_dir.to_string_lossy().to_string()),
+            rev: Some(rev.to_owned()),
+            ..Default::default()
+        };
+        manifest("0x1", vec![("A", dep)])
+    };
+
+    let dependencies = resolve(project_dir, &git_manifest("v1"), &MoveLock::default()).unwrap();
+    assert_eq!(dependencies.git.len(), 1);
+    assert_eq!(dependencies.git[0].rev, "v1");
+    assert_eq!(
+        dependencies.git[0].commit,
+        git(repo_dir, &["rev-parse", "v1"])
+    );
+    let lock = MoveLock::new(dependencies.git, &HashMap::new());
+
+    let dependencies = resolve(project_dir, &git_manifest("v1"), &lock).unwrap();
+    assert_eq!(
+        dependencies.git[0].commit,
+        git(repo_dir, &["rev-parse", "v1"])
+    );
+
+    let dependencies = resolve(project_dir, &git_manifest("v2"), &lock).unwrap();
+    assert_eq!(dependencies.git[0].rev, "v2");
+    assert_eq!(
+        dependencies.git[0].commit,
+        git(repo_dir, &["rev-parse", "v2"])
+    );
+}
+
+#[test]
+fn test_invalid_git_dependency() {
+    let project = temp_dir();
+    let project_dir = project.path();
+    for (url, rev) in &[
+        ("--upload-pack=touch pwned", "master"),
+        ("https://github.com/dfinance/move-stdlib", "--output=pwned"),
+        ("https://github.com/dfinance/move-stdlib", "master pwned"),
+    ] {
+        let dep = Dependency {
+            git: Some(url.to_string()),
+            rev: Some(rev.to_string()),
+            ..Default::default()
+        };
+        let manifest = manifest("0x1", vec![("A", dep)]);
+        let err = resolve(project_dir, &manifest, &MoveLock::default()).unwrap_err();
+        assert!(err.to_string().contains("invalid git"));
+    }
+    assert!(!project_dir.join("pwned").exists());
+}
+
+#[test]
+fn test_resolve_chain_module() {
+    let project = temp_dir();
+    let project_dir = project.path();
+    let (id, bytecode) = module(&AccountAddress::random(), "module M {}");
+    let chain_manifest = |module: &str, hash: &str| {
+        let dep = Dependency {
+            module: Some(module.to_owned()),
+            hash: Some(hash.to_owned()),
+            ..Default::default()
+        };
+        let mut manifest = manifest("0x1", vec![("M", dep)]);
+        manifest.package.blockchain_api = Some("http://127.0.0.1:1".to_owned());
+        manifest
+    };
+    let module_id = format!("0x{}::M", id.address());
+
+    // Cached chain modules are loaded without the node.
+    let mut digest = Sha3::v256();
+    digest.update(id.name().as_bytes());
+    digest.update(id.address().as_ref());
+    let mut name = [0; 32];
+    digest.finalize(&mut name);
+    let cache_dir = project_dir.join("target").join("deps");
+    fs::create_dir_all(&cache_dir).unwrap();
+    fs::write(cache_dir.join(hex::encode(&name)), &bytecode).unwrap();
+
+    let manifest = chain_manifest(&module_id, &bytecode_hash(&bytecode));
+    let dependencies = resolve(project_dir, &manifest, &MoveLock::default()).unwrap();
+    assert_eq!(dependencies.modules.get(&id), Some(&bytecode));
+    assert!(dependencies.pins.contains_key(&id));
+
+    let manifest = chain_manifest(&module_id, "00");
+    let err = resolve(project_dir, &manifest, &MoveLock::default()).unwrap_err();
+    assert!(err.to_string().contains("does not match the pinned hash"));
+
+    let manifest = chain_manifest("0x1::Unknown", "00");
+    assert!(resolve(project_dir, &manifest, &MoveLock::default()).is_err());
+
+    let mut manifest = chain_manifest(&module_id, &bytecode_hash(&bytecode));
+    manifest.package.blockchain_api = None;
+    assert!(resolve(project_dir, &manifest, &MoveLock::default()).is_err());
+}
+
+#[test]
+fn test_parse_module_id() {
+    let id = parse_module_id("0x1::Coins").unwrap();
+    assert_eq!(id.address(), &CORE_CODE_ADDRESS);
+    assert_eq!(id.name().as_str(), "Coins");
+
+    assert!(parse_module_id("0x1").is_err());
+    assert!(parse_module_id("0x1::Coins::ETH").is_err());
+}
@@ -0,0 +1,198 @@
+mod common;
+
+use std::collections::HashMap;
+use std::fs;
+
+use ds::MockDataSource;
+use libra::prelude::*;
+use dvm_compiler::Compiler;
+use dvm_compiler::builder::Builder;
+use dvm_compiler::dependence::loader::{Loader, ZeroLoader};
+use dvm_compiler::diagnostics::{diagnostics, position, MessageFormat, Severity};
+use dvm_compiler::manifest::{Layout, MoveToml};
+use dvm_compiler::preprocessor::preprocess;
+
+use common::temp_dir;
+
+const BECH32: &str = "wallet1me0cdn52672y7feddy7tgcj6j4dkzq2su745vh";
+const LIBRA: &str = "0xde5f86ce8ad7944f272d693cb4625a955b610150";
+
+#[test]
+fn test_offsets_after_replacement() {
+    let source = format!("let a = {}; let b = 1;", BECH32);
+    let (code, offsets) = preprocess(&source);
+    assert_eq!(code, format!("let a = {}; let b = 1;", LIBRA));
+    assert!(!offsets.is_empty());
+
+    assert_eq!(offsets.to_original(4), 4);
+    assert_eq!(offsets.to_original(8), 8);
+    let b = code.find("b =").unwrap();
+    assert_eq!(offsets.to_original(b), source.find("b =").unwrap());
+    assert_eq!(offsets.to_original(code.len()), source.len());
+}
+
+#[test]
+fn test_offsets_inside_replacement() {
+    let (_, offsets) = preprocess(BECH32);
+    assert_eq!(offsets.to_original(0), 0);
+    assert_eq!(offsets.to_original(LIBRA.len() - 1), BECH32.len() - 1);
+    assert_eq!(offsets.to_original(LIBRA.len()), BECH32.len());
+}
+
+#[test]
+fn test_offsets_without_replacement() {
+    let (code, offsets) = preprocess("module M {}");
+    assert_eq!(code, "module M {}");
+    assert!(offsets.is_empty());
+    assert_eq!(offsets.to_original(7), 7);
+}
+
+#[test]
+fn test_position() {
+    let text = "first\nвторая\nthird";
+    let pos = position(text, text.find("third").unwrap());
+    assert_eq!((pos.line, pos.column), (3, 1));
+    let pos = position(text, text.find("рая").unwrap());
+    assert_eq!((pos.line, pos.column), (2, 4));
+    assert_eq!(position(text, 0).line, 1);
+}
+
+#[test]
+fn test_message_format() {
+    assert_eq!(
+        "json".parse::<MessageFormat>().unwrap(),
+        MessageFormat::Json
+    );
+    assert_eq!(
+        "Human".parse::<MessageFormat>().unwrap(),
+        MessageFormat::Human
+    );
+    assert!("xml".parse::<MessageFormat>().is_err());
+}
+
+#[test]
+fn test_compilation_diagnostics() {
+    let source = format!(
+        "script {{\n    fun main() {{\n        let _ = {}; let a: u128;\n    }}\n}}",
+        BECH32
+    );
+    let mut source_map = HashMap::new();
+    source_map.insert("main".to_owned(), source.clone());
+
+    let compiler = Compiler::new(MockDataSource::new());
+    let err = compiler
+        .compile_source_map(source_map, Some(AccountAddress::random()))
+        .unwrap_err();
+    let diagnostics = diagnostics(&err);
+    let diagnostic = diagnostics
+        .iter()
+        .find(|diagnostic| diagnostic.message.contains("Unused local 'a'"))
+        .unwrap();
+    assert_eq!(diagnostic.severity, Severity::Error);
+    assert_eq!(diagnostic.file.as_deref(), Some("main"));
+
+    let span = diagnostic.span.unwrap();
+    assert_eq!(&source[span.start.byte..span.end.byte], "a");
+    assert_eq!(span.start.line, 3);
+    assert_eq!(
+        span.start.column,
+        source.lines().nth(2).unwrap().find("a:").unwrap() + 1
+    );
+}
+
+#[test]
+fn test_offsets_with_multiple_addresses_per_line() {
+    let second = "wallet1zhw7vn8stj4zu7jgjalyunyhn8462pwnu0v252";
+    let source = format!(
+        "use {}::A; use 0x1::B; use {}::C; let x = 1;",
+        BECH32, second
+    );
+    let (code, offsets) = preprocess(&source);
+    assert_eq!(
+        code,
+        format!(
+            "use {}::A; use 0x1::B; use 0x15dde64cf05caa2e7a48977e4e4c9799eba505d3::C; let x = 1;",
+            LIBRA
+        )
+    );
+
+    for fragment in &["::A", "use 0x1::B", "::C", "x = 1"] {
+        assert_eq!(
+            offsets.to_original(code.find(fragment).unwrap()),
+            source.find(fragment).unwrap()
+        );
+    }
+    let second_start = code.find("0x15dde64c").unwrap();
+    assert_eq!(
+        offsets.to_original(second_start),
+        source.find(second).unwrap()
+    );
+}
+
+#[test]
+fn test_errors_after_multiple_addresses_per_line() {
+    let source = format!(
+        "script {{\n    fun main() {{\n        let _ = {}; let _ = {}; let a: u128;\n    }}\n}}",
+        BECH32, BECH32
+    );
+    let mut source_map = HashMap::new();
+    source_map.insert("main".to_owned(), source.clone());
+
+    let compiler = Compiler::new(MockDataSource::new());
+    let err = compiler
+        .compile_source_map(source_map, Some(AccountAddress::random()))
+        .unwrap_err();
+    let rendered = err.to_string();
+    assert!(rendered.contains("main"));
+    assert!(rendered.contains(BECH32));
+    assert!(!rendered.contains(LIBRA));
+
+    let diagnostic = diagnostics(&err)
+        .into_iter()
+        .find(|diagnostic| diagnostic.message.contains("Unused local 'a'"))
+        .unwrap();
+    let span = diagnostic.span.unwrap();
+    assert_eq!(&source[span.start.byte..span.end.byte], "a");
+    assert_eq!(
+        span.start.column,
+        source.lines().nth(2).unwrap().find("a:").unwrap() + 1
+    );
+}
+
+#[test]
+fn test_check_diagnostics() {
+    let dir = temp_dir();
+    let scripts_dir = dir.path().join("src").join("scripts");
+    fs::create_dir_all(&scripts_dir).unwrap();
+    let source = format!(
+        "script {{\n    fun main() {{\n        let _ = {}; let a: u128;\n    }}\n}}",
+        BECH32
+    );
+    fs::write(scripts_dir.join("main.move"), &source).unwrap();
+
+    let mut manifest = MoveToml::default();
+    manifest.package.account_address = Some("0x2".to_owned());
+    let mut layout = Layout::default();
+    layout.fill();
+    manifest.layout = Some(layout);
+
+    let loader: Option<Loader<ZeroLoader>> = None;
+    let builder = Builder::new(dir.path(), manifest, &loader, false, false);
+    builder.init_build_layout().unwrap();
+    let sources = builder
+        .preprocess_source_map(builder.make_source_map().unwrap())
+        .unwrap();
+    let err = builder.check(sources, vec![]).unwrap_err();
+
+    let diagnostic = diagnostics(&err)
+        .into_iter()
+        .find(|diagnostic| diagnostic.message.contains("Unused local 'a'"))
+        .unwrap();
+    assert!(diagnostic.file.unwrap().ends_with("main.move"));
+    let span = diagnostic.span.unwrap();
+    assert_eq!(&source[span.start.byte..span.end.byte], "a");
+    assert_eq!(
+        span.start.column,
+        source.lines().nth(2).unwrap().find("a:").unwrap() + 1
+    );
+}
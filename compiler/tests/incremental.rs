@@ -0,0 +1,230 @@
+mod common;
+
+use std::collections::HashMap;
+use std::fs;
+
+use libra::prelude::*;
+use dvm_compiler::builder::Builder;
+use dvm_compiler::compile;
+use dvm_compiler::dependence::loader::{Loader, ZeroLoader};
+use dvm_compiler::incremental;
+use dvm_compiler::manifest::{Layout, MoveToml};
+use dvm_compiler::TempDir;
+
+use common::temp_dir;
+
+struct Project {
+    dir: TempDir,
+    manifest: MoveToml,
+}
+
+impl Project {
+    fn new() -> Project {
+        let dir = temp_dir();
+        fs::create_dir_all(dir.path().join("src").join("modules")).unwrap();
+        fs::create_dir_all(dir.path().join("src").join("scripts")).unwrap();
+
+        let mut manifest = MoveToml::default();
+        manifest.package.account_address = Some("0x2".to_owned());
+        let mut layout = Layout::default();
+        layout.fill();
+        manifest.layout = Some(layout);
+        Project { dir, manifest }
+    }
+
+    fn module(&self, file: &str, code: &str) {
+        fs::write(self.dir.path().join("src").join("modules").join(file), code).unwrap();
+    }
+
+    fn script(&self, file: &str, code: &str) {
+        fs::write(self.dir.path().join("src").join("scripts").join(file), code).unwrap();
+    }
+
+    /// Builds the project and returns the recompiled files.
+    fn build(&self, dependencies: &[Vec<u8>]) -> Vec<String> {
+        let loader = if dependencies.is_empty() {
+            None
+        } else {
+            let modules = dependencies
+                .iter()
+                .map(|bytecode| {
+                    let id = CompiledModule::deserialize(bytecode).unwrap().self_id();
+                    (id, bytecode.to_owned())
+                })
+                .collect::<HashMap<_, _>>();
+            Some(Loader::<ZeroLoader>::with_modules_only(modules))
+        };
+
+        let builder = Builder::new(
+            self.dir.path(),
+            self.manifest.clone(),
+            &loader,
+            false,
+            false,
+        );
+        builder.init_build_layout().unwrap();
+        let sources = builder
+            .preprocess_source_map(builder.make_source_map().unwrap())
+            .unwrap();
+        let bytecode_map = builder.load_dependencies(&sources).unwrap();
+        let mut recompiled = incremental::compile(&builder, sources, bytecode_map)
+            .unwrap()
+            .recompiled;
+        recompiled.sort();
+        recompiled
+    }
+}
+
+fn files(files: &[&str]) -> Vec<String> {
+    files.iter().map(|file| file.to_string()).collect()
+}
+
+#[test]
+fn test_unchanged_sources() {
+    let project = Project::new();
+    project.module("a.move", "module A { public fun a(): u64 { 1 } }");
+    project.module(
+        "b.move",
+        "module B { use 0x2::A; public fun b(): u64 { A::a() } }",
+    );
+    project.script("main.move", "script { use 0x2::B; fun main() { B::b(); } }");
+
+    assert_eq!(
+        project.build(&[]),
+        files(&["modules/a.move", "modules/b.move", "scripts/main.move"])
+    );
+    assert!(project.build(&[]).is_empty());
+}
+
+#[test]
+fn test_body_change() {
+    let project = Project::new();
+    project.module("a.move", "module A { public fun a(): u64 { 1 } }");
+    project.module(
+        "b.move",
+        "module B { use 0x2::A; public fun b(): u64 { A::a() } }",
+    );
+    project.build(&[]);
+
+    project.module("a.move", "module A { public fun a(): u64 { 2 } }");
+    assert_eq!(project.build(&[]), files(&["modules/a.move"]));
+}
+
+#[test]
+fn test_transitive_interface_change() {
+    let project = Project::new();
+    project.module("a.move", "module A { public fun a(): u64 { 1 } }");
+    project.module(
+        "b.move",
+        "module B { use 0x2::A; public fun b(): u64 { A::a() } }",
+    );
+    project.module(
+        "c.move",
+        "module C { use 0x2::B; public fun c(): u64 { B::b() } }",
+    );
+    project.module("d.move", "module D { public fun d(): u64 { 1 } }");
+    project.build(&[]);
+
+    project.module(
+        "a.move",
+        "module A { public fun a(): u64 { 1 } public fun a2(): u64 { 2 } }",
+    );
+    assert_eq!(
+        project.build(&[]),
+        files(&["modules/a.move", "modules/b.move", "modules/c.move"])
+    );
+}
+
+#[test]
+fn test_dependency_change() {
+    let dep_address = AccountAddress::from_hex_literal("0x3").unwrap();
+    let dep = compile(
+        "module Dep { public fun f(): u64 { 1 } }",
+        Some(dep_address),
+    )
+    .unwrap();
+
+    let project = Project::new();
+    project.module(
+        "a.move",
+        "module A { use 0x3::Dep; public fun a(): u64 { Dep::f() } }",
+    );
+    project.module("b.move", "module B { public fun b(): u64 { 1 } }");
+    project.build(&[dep]);
+
+    let body_changed = compile(
+        "module Dep { public fun f(): u64 { 2 } }",
+        Some(dep_address),
+    )
+    .unwrap();
+    assert!(project.build(&[body_changed]).is_empty());
+
+    let interface_changed = compile(
+        "module Dep { public fun f(): u64 { 2 } public fun g() {} }",
+        Some(dep_address),
+    )
+    .unwrap();
+    assert_eq!(
+        project.build(&[interface_changed]),
+        files(&["modules/a.move"])
+    );
+}
+
+#[test]
+fn test_address_change() {
+    let mut project = Project::new();
+    project.module("a.move", "module A { public fun a(): u64 { 1 } }");
+    project.module("b.move", "module B { public fun b(): u64 { 1 } }");
+    project.build(&[]);
+
+    project.manifest.package.account_address = Some("0x4".to_owned());
+    assert_eq!(
+        project.build(&[]),
+        files(&["modules/a.move", "modules/b.move"])
+    );
+}
+
+#[test]
+fn test_dependent_files_change() {
+    let project = Project::new();
+    project.module("a.move", "module A { public fun a(): u64 { 1 } }");
+    project.module(
+        "b.move",
+        "module B { use 0x2::A; public fun b(): u64 { A::a() } }",
+    );
+    project.module("c.move", "module C { public fun c(): u64 { 1 } }");
+    project.build(&[]);
+
+    project.module(
+        "a.move",
+        "module A { public fun a(): u64 { 1 } public fun a2(): u64 { 2 } }",
+    );
+    project.module(
+        "b.move",
+        "module B { use 0x2::A; public fun b(): u64 { A::a2() } }",
+    );
+    assert_eq!(
+        project.build(&[]),
+        files(&["modules/a.move", "modules/b.move"])
+    );
+    assert!(project.build(&[]).is_empty());
+}
+
+#[test]
+fn test_dependency_moved_to_sources() {
+    let code = "module B { public fun b(): u64 { 1 } }";
+    let dep = compile(code, Some(AccountAddress::from_hex_literal("0x2").unwrap())).unwrap();
+
+    let project = Project::new();
+    project.module(
+        "a.move",
+        "module A { use 0x2::B; public fun a(): u64 { B::b() } }",
+    );
+    assert_eq!(project.build(&[dep]), files(&["modules/a.move"]));
+
+    project.module("b.move", code);
+    assert_eq!(
+        project.build(&[]),
+        files(&["modules/a.move", "modules/b.move"])
+    );
+}
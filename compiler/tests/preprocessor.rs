@@ -0,0 +1,123 @@
+use std::collections::{BTreeMap, HashMap};
+
+use ds::MockDataSource;
+use libra::prelude::*;
+use dvm_compiler::Compiler;
+use dvm_compiler::diagnostics::diagnostics;
+use dvm_compiler::manifest::{MoveToml, Profile};
+use dvm_compiler::preprocessor::{preprocess_with_names, Names};
+
+const BECH32: &str = "wallet1me0cdn52672y7feddy7tgcj6j4dkzq2su745vh";
+const LIBRA: &str = "0xde5f86ce8ad7944f272d693cb4625a955b610150";
+
+fn map(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_substitute_names() {
+    let names = Names::new(
+        &map(&[("Treasury", BECH32), ("Std", "0x1")]),
+        &map(&[("MAX_SUPPLY", "1000u128")]),
+    )
+    .unwrap();
+    let source = "use @Std::Coins; use @Treasury::Vault; fun max(): u128 { @MAX_SUPPLY }";
+    let (code, offsets, unknown) = preprocess_with_names(source, &names);
+    assert!(unknown.is_empty());
+    assert_eq!(
+        code,
+        format!(
+            "use 0x1::Coins; use {}::Vault; fun max(): u128 {{ 1000u128 }}",
+            LIBRA
+        )
+    );
+    assert_eq!(
+        offsets.to_original(code.find("::Vault").unwrap()),
+        source.find("::Vault").unwrap()
+    );
+    assert_eq!(
+        offsets.to_original(code.find(" }").unwrap()),
+        source.find(" }").unwrap()
+    );
+}
+
+#[test]
+fn test_unknown_names() {
+    let names = Names::new(&map(&[("Std", "0x1")]), &BTreeMap::new()).unwrap();
+    let source = "use @Std::Coins; use @Oracle::Price; // @Commented\nlet s = b\"@Quoted\";";
+    let (code, _, unknown) = preprocess_with_names(source, &names);
+    assert!(code.contains("@Oracle::Price"));
+    assert_eq!(unknown.len(), 1);
+    assert_eq!(unknown[0].name, "Oracle");
+    assert_eq!(&source[unknown[0].range.clone()], "@Oracle");
+}
+
+#[test]
+fn test_invalid_names() {
+    assert!(Names::new(&map(&[("Treasury", "wallet1invalid")]), &BTreeMap::new()).is_err());
+    assert!(Names::new(&map(&[("1Treasury", "0x1")]), &BTreeMap::new()).is_err());
+    assert!(Names::new(&map(&[("Std", "0x1")]), &map(&[("Std", "1")])).is_err());
+}
+
+#[test]
+fn test_apply_profile() {
+    let mut manifest = MoveToml::default();
+    manifest.addresses = Some(map(&[("Treasury", "0x2"), ("Std", "0x1")]));
+    let mut profiles = BTreeMap::new();
+    profiles.insert(
+        "testnet".to_owned(),
+        Profile {
+            addresses: Some(map(&[("Treasury", BECH32)])),
+            constants: Some(map(&[("FEE", "10")])),
+        },
+    );
+    manifest.profile = Some(profiles);
+
+    assert!(manifest.clone().apply_profile("mainnet").is_err());
+    manifest.apply_profile("testnet").unwrap();
+    assert_eq!(
+        manifest.addresses,
+        Some(map(&[("Treasury", BECH32), ("Std", "0x1")]))
+    );
+    assert_eq!(manifest.constants, Some(map(&[("FEE", "10")])));
+}
+
+#[test]
+fn test_compile_with_named_addresses() {
+    let ds = MockDataSource::new();
+    let compiler = Compiler::new(ds.clone());
+    let treasury = AccountAddress::from_hex_literal(LIBRA).unwrap();
+    ds.publish_module(
+        compiler
+            .compile("module Vault { public fun f() {} }", Some(treasury))
+            .unwrap(),
+    )
+    .unwrap();
+
+    let mut source_map = HashMap::new();
+    source_map.insert(
+        "main".to_owned(),
+        "script { use @Treasury::Vault; fun main() { Vault::f(); } }".to_owned(),
+    );
+    compiler
+        .compile_source_map_with_addresses(
+            source_map.clone(),
+            Some(AccountAddress::random()),
+            map(&[("Treasury", BECH32)]),
+        )
+        .unwrap();
+
+    let err = compiler
+        .compile_source_map(source_map, Some(AccountAddress::random()))
+        .unwrap_err();
+    let diagnostics = diagnostics(&err);
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0].message.contains("Unknown name '@Treasury'"));
+    assert_eq!(diagnostics[0].file.as_deref(), Some("main"));
+    let span = diagnostics[0].span.unwrap();
+    assert_eq!((span.start.line, span.start.column), (1, 14));
+    assert_eq!(span.end.column, 23);
+}
@@ -0,0 +1,145 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use libra::prelude::*;
+use dvm_compiler::manifest::{store_manifest, MoveToml, Workspace, MANIFEST};
+use dvm_compiler::dependence::resolver::parse_module_id;
+use dvm_compiler::workspace::{build, members};
+
+use common::temp_dir;
+
+fn package(workspace_dir: &Path, name: &str, address: &str, modules: &[(&str, &str)]) {
+    let package_dir = workspace_dir.join(name);
+    let module_dir = package_dir.join("src").join("modules");
+    fs::create_dir_all(&module_dir).unwrap();
+
+    let mut manifest = MoveToml::default();
+    manifest.package.name = Some(name.to_owned());
+    manifest.package.account_address = Some(address.to_owned());
+    store_manifest(&package_dir.join(MANIFEST), manifest).unwrap();
+
+    for (file, code) in modules {
+        fs::write(module_dir.join(file), code).unwrap();
+    }
+}
+
+fn workspace(members: &[&str]) -> MoveToml {
+    let mut manifest = MoveToml::default();
+    manifest.workspace = Some(Workspace {
+        members: members.iter().map(|member| member.to_string()).collect(),
+        target_dir: None,
+    });
+    manifest
+}
+
+#[test]
+fn test_members_order() {
+    let dir = temp_dir();
+    let workspace_dir = dir.path();
+    package(
+        workspace_dir,
+        "market",
+        "0x3",
+        &[(
+            "market.move",
+            "module Market { use 0x2::Coins; public fun price(): u64 { Coins::value() } }",
+        )],
+    );
+    package(
+        workspace_dir,
+        "coins",
+        "0x2",
+        &[(
+            "coins.move",
+            "module Coins { public fun value(): u64 { 1 } }",
+        )],
+    );
+
+    let members = members(workspace_dir, &workspace(&["market", "coins"])).unwrap();
+    let names = members
+        .iter()
+        .map(|member| member.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["coins", "market"]);
+}
+
+#[test]
+fn test_build_workspace() {
+    let dir = temp_dir();
+    let workspace_dir = dir.path();
+    package(
+        workspace_dir,
+        "market",
+        "0x3",
+        &[(
+            "market.move",
+            "module Market { use 0x2::Coins; public fun price(): u64 { Coins::value() } }",
+        )],
+    );
+    package(
+        workspace_dir,
+        "coins",
+        "0x2",
+        &[(
+            "coins.move",
+            "module Coins { public fun value(): u64 { 1 } }",
+        )],
+    );
+
+    let built = build(workspace_dir, &workspace(&["market", "coins"]), true).unwrap();
+    assert_eq!(built.len(), 2);
+    assert!(built[0]
+        .modules
+        .contains_key(&parse_module_id("0x2::Coins").unwrap()));
+    assert!(built[1]
+        .modules
+        .contains_key(&parse_module_id("0x3::Market").unwrap()));
+
+    let artifacts = workspace_dir.join("target").join("artifacts");
+    for member in &["coins", "market"] {
+        let modules = fs::read_dir(artifacts.join(member).join("modules"))
+            .unwrap()
+            .count();
+        assert_eq!(modules, 1);
+    }
+}
+
+#[test]
+fn test_cyclic_members() {
+    let dir = temp_dir();
+    let workspace_dir = dir.path();
+    package(
+        workspace_dir,
+        "a",
+        "0x2",
+        &[(
+            "a.move",
+            "module A { use 0x3::B; public fun a() { B::b() } }",
+        )],
+    );
+    package(
+        workspace_dir,
+        "b",
+        "0x3",
+        &[(
+            "b.move",
+            "module B { use 0x2::A; public fun b() { A::a() } }",
+        )],
+    );
+
+    let err = members(workspace_dir, &workspace(&["a", "b"])).unwrap_err();
+    assert!(err.to_string().contains("Cyclic dependency"));
+}
+
+#[test]
+fn test_duplicate_module() {
+    let dir = temp_dir();
+    let workspace_dir = dir.path();
+    package(workspace_dir, "a", "0x2", &[("m.move", "module M {}")]);
+    package(workspace_dir, "b", "0x2", &[("m.move", "module M {}")]);
+
+    let err = members(workspace_dir, &workspace(&["a", "b"])).unwrap_err();
+    assert!(err.to_string().contains("is defined in both"));
+}
@@ -1,4 +1,6 @@
-use crate::{DataSource, RemoveModule, Oracle, Balance, GetCurrencyInfo, CurrencyInfo};
+use crate::{
+    DataSource, RemoveModule, Oracle, Balance, GetCurrencyInfo, CurrencyInfo, Price, SupplyRecord,
+};
 use std::collections::HashSet;
 use libra::prelude::*;
 use anyhow::Error;
@@ -64,7 +66,7 @@ where
 }
 
 impl<D: DataSource> Oracle for BlackListDataSource<D> {
-    fn get_price(&self, currency_1: String, currency_2: String) -> Result<Option<u128>, Error> {
+    fn get_price(&self, currency_1: String, currency_2: String) -> Result<Option<Price>, Error> {
         self.inner.get_price(currency_1, currency_2)
     }
 }
@@ -79,6 +81,18 @@ impl<D: DataSource> GetCurrencyInfo for BlackListDataSource<D> {
     fn get_currency_info(&self, ticker: String) -> Result<Option<CurrencyInfo>, Error> {
         self.inner.get_currency_info(ticker)
     }
+
+    fn get_currency_list(&self) -> Result<Vec<(String, CurrencyInfo)>, Error> {
+        self.inner.get_currency_list()
+    }
+
+    fn get_supply_history(
+        &self,
+        ticker: String,
+        height: u64,
+    ) -> Result<Option<Vec<SupplyRecord>>, Error> {
+        self.inner.get_supply_history(ticker, height)
+    }
 }
 
 impl<D> DataSource for BlackListDataSource<D> where D: DataSource {}
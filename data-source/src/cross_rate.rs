@@ -0,0 +1,261 @@
+use anyhow::Error;
+
+use libra::prelude::*;
+
+use crate::{
+    Balance, CurrencyInfo, DataSource, GetCurrencyInfo, Oracle, Price, RemoveModule, SupplyRecord,
+};
+
+/// Base currency used by default.
+pub const DEFAULT_BASE_CURRENCY: &str = "XFI";
+/// Number of price decimals used by default. Matches the max decimals of the stdlib `Math` module.
+pub const DEFAULT_DECIMALS: u8 = 18;
+/// Max number of price decimals. `10^(2 * decimals)` must fit into `u128`.
+const MAX_DECIMALS: u8 = 18;
+
+/// Cross-rate resolution settings.
+///
+/// Resolution changes prices visible to the scripts,
+/// so all nodes of the network must use the same settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrossRateConfig {
+    base: String,
+    decimals: u8,
+}
+
+impl CrossRateConfig {
+    /// Create a new config with `base` currency and prices with `decimals` decimals.
+    pub fn new(base: &str, decimals: u8) -> Result<CrossRateConfig, Error> {
+        ensure!(
+            decimals <= MAX_DECIMALS,
+            "Price decimals must be in the range from 0 to {}",
+            MAX_DECIMALS
+        );
+        ensure!(!base.is_empty(), "Base currency must not be empty");
+        Ok(CrossRateConfig {
+            base: base.to_uppercase(),
+            decimals,
+        })
+    }
+
+    /// Returns base currency ticker.
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    /// Returns number of price decimals.
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    /// Returns fixed-point one.
+    fn one(&self) -> u128 {
+        10u128.pow(self.decimals as u32)
+    }
+}
+
+impl Default for CrossRateConfig {
+    fn default() -> Self {
+        CrossRateConfig {
+            base: DEFAULT_BASE_CURRENCY.to_owned(),
+            decimals: DEFAULT_DECIMALS,
+        }
+    }
+}
+
+/// Wrapper for data source which resolves missing oracle prices from the known ones.
+///
+/// If the pair `(currency_1, currency_2)` is not found, the price is resolved as:
+/// 1. the inverse of `(currency_2, currency_1)`;
+/// 2. the cross rate via the base currency: `(currency_1, base) * (base, currency_2)`,
+///    where each leg is either a direct or an inverse price.
+///
+/// Prices are fixed-point numbers with `decimals` decimals, every operation is rounded down.
+/// The timestamp of a resolved price is the timestamp of its oldest component,
+/// it is unknown if the timestamp of any component is unknown.
+/// Resolution is disabled if config is not passed.
+#[derive(Debug, Clone)]
+pub struct CrossRateDataSource<D>
+where
+    D: DataSource,
+{
+    inner: D,
+    config: Option<CrossRateConfig>,
+}
+
+impl<D> CrossRateDataSource<D>
+where
+    D: DataSource,
+{
+    /// Create a new CrossRateDataSource with DataSource.
+    pub fn new(inner: D, config: Option<CrossRateConfig>) -> CrossRateDataSource<D> {
+        CrossRateDataSource { inner, config }
+    }
+
+    /// Returns direct or inverse price.
+    fn direct_or_inverse(
+        &self,
+        config: &CrossRateConfig,
+        currency_1: &str,
+        currency_2: &str,
+    ) -> Result<Option<Price>, Error> {
+        if let Some(price) = self
+            .inner
+            .get_price(currency_1.to_owned(), currency_2.to_owned())?
+        {
+            return Ok(Some(price));
+        }
+
+        let one = config.one();
+        Ok(self
+            .inner
+            .get_price(currency_2.to_owned(), currency_1.to_owned())?
+            .and_then(|price| {
+                mul_div(one, one, price.price).map(|value| Price {
+                    price: value,
+                    timestamp: price.timestamp,
+                })
+            }))
+    }
+
+    /// Returns resolved price.
+    fn resolve(
+        &self,
+        config: &CrossRateConfig,
+        currency_1: &str,
+        currency_2: &str,
+    ) -> Result<Option<Price>, Error> {
+        if let Some(price) = self.direct_or_inverse(config, currency_1, currency_2)? {
+            return Ok(Some(price));
+        }
+
+        let base = config.base();
+        if currency_1 == currency_2 || currency_1 == base || currency_2 == base {
+            return Ok(None);
+        }
+
+        let first = match self.direct_or_inverse(config, currency_1, base)? {
+            Some(price) => price,
+            None => return Ok(None),
+        };
+        let second = match self.direct_or_inverse(config, base, currency_2)? {
+            Some(price) => price,
+            None => return Ok(None),
+        };
+
+        Ok(
+            mul_div(first.price, second.price, config.one()).map(|value| Price {
+                price: value,
+                timestamp: first.timestamp.min(second.timestamp),
+            }),
+        )
+    }
+}
+
+impl<D> RemoteCache for CrossRateDataSource<D>
+where
+    D: DataSource,
+{
+    fn get_module(&self, module_id: &ModuleId) -> VMResult<Option<Vec<u8>>> {
+        self.inner.get_module(module_id)
+    }
+
+    fn get_resource(
+        &self,
+        address: &AccountAddress,
+        tag: &StructTag,
+    ) -> PartialVMResult<Option<Vec<u8>>> {
+        self.inner.get_resource(address, tag)
+    }
+}
+
+impl<D: DataSource> Oracle for CrossRateDataSource<D> {
+    fn get_price(&self, currency_1: String, currency_2: String) -> Result<Option<Price>, Error> {
+        match &self.config {
+            Some(config) => self.resolve(config, &currency_1, &currency_2),
+            None => self.inner.get_price(currency_1, currency_2),
+        }
+    }
+}
+
+impl<D: DataSource> Balance for CrossRateDataSource<D> {
+    fn get_balance(&self, address: AccountAddress, ticker: String) -> Result<Option<u128>, Error> {
+        self.inner.get_balance(address, ticker)
+    }
+}
+
+impl<D: DataSource> GetCurrencyInfo for CrossRateDataSource<D> {
+    fn get_currency_info(&self, ticker: String) -> Result<Option<CurrencyInfo>, Error> {
+        self.inner.get_currency_info(ticker)
+    }
+
+    fn get_currency_list(&self) -> Result<Vec<(String, CurrencyInfo)>, Error> {
+        self.inner.get_currency_list()
+    }
+
+    fn get_supply_history(
+        &self,
+        ticker: String,
+        height: u64,
+    ) -> Result<Option<Vec<SupplyRecord>>, Error> {
+        self.inner.get_supply_history(ticker, height)
+    }
+}
+
+impl<D> DataSource for CrossRateDataSource<D> where D: DataSource {}
+
+impl<D> RemoveModule for CrossRateDataSource<D>
+where
+    D: DataSource,
+{
+    fn remove_module(&self, module_id: &ModuleId) {
+        self.inner.remove_module(module_id)
+    }
+}
+
+/// Returns `a * b / c` rounded down.
+/// Returns `None` if `c` is zero or the result does not fit into `u128`.
+pub fn mul_div(a: u128, b: u128, c: u128) -> Option<u128> {
+    if c == 0 {
+        return None;
+    }
+    if let Some(product) = a.checked_mul(b) {
+        return Some(product / c);
+    }
+
+    let (hi, lo) = full_mul(a, b);
+    if hi >= c {
+        return None;
+    }
+
+    // Long division of the 256-bit product.
+    let mut rem = hi;
+    let mut quot = 0u128;
+    for i in (0..128).rev() {
+        let carry = rem >> 127;
+        rem = (rem << 1) | ((lo >> i) & 1);
+        quot <<= 1;
+        if carry == 1 || rem >= c {
+            rem = rem.wrapping_sub(c);
+            quot |= 1;
+        }
+    }
+    Some(quot)
+}
+
+/// Returns 256-bit product of `a` and `b` as `(hi, lo)`.
+fn full_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_hi, a_lo) = (a >> 64, a & MASK);
+    let (b_hi, b_lo) = (b >> 64, b & MASK);
+
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+
+    let mid = (lo_lo >> 64) + (hi_lo & MASK) + (lo_hi & MASK);
+    let lo = (lo_lo & MASK) | (mid << 64);
+    let hi = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (mid >> 64);
+    (hi, lo)
+}
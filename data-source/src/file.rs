@@ -0,0 +1,536 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Error;
+use serde::{Deserialize, Serialize};
+
+use libra::prelude::*;
+
+use crate::{
+    Balance, CurrencyInfo, DataSource, GetCurrencyInfo, Oracle, Price, RemoveModule, SupplyRecord,
+};
+
+/// `DataSource` backed by a snapshot file on disk.
+///
+/// The whole state is kept in memory; `save` writes it back to the snapshot file.
+#[derive(Debug, Clone)]
+pub struct FileDataSource {
+    path: Arc<PathBuf>,
+    data: Arc<Mutex<HashMap<AccessPath, Vec<u8>>>>,
+    oracle: Arc<Mutex<HashMap<(String, String), Price>>>,
+    native_balance: Arc<Mutex<HashMap<(AccountAddress, String), u128>>>,
+    coin_info: Arc<Mutex<HashMap<String, CurrencyInfo>>>,
+    supply_history: Arc<Mutex<HashMap<String, Vec<SupplyRecord>>>>,
+    block: Arc<Mutex<BlockEntry>>,
+}
+
+impl FileDataSource {
+    /// Opens the snapshot file by `path`.
+    /// Creates an empty data source if the file does not exist yet.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<FileDataSource, Error> {
+        let path = path.as_ref();
+        let snapshot = if path.exists() {
+            Snapshot::load(path)?
+        } else {
+            Snapshot::default()
+        };
+        FileDataSource::with_snapshot(path, snapshot)
+    }
+
+    /// Creates a data source with `snapshot` state which will be saved by `path`.
+    pub fn with_snapshot<P: AsRef<Path>>(
+        path: P,
+        snapshot: Snapshot,
+    ) -> Result<FileDataSource, Error> {
+        let ds = FileDataSource {
+            path: Arc::new(path.as_ref().to_owned()),
+            data: Default::default(),
+            oracle: Default::default(),
+            native_balance: Default::default(),
+            coin_info: Default::default(),
+            supply_history: Default::default(),
+            block: Default::default(),
+        };
+        ds.apply_snapshot(snapshot)?;
+        Ok(ds)
+    }
+
+    /// Returns the snapshot file path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Writes the current state to the snapshot file.
+    pub fn save(&self) -> Result<(), Error> {
+        self.snapshot().store(&self.path)
+    }
+
+    /// Makes a snapshot of the current state.
+    pub fn snapshot(&self) -> Snapshot {
+        let mut write_set = self
+            .data
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(path, value)| WriteSetEntry {
+                address: hex::encode(path.address),
+                path: hex::encode(&path.path),
+                value: Some(hex::encode(value)),
+            })
+            .collect::<Vec<_>>();
+        write_set.sort_by(|a, b| (&a.address, &a.path).cmp(&(&b.address, &b.path)));
+
+        let mut prices = self
+            .oracle
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|((currency_1, currency_2), price)| PriceEntry {
+                currency_1: currency_1.to_owned(),
+                currency_2: currency_2.to_owned(),
+                price: price.price,
+                timestamp: price.timestamp,
+            })
+            .collect::<Vec<_>>();
+        prices.sort_by(|a, b| (&a.currency_1, &a.currency_2).cmp(&(&b.currency_1, &b.currency_2)));
+
+        let mut balances = self
+            .native_balance
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|((address, ticker), value)| BalanceEntry {
+                address: hex::encode(address),
+                ticker: ticker.to_owned(),
+                value: *value,
+            })
+            .collect::<Vec<_>>();
+        balances.sort_by(|a, b| (&a.address, &a.ticker).cmp(&(&b.address, &b.ticker)));
+
+        let mut currency_info = self
+            .coin_info
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(ticker, info)| CurrencyInfoEntry::new(ticker, info))
+            .collect::<Vec<_>>();
+        currency_info.sort_by(|a, b| a.ticker.cmp(&b.ticker));
+
+        let mut supply_history = self
+            .supply_history
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|(ticker, history)| {
+                history.iter().map(move |record| SupplyEntry {
+                    ticker: ticker.to_owned(),
+                    height: record.height,
+                    total_supply: record.total_supply,
+                })
+            })
+            .collect::<Vec<_>>();
+        supply_history.sort_by(|a, b| (&a.ticker, a.height).cmp(&(&b.ticker, b.height)));
+
+        let block = *self.block.lock().unwrap();
+
+        Snapshot {
+            write_set,
+            prices,
+            balances,
+            currency_info,
+            supply_history,
+            block: if block == BlockEntry::default() {
+                None
+            } else {
+                Some(block)
+            },
+        }
+    }
+
+    /// Merges `snapshot` into the current state.
+    pub fn apply_snapshot(&self, snapshot: Snapshot) -> Result<(), Error> {
+        for entry in snapshot.write_set {
+            let path = AccessPath::new(parse_address(&entry.address)?, hex::decode(&entry.path)?);
+            match entry.value.as_deref() {
+                None | Some("") => self.delete(path),
+                Some(value) => self.insert(path, hex::decode(value)?),
+            }
+        }
+        for entry in snapshot.prices {
+            self.insert_price(
+                &entry.currency_1,
+                &entry.currency_2,
+                entry.price,
+                entry.timestamp,
+            );
+        }
+        for entry in snapshot.balances {
+            self.set_balance(parse_address(&entry.address)?, &entry.ticker, entry.value);
+        }
+        for entry in snapshot.currency_info {
+            let (ticker, info) = entry.into_info()?;
+            self.set_currency_info(&ticker, info);
+        }
+        for entry in snapshot.supply_history {
+            self.add_supply_record(
+                &entry.ticker,
+                SupplyRecord {
+                    height: entry.height,
+                    total_supply: entry.total_supply,
+                },
+            );
+        }
+        if let Some(block) = snapshot.block {
+            self.set_block(block);
+        }
+        Ok(())
+    }
+
+    /// Add module to internal state.
+    pub fn publish_module(&self, module: Vec<u8>) -> Result<ModuleId, Error> {
+        let id = CompiledModule::deserialize(&module)
+            .map_err(|e| e.finish(Location::Undefined).into_vm_status())?
+            .self_id();
+        self.insert((&id).into(), module);
+        Ok(id)
+    }
+
+    /// Returns chain data by access path.
+    pub fn get(&self, access_path: &AccessPath) -> Option<Vec<u8>> {
+        let data = &self.data.lock().unwrap();
+        data.get(access_path).cloned()
+    }
+
+    /// Wrapper around internal `HashMap.insert()`.
+    pub fn insert(&self, access_path: AccessPath, blob: Vec<u8>) {
+        let data = &mut self.data.lock().unwrap();
+        data.insert(access_path, blob);
+    }
+
+    /// Wrapper around internal `HashMap.delete()`.
+    pub fn delete(&self, access_path: AccessPath) {
+        let data = &mut self.data.lock().unwrap();
+        data.remove(&access_path);
+    }
+
+    /// Merge `WriteSet` into internal chain state.
+    pub fn merge_write_set(&self, write_set: WriteSet) {
+        let data = &mut self.data.lock().unwrap();
+        for (access_path, write_op) in write_set {
+            match write_op {
+                WriteOp::Value(blob) => {
+                    data.insert(access_path, blob);
+                }
+                WriteOp::Deletion => {
+                    data.remove(&access_path);
+                }
+            }
+        }
+    }
+
+    /// Add price with unknown time of its update.
+    pub fn add_price(&self, curr_1: &str, curr_2: &str, val: u128) {
+        self.insert_price(curr_1, curr_2, val, None);
+    }
+
+    /// Add price with the time of its update.
+    pub fn add_price_with_time(&self, curr_1: &str, curr_2: &str, val: u128, timestamp: u64) {
+        self.insert_price(curr_1, curr_2, val, Some(timestamp));
+    }
+
+    fn insert_price(&self, curr_1: &str, curr_2: &str, val: u128, timestamp: Option<u64>) {
+        let mut data = self.oracle.lock().unwrap();
+        data.insert(
+            (curr_1.to_owned(), curr_2.to_owned()),
+            Price {
+                price: val,
+                timestamp,
+            },
+        );
+    }
+
+    /// Set balance.
+    pub fn set_balance(&self, address: AccountAddress, ticker: &str, val: u128) {
+        let mut data = self.native_balance.lock().unwrap();
+        data.insert((address, ticker.to_owned()), val);
+    }
+
+    /// Set currency info.
+    pub fn set_currency_info(&self, ticker: &str, info: CurrencyInfo) {
+        let mut data = self.coin_info.lock().unwrap();
+        data.insert(ticker.to_owned(), info);
+    }
+
+    /// Add total supply record to the currency history.
+    pub fn add_supply_record(&self, ticker: &str, record: SupplyRecord) {
+        let mut data = self.supply_history.lock().unwrap();
+        let history = data.entry(ticker.to_owned()).or_insert_with(Vec::new);
+        history.retain(|rec| rec.height != record.height);
+        history.push(record);
+        history.sort_by_key(|rec| rec.height);
+    }
+
+    /// Returns the last block metadata.
+    pub fn block(&self) -> BlockEntry {
+        *self.block.lock().unwrap()
+    }
+
+    /// Set the last block metadata.
+    pub fn set_block(&self, block: BlockEntry) {
+        *self.block.lock().unwrap() = block;
+    }
+}
+
+impl RemoteCache for FileDataSource {
+    fn get_module(&self, module_id: &ModuleId) -> VMResult<Option<Vec<u8>>> {
+        Ok(self.get(&AccessPath::from(module_id)))
+    }
+
+    fn get_resource(
+        &self,
+        address: &AccountAddress,
+        tag: &StructTag,
+    ) -> PartialVMResult<Option<Vec<u8>>> {
+        let resource_tag = ResourceKey::new(*address, tag.to_owned());
+        let path = AccessPath::resource_access_path(&resource_tag);
+        Ok(self.get(&path))
+    }
+}
+
+impl Oracle for FileDataSource {
+    fn get_price(&self, currency_1: String, currency_2: String) -> Result<Option<Price>, Error> {
+        let oracle = &self.oracle.lock().unwrap();
+        Ok(oracle.get(&(currency_1, currency_2)).copied())
+    }
+}
+
+impl Balance for FileDataSource {
+    fn get_balance(&self, address: AccountAddress, ticker: String) -> Result<Option<u128>, Error> {
+        let balance = &self.native_balance.lock().unwrap();
+        Ok(balance.get(&(address, ticker)).copied())
+    }
+}
+
+impl GetCurrencyInfo for FileDataSource {
+    fn get_currency_info(&self, ticker: String) -> Result<Option<CurrencyInfo>, Error> {
+        let coin_info = &self.coin_info.lock().unwrap();
+        Ok(coin_info.get(&ticker).cloned())
+    }
+
+    fn get_currency_list(&self) -> Result<Vec<(String, CurrencyInfo)>, Error> {
+        let coin_info = &self.coin_info.lock().unwrap();
+        let mut currencies = coin_info
+            .iter()
+            .map(|(ticker, info)| (ticker.to_owned(), info.to_owned()))
+            .collect::<Vec<_>>();
+        currencies.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(currencies)
+    }
+
+    fn get_supply_history(
+        &self,
+        ticker: String,
+        height: u64,
+    ) -> Result<Option<Vec<SupplyRecord>>, Error> {
+        let supply_history = &self.supply_history.lock().unwrap();
+        Ok(supply_history.get(&ticker).map(|history| {
+            history
+                .iter()
+                .filter(|record| record.height <= height)
+                .cloned()
+                .collect()
+        }))
+    }
+}
+
+impl RemoveModule for FileDataSource {}
+
+impl DataSource for FileDataSource {}
+
+/// Chain state snapshot.
+///
+/// Stored as a json document:
+/// ```json
+/// {
+///   "write_set": [{ "address": "<hex>", "path": "<hex>", "value": "<hex>" }],
+///   "prices": [{ "currency_1": "ETH", "currency_2": "BTC", "price": 4078, "timestamp": 1600000000 }],
+///   "balances": [{ "address": "<hex>", "ticker": "XFI", "value": 1000 }],
+///   "currency_info": [{
+///     "ticker": "XFI", "denom": "xfi", "decimals": 18,
+///     "is_token": false, "address": "<hex>", "total_supply": 0
+///   }],
+///   "supply_history": [{ "ticker": "XFI", "height": 10, "total_supply": 1000 }],
+///   "block": { "height": 10, "timestamp": 1600000000 }
+/// }
+/// ```
+/// Every section is optional, so the output of `stdlib-builder` is a valid snapshot.
+/// Write set entries with an empty or `null` value are deletions.
+/// Addresses may be passed with or without the `0x` prefix.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Access paths with their values.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub write_set: Vec<WriteSetEntry>,
+    /// Oracle prices.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prices: Vec<PriceEntry>,
+    /// Native balances.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub balances: Vec<BalanceEntry>,
+    /// Currencies info.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub currency_info: Vec<CurrencyInfoEntry>,
+    /// Currencies total supply history.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub supply_history: Vec<SupplyEntry>,
+    /// Last block metadata.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block: Option<BlockEntry>,
+}
+
+impl Snapshot {
+    /// Reads the snapshot by path.
+    pub fn load(path: &Path) -> Result<Snapshot, Error> {
+        serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| anyhow!("Failed to read snapshot {:?}: {}", path, err))
+    }
+
+    /// Stores the snapshot by path.
+    pub fn store(&self, path: &Path) -> Result<(), Error> {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() && !parent.exists() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+        // Write to the temporary file first so that the snapshot is never left half written.
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+}
+
+/// Access path value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WriteSetEntry {
+    /// Hex encoded account address.
+    pub address: String,
+    /// Hex encoded path.
+    pub path: String,
+    /// Hex encoded value. Empty or `None` for the deleted path.
+    #[serde(default)]
+    pub value: Option<String>,
+}
+
+/// Oracle price of `currency_2` in `currency_1`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriceEntry {
+    /// First currency ticker.
+    pub currency_1: String,
+    /// Second currency ticker.
+    pub currency_2: String,
+    /// Price.
+    pub price: u128,
+    /// Unix time of the price update in seconds. Missing or `null` if it is unknown.
+    #[serde(default)]
+    pub timestamp: Option<u64>,
+}
+
+/// Native balance.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceEntry {
+    /// Hex encoded account address.
+    pub address: String,
+    /// Currency ticker.
+    pub ticker: String,
+    /// Balance value.
+    pub value: u128,
+}
+
+/// Total supply of currency at the block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SupplyEntry {
+    /// Currency ticker.
+    pub ticker: String,
+    /// Block height.
+    pub height: u64,
+    /// Total supply.
+    pub total_supply: u128,
+}
+
+/// Block metadata.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockEntry {
+    /// Block height.
+    pub height: u64,
+    /// Block timestamp in seconds.
+    pub timestamp: u64,
+}
+
+impl BlockEntry {
+    /// Returns metadata of the next block.
+    /// The timestamp is taken from the local clock and is always greater than the current one.
+    pub fn next(&self) -> BlockEntry {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_secs())
+            .unwrap_or_default();
+        BlockEntry {
+            height: self.height + 1,
+            timestamp: now.max(self.timestamp + 1),
+        }
+    }
+}
+
+/// Currency info.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CurrencyInfoEntry {
+    /// Currency ticker.
+    pub ticker: String,
+    /// Denom.
+    pub denom: String,
+    /// Decimals.
+    pub decimals: u8,
+    /// Is token.
+    pub is_token: bool,
+    /// Hex encoded owner address.
+    pub address: String,
+    /// Total supply.
+    pub total_supply: u128,
+}
+
+impl CurrencyInfoEntry {
+    fn new(ticker: &str, info: &CurrencyInfo) -> CurrencyInfoEntry {
+        CurrencyInfoEntry {
+            ticker: ticker.to_owned(),
+            denom: String::from_utf8_lossy(&info.denom).into_owned(),
+            decimals: info.decimals,
+            is_token: info.is_token,
+            address: hex::encode(info.address),
+            total_supply: info.total_supply,
+        }
+    }
+
+    fn into_info(self) -> Result<(String, CurrencyInfo), Error> {
+        Ok((
+            self.ticker,
+            CurrencyInfo {
+                denom: self.denom.into_bytes(),
+                decimals: self.decimals,
+                is_token: self.is_token,
+                address: parse_address(&self.address)?,
+                total_supply: self.total_supply,
+            },
+        ))
+    }
+}
+
+/// Parses hex encoded account address with optional `0x` prefix.
+pub fn parse_address(address: &str) -> Result<AccountAddress, Error> {
+    let address = address.trim_start_matches("0x");
+    AccountAddress::from_hex_literal(&format!("0x{}", address))
+        .map_err(|err| anyhow!("Invalid address '{}': {}", address, err))
+}
@@ -19,12 +19,16 @@ use dvm_net::tonic;
 use dvm_net::tonic::Status;
 use libra::prelude::*;
 
-use crate::{Balance, CurrencyInfo, DataSource, GetCurrencyInfo, Oracle, RemoveModule};
+use crate::{
+    Balance, CurrencyInfo, DataSource, GetCurrencyInfo, Oracle, Price, RemoveModule, SupplyRecord,
+};
 
 /// Receiver for a channel that handles shutdown signals.
 pub type ShutdownSig = tokio::sync::oneshot::Receiver<()>;
 
 /// Wrapper around gRPC-based interface to dnode. Used for the resource resolution inside the VM.
+///
+/// The data source api has no currency list and supply history requests, so they are not available.
 #[derive(Clone)]
 pub struct GrpcDataSource {
     handler: Arc<JoinHandle<()>>,
@@ -113,7 +117,11 @@ impl GrpcDataSource {
                                 handler.send(handle_response(
                                     resp.error_code,
                                     resp.error_message,
-                                    resp.price.map(U128::into),
+                                    // The data source api does not provide the price update time, so it is unknown.
+                                    resp.price.map(|price| Price {
+                                        price: price.into(),
+                                        timestamp: None,
+                                    }),
                                 ));
                             }
                             Request::Balance(BalanceRequest { request, handler }) => {
@@ -205,7 +213,7 @@ struct OracleRequest {
     handler: OracleHandler,
 }
 
-struct OracleHandler(Sender<Result<Option<u128>, Error>>);
+struct OracleHandler(Sender<Result<Option<Price>, Error>>);
 
 struct BalanceRequest {
     request: tonic::Request<NativeBalanceRequest>,
@@ -267,7 +275,7 @@ impl SendResult for CurrencyInfoHandler {
 }
 
 impl SendResult for OracleHandler {
-    type Resp = u128;
+    type Resp = Price;
 
     fn send(&self, response: Result<Option<Self::Resp>, Error>) {
         if let Err(err) = self.0.send(response) {
@@ -325,7 +333,7 @@ impl Balance for GrpcDataSource {
 }
 
 impl Oracle for GrpcDataSource {
-    fn get_price(&self, currency_1: String, currency_2: String) -> Result<Option<u128>, Error> {
+    fn get_price(&self, currency_1: String, currency_2: String) -> Result<Option<Price>, Error> {
         let (tx, rx) = bounded(0);
         self.sender.send(Request::Oracle(OracleRequest {
             request: tonic::Request::new(OraclePriceRequest {
@@ -348,6 +356,20 @@ impl GetCurrencyInfo for GrpcDataSource {
             }))?;
         rx.recv()?
     }
+
+    fn get_currency_list(&self) -> Result<Vec<(String, CurrencyInfo)>, Error> {
+        Err(anyhow!("The data source api has no currency list request."))
+    }
+
+    fn get_supply_history(
+        &self,
+        _ticker: String,
+        _height: u64,
+    ) -> Result<Option<Vec<SupplyRecord>>, Error> {
+        Err(anyhow!(
+            "The data source api has no supply history request."
+        ))
+    }
 }
 
 impl DataSource for GrpcDataSource {}
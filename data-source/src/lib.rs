@@ -11,11 +11,14 @@ use anyhow::Error;
 use serde::{Deserialize, Serialize};
 
 pub use blacklist::BlackListDataSource;
+pub use cross_rate::{CrossRateConfig, CrossRateDataSource};
+pub use file::FileDataSource;
 pub use grpc::GrpcDataSource;
 use libra::prelude::*;
 pub use metrics::DsMeter;
 pub use mock::MockDataSource;
 pub use module_cache::ModuleCache;
+pub use record::{ReadSet, RecordingDataSource, ReplayDataSource};
 
 /// `GrpcDataSource` to wrap all gRPC calls to `dnode`.
 pub mod grpc;
@@ -26,12 +29,21 @@ pub mod metrics;
 /// `MockDataSource` to be used in test_kit.
 pub mod mock;
 
+/// `FileDataSource` which loads and persists chain state in a snapshot file.
+pub mod file;
+
 /// Defines `ModuleCache` which implements caching for fetching modules from `dnode`.
 pub mod module_cache;
 
 /// Defines `BlackListDataSource` which provides implements blacklist of access path.
 pub mod blacklist;
 
+/// Defines `CrossRateDataSource` which resolves oracle prices via inverse pairs and the base currency.
+pub mod cross_rate;
+
+/// Defines `RecordingDataSource` and `ReplayDataSource` which record and replay data source reads.
+pub mod record;
+
 /// Thread-safe `StateView`.
 pub trait DataSource:
     RemoteCache + Oracle + Balance + RemoveModule + GetCurrencyInfo + Clone + Send + Sync + 'static
@@ -40,8 +52,17 @@ pub trait DataSource:
 
 /// Oracle access.
 pub trait Oracle {
-    /// Results price of `currency_2` in `currency_1`.
-    fn get_price(&self, currency_1: String, currency_2: String) -> Result<Option<u128>, Error>;
+    /// Results price of `currency_2` in `currency_1` with the time of its last update.
+    fn get_price(&self, currency_1: String, currency_2: String) -> Result<Option<Price>, Error>;
+}
+
+/// Oracle price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Price {
+    /// Price value.
+    pub price: u128,
+    /// Unix time of the last price update in seconds, `None` if it is unknown.
+    pub timestamp: Option<u64>,
 }
 
 /// Balance access.
@@ -54,6 +75,17 @@ pub trait Balance {
 pub trait GetCurrencyInfo {
     /// Returns info abort currency with `ticker`.
     fn get_currency_info(&self, ticker: String) -> Result<Option<CurrencyInfo>, Error>;
+
+    /// Returns tickers and info of all registered currencies ordered by ticker.
+    fn get_currency_list(&self) -> Result<Vec<(String, CurrencyInfo)>, Error>;
+
+    /// Returns total supply history of currency with `ticker` ordered by block height.
+    /// Only records up to the `height` of the executed block are returned.
+    fn get_supply_history(
+        &self,
+        ticker: String,
+        height: u64,
+    ) -> Result<Option<Vec<SupplyRecord>>, Error>;
 }
 
 /// Currency info.
@@ -71,6 +103,15 @@ pub struct CurrencyInfo {
     pub total_supply: u128,
 }
 
+/// Total supply of currency at the block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SupplyRecord {
+    /// Block height.
+    pub height: u64,
+    /// Total supply.
+    pub total_supply: u128,
+}
+
 /// Trait to `remove_module` internal data structure.
 pub trait RemoveModule {
     /// Removes the module by its id.
@@ -4,7 +4,9 @@ use dvm_info::metrics::execution::ExecutionResult;
 use dvm_info::metrics::meter::ScopeMeter;
 use libra::prelude::*;
 
-use crate::{Balance, CurrencyInfo, DataSource, GetCurrencyInfo, Oracle, RemoveModule};
+use crate::{
+    Balance, CurrencyInfo, DataSource, GetCurrencyInfo, Oracle, Price, RemoveModule, SupplyRecord,
+};
 
 /// Wrapper for data source which collects metrics queries.
 #[derive(Debug, Clone)]
@@ -108,7 +110,7 @@ impl<D> Oracle for DsMeter<D>
 where
     D: DataSource,
 {
-    fn get_price(&self, currency_1: String, currency_2: String) -> Result<Option<u128>, Error> {
+    fn get_price(&self, currency_1: String, currency_2: String) -> Result<Option<Price>, Error> {
         let mut meter = ScopeMeter::new("oracle_access");
         match self.inner.get_price(currency_1, currency_2) {
             Ok(Some(data)) => {
@@ -148,4 +150,40 @@ where
             }
         }
     }
+
+    fn get_currency_list(&self) -> Result<Vec<(String, CurrencyInfo)>, Error> {
+        let mut meter = ScopeMeter::new("currency_list_access");
+        match self.inner.get_currency_list() {
+            Ok(data) => {
+                meter.set_result(ExecutionResult::new(true, 200, 0));
+                Ok(data)
+            }
+            Err(err) => {
+                meter.set_result(ExecutionResult::new(false, 500, 0));
+                Err(err)
+            }
+        }
+    }
+
+    fn get_supply_history(
+        &self,
+        ticker: String,
+        height: u64,
+    ) -> Result<Option<Vec<SupplyRecord>>, Error> {
+        let mut meter = ScopeMeter::new("supply_history_access");
+        match self.inner.get_supply_history(ticker, height) {
+            Ok(Some(data)) => {
+                meter.set_result(ExecutionResult::new(true, 200, 0));
+                Ok(Some(data))
+            }
+            Ok(None) => {
+                meter.set_result(ExecutionResult::new(false, 404, 0));
+                Ok(None)
+            }
+            Err(err) => {
+                meter.set_result(ExecutionResult::new(false, 500, 0));
+                Err(err)
+            }
+        }
+    }
 }
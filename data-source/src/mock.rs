@@ -5,15 +5,18 @@ use anyhow::Error;
 
 use libra::prelude::*;
 
-use crate::{Balance, CurrencyInfo, DataSource, GetCurrencyInfo, Oracle, RemoveModule};
+use crate::{
+    Balance, CurrencyInfo, DataSource, GetCurrencyInfo, Oracle, Price, RemoveModule, SupplyRecord,
+};
 
 /// `StateView` implementation to be used in test_kit.
 #[derive(Debug, Clone, Default)]
 pub struct MockDataSource {
     data: Arc<Mutex<HashMap<AccessPath, Vec<u8>>>>,
-    oracle: Arc<Mutex<HashMap<(String, String), u128>>>,
+    oracle: Arc<Mutex<HashMap<(String, String), Price>>>,
     native_balance: Arc<Mutex<HashMap<(AccountAddress, String), u128>>>,
     coin_info: Arc<Mutex<HashMap<String, CurrencyInfo>>>,
+    supply_history: Arc<Mutex<HashMap<String, Vec<SupplyRecord>>>>,
 }
 
 impl MockDataSource {
@@ -24,6 +27,7 @@ impl MockDataSource {
             oracle: Arc::new(Mutex::new(Default::default())),
             native_balance: Arc::new(Mutex::new(Default::default())),
             coin_info: Arc::new(Mutex::new(Default::default())),
+            supply_history: Arc::new(Mutex::new(Default::default())),
         }
     }
 
@@ -69,6 +73,8 @@ impl MockDataSource {
         data.clear();
         let mut data = self.coin_info.lock().unwrap();
         data.clear();
+        let mut data = self.supply_history.lock().unwrap();
+        data.clear();
     }
 
     /// Returns chain data by access path.
@@ -85,10 +91,25 @@ impl MockDataSource {
         data.insert(access_path, blob);
     }
 
-    /// Add price.
+    /// Add price with unknown time of its update.
     pub fn add_price(&self, curr_1: &str, curr_2: &str, val: u128) {
+        self.insert_price(curr_1, curr_2, val, None);
+    }
+
+    /// Add price with the time of its update.
+    pub fn add_price_with_time(&self, curr_1: &str, curr_2: &str, val: u128, timestamp: u64) {
+        self.insert_price(curr_1, curr_2, val, Some(timestamp));
+    }
+
+    fn insert_price(&self, curr_1: &str, curr_2: &str, val: u128, timestamp: Option<u64>) {
         let mut data = self.oracle.lock().unwrap();
-        data.insert((curr_1.to_owned(), curr_2.to_owned()), val);
+        data.insert(
+            (curr_1.to_owned(), curr_2.to_owned()),
+            Price {
+                price: val,
+                timestamp,
+            },
+        );
     }
 
     /// Set balance.
@@ -103,6 +124,15 @@ impl MockDataSource {
         data.insert(ticker.to_owned(), info);
     }
 
+    /// Add total supply record to the currency history.
+    pub fn add_supply_record(&self, ticker: &str, record: SupplyRecord) {
+        let mut data = self.supply_history.lock().unwrap();
+        let history = data.entry(ticker.to_owned()).or_insert_with(Vec::new);
+        history.retain(|rec| rec.height != record.height);
+        history.push(record);
+        history.sort_by_key(|rec| rec.height);
+    }
+
     /// Wrapper around internal `HashMap.delete()`.
     pub fn delete(&self, access_path: AccessPath) {
         let data = &mut self.data.lock().unwrap();
@@ -142,7 +172,7 @@ impl RemoteCache for MockDataSource {
 }
 
 impl Oracle for MockDataSource {
-    fn get_price(&self, currency_1: String, currency_2: String) -> Result<Option<u128>, Error> {
+    fn get_price(&self, currency_1: String, currency_2: String) -> Result<Option<Price>, Error> {
         let oracle = &mut self.oracle.lock().unwrap();
         Ok(oracle.get(&(currency_1, currency_2)).copied())
     }
@@ -160,6 +190,31 @@ impl GetCurrencyInfo for MockDataSource {
         let coin_info = &mut self.coin_info.lock().unwrap();
         Ok(coin_info.get(&ticker).cloned())
     }
+
+    fn get_currency_list(&self) -> Result<Vec<(String, CurrencyInfo)>, Error> {
+        let coin_info = &self.coin_info.lock().unwrap();
+        let mut currencies = coin_info
+            .iter()
+            .map(|(ticker, info)| (ticker.to_owned(), info.to_owned()))
+            .collect::<Vec<_>>();
+        currencies.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(currencies)
+    }
+
+    fn get_supply_history(
+        &self,
+        ticker: String,
+        height: u64,
+    ) -> Result<Option<Vec<SupplyRecord>>, Error> {
+        let supply_history = &self.supply_history.lock().unwrap();
+        Ok(supply_history.get(&ticker).map(|history| {
+            history
+                .iter()
+                .filter(|record| record.height <= height)
+                .cloned()
+                .collect()
+        }))
+    }
 }
 
 impl RemoveModule for MockDataSource {}
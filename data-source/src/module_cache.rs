@@ -1,7 +1,9 @@
 use std::sync::{Arc, Mutex};
 
 use libra::prelude::*;
-use crate::{RemoveModule, DataSource, Oracle, Balance, GetCurrencyInfo, CurrencyInfo};
+use crate::{
+    RemoveModule, DataSource, Oracle, Balance, GetCurrencyInfo, CurrencyInfo, Price, SupplyRecord,
+};
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use std::rc::Rc;
@@ -67,7 +69,7 @@ where
 }
 
 impl<D: DataSource> Oracle for ModuleCache<D> {
-    fn get_price(&self, currency_1: String, currency_2: String) -> Result<Option<u128>, Error> {
+    fn get_price(&self, currency_1: String, currency_2: String) -> Result<Option<Price>, Error> {
         self.inner.get_price(currency_1, currency_2)
     }
 }
@@ -82,6 +84,18 @@ impl<D: DataSource> GetCurrencyInfo for ModuleCache<D> {
     fn get_currency_info(&self, ticker: String) -> Result<Option<CurrencyInfo>, Error> {
         self.inner.get_currency_info(ticker)
     }
+
+    fn get_currency_list(&self) -> Result<Vec<(String, CurrencyInfo)>, Error> {
+        self.inner.get_currency_list()
+    }
+
+    fn get_supply_history(
+        &self,
+        ticker: String,
+        height: u64,
+    ) -> Result<Option<Vec<SupplyRecord>>, Error> {
+        self.inner.get_supply_history(ticker, height)
+    }
 }
 
 impl<D> DataSource for ModuleCache<D> where D: DataSource {}
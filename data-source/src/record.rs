@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Error;
+
+use libra::prelude::*;
+
+use crate::{
+    Balance, CurrencyInfo, DataSource, GetCurrencyInfo, Oracle, Price, RemoveModule, SupplyRecord,
+};
+
+/// Data source reads performed during the transaction execution.
+#[derive(Debug, Clone, Default)]
+pub struct ReadSet {
+    modules: HashMap<ModuleId, Option<Vec<u8>>>,
+    resources: HashMap<(AccountAddress, StructTag), Option<Vec<u8>>>,
+    prices: HashMap<(String, String), Option<Price>>,
+    balances: HashMap<(AccountAddress, String), Option<u128>>,
+    currency_info: HashMap<String, Option<CurrencyInfo>>,
+    currency_list: Option<Vec<(String, CurrencyInfo)>>,
+    supply_history: HashMap<String, Option<Vec<SupplyRecord>>>,
+    /// At least one read has failed and was not recorded.
+    incomplete: bool,
+}
+
+impl ReadSet {
+    /// Returns `true` if at least one read has failed.
+    /// Replay of the incomplete read set is not equivalent to the original execution.
+    pub fn is_incomplete(&self) -> bool {
+        self.incomplete
+    }
+}
+
+/// Wrapper for data source which records all successful reads.
+///
+/// Clones share the same read set.
+#[derive(Debug, Clone)]
+pub struct RecordingDataSource<D>
+where
+    D: DataSource,
+{
+    inner: D,
+    reads: Arc<Mutex<ReadSet>>,
+}
+
+impl<D> RecordingDataSource<D>
+where
+    D: DataSource,
+{
+    /// Create a new RecordingDataSource with DataSource.
+    pub fn new(inner: D) -> RecordingDataSource<D> {
+        RecordingDataSource {
+            inner,
+            reads: Default::default(),
+        }
+    }
+
+    /// Returns recorded reads.
+    pub fn reads(&self) -> ReadSet {
+        self.reads.lock().unwrap().clone()
+    }
+
+    /// Records the read result.
+    fn record<T, E, F>(&self, result: Result<T, E>, store: F) -> Result<T, E>
+    where
+        T: Clone,
+        F: FnOnce(&mut ReadSet, T),
+    {
+        let mut reads = self.reads.lock().unwrap();
+        match &result {
+            Ok(value) => store(&mut reads, value.clone()),
+            Err(_) => reads.incomplete = true,
+        }
+        result
+    }
+}
+
+impl<D> RemoteCache for RecordingDataSource<D>
+where
+    D: DataSource,
+{
+    fn get_module(&self, module_id: &ModuleId) -> VMResult<Option<Vec<u8>>> {
+        self.record(self.inner.get_module(module_id), |reads, module| {
+            reads.modules.insert(module_id.to_owned(), module);
+        })
+    }
+
+    fn get_resource(
+        &self,
+        address: &AccountAddress,
+        tag: &StructTag,
+    ) -> PartialVMResult<Option<Vec<u8>>> {
+        self.record(self.inner.get_resource(address, tag), |reads, resource| {
+            reads.resources.insert((*address, tag.to_owned()), resource);
+        })
+    }
+}
+
+impl<D: DataSource> Oracle for RecordingDataSource<D> {
+    fn get_price(&self, currency_1: String, currency_2: String) -> Result<Option<Price>, Error> {
+        let price = self.inner.get_price(currency_1.clone(), currency_2.clone());
+        self.record(price, |reads, price| {
+            reads.prices.insert((currency_1, currency_2), price);
+        })
+    }
+}
+
+impl<D: DataSource> Balance for RecordingDataSource<D> {
+    fn get_balance(&self, address: AccountAddress, ticker: String) -> Result<Option<u128>, Error> {
+        let balance = self.inner.get_balance(address, ticker.clone());
+        self.record(balance, |reads, balance| {
+            reads.balances.insert((address, ticker), balance);
+        })
+    }
+}
+
+impl<D: DataSource> GetCurrencyInfo for RecordingDataSource<D> {
+    fn get_currency_info(&self, ticker: String) -> Result<Option<CurrencyInfo>, Error> {
+        let info = self.inner.get_currency_info(ticker.clone());
+        self.record(info, |reads, info| {
+            reads.currency_info.insert(ticker, info);
+        })
+    }
+
+    fn get_currency_list(&self) -> Result<Vec<(String, CurrencyInfo)>, Error> {
+        self.record(self.inner.get_currency_list(), |reads, list| {
+            reads.currency_list = Some(list);
+        })
+    }
+
+    fn get_supply_history(
+        &self,
+        ticker: String,
+        height: u64,
+    ) -> Result<Option<Vec<SupplyRecord>>, Error> {
+        let history = self.inner.get_supply_history(ticker.clone(), height);
+        self.record(history, |reads, history| {
+            reads.supply_history.insert(ticker, history);
+        })
+    }
+}
+
+impl<D> DataSource for RecordingDataSource<D> where D: DataSource {}
+
+impl<D> RemoveModule for RecordingDataSource<D>
+where
+    D: DataSource,
+{
+    fn remove_module(&self, module_id: &ModuleId) {
+        self.inner.remove_module(module_id)
+    }
+}
+
+/// Data source which serves the recorded reads.
+///
+/// Modules which were not recorded are loaded from the inner data source:
+/// they may be missing from the read set because the original virtual machine had them in its cache.
+/// Any other read that was not recorded fails.
+#[derive(Debug, Clone)]
+pub struct ReplayDataSource<D>
+where
+    D: DataSource,
+{
+    inner: D,
+    reads: Arc<ReadSet>,
+}
+
+impl<D> ReplayDataSource<D>
+where
+    D: DataSource,
+{
+    /// Create a new ReplayDataSource with the read set and DataSource for modules.
+    pub fn new(inner: D, reads: ReadSet) -> ReplayDataSource<D> {
+        ReplayDataSource {
+            inner,
+            reads: Arc::new(reads),
+        }
+    }
+}
+
+/// Returns error about the read missing from the read set.
+fn unrecorded(read: &str) -> Error {
+    anyhow!("Unrecorded {} read", read)
+}
+
+impl<D> RemoteCache for ReplayDataSource<D>
+where
+    D: DataSource,
+{
+    fn get_module(&self, module_id: &ModuleId) -> VMResult<Option<Vec<u8>>> {
+        match self.reads.modules.get(module_id) {
+            Some(module) => Ok(module.clone()),
+            None => self.inner.get_module(module_id),
+        }
+    }
+
+    fn get_resource(
+        &self,
+        address: &AccountAddress,
+        tag: &StructTag,
+    ) -> PartialVMResult<Option<Vec<u8>>> {
+        self.reads
+            .resources
+            .get(&(*address, tag.to_owned()))
+            .cloned()
+            .ok_or_else(|| {
+                PartialVMError::new(StatusCode::STORAGE_ERROR)
+                    .with_message(unrecorded("resource").to_string())
+            })
+    }
+}
+
+impl<D: DataSource> Oracle for ReplayDataSource<D> {
+    fn get_price(&self, currency_1: String, currency_2: String) -> Result<Option<Price>, Error> {
+        self.reads
+            .prices
+            .get(&(currency_1, currency_2))
+            .cloned()
+            .ok_or_else(|| unrecorded("price"))
+    }
+}
+
+impl<D: DataSource> Balance for ReplayDataSource<D> {
+    fn get_balance(&self, address: AccountAddress, ticker: String) -> Result<Option<u128>, Error> {
+        self.reads
+            .balances
+            .get(&(address, ticker))
+            .cloned()
+            .ok_or_else(|| unrecorded("balance"))
+    }
+}
+
+impl<D: DataSource> GetCurrencyInfo for ReplayDataSource<D> {
+    fn get_currency_info(&self, ticker: String) -> Result<Option<CurrencyInfo>, Error> {
+        self.reads
+            .currency_info
+            .get(&ticker)
+            .cloned()
+            .ok_or_else(|| unrecorded("currency info"))
+    }
+
+    fn get_currency_list(&self) -> Result<Vec<(String, CurrencyInfo)>, Error> {
+        self.reads
+            .currency_list
+            .clone()
+            .ok_or_else(|| unrecorded("currency list"))
+    }
+
+    fn get_supply_history(
+        &self,
+        ticker: String,
+        _height: u64,
+    ) -> Result<Option<Vec<SupplyRecord>>, Error> {
+        self.reads
+            .supply_history
+            .get(&ticker)
+            .cloned()
+            .ok_or_else(|| unrecorded("supply history"))
+    }
+}
+
+impl<D> DataSource for ReplayDataSource<D> where D: DataSource {}
+
+impl<D> RemoveModule for ReplayDataSource<D> where D: DataSource {}
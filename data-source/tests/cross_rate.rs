@@ -0,0 +1,121 @@
+use dvm_data_source::cross_rate::mul_div;
+use dvm_data_source::{CrossRateConfig, CrossRateDataSource, MockDataSource, Oracle, Price};
+
+const ONE: u128 = 1_000_000_000_000_000_000;
+
+fn price(ds: &impl Oracle, currency_1: &str, currency_2: &str) -> Option<Price> {
+    ds.get_price(currency_1.to_owned(), currency_2.to_owned())
+        .unwrap()
+}
+
+fn data_source() -> MockDataSource {
+    let ds = MockDataSource::new();
+    ds.add_price_with_time("ETH", "XFI", 400 * ONE, 10);
+    ds.add_price_with_time("XFI", "USDT", 2 * ONE, 20);
+    ds.add_price_with_time("BTC", "XFI", 3 * ONE, 30);
+    ds
+}
+
+#[test]
+fn test_cross_rate_disabled() {
+    let ds = CrossRateDataSource::new(data_source(), None);
+    assert_eq!(
+        price(&ds, "ETH", "XFI"),
+        Some(Price {
+            price: 400 * ONE,
+            timestamp: Some(10)
+        })
+    );
+    assert_eq!(price(&ds, "XFI", "ETH"), None);
+    assert_eq!(price(&ds, "ETH", "USDT"), None);
+}
+
+#[test]
+fn test_inverse_price() {
+    let ds = CrossRateDataSource::new(data_source(), Some(CrossRateConfig::default()));
+    assert_eq!(
+        price(&ds, "XFI", "ETH"),
+        Some(Price {
+            price: ONE / 400,
+            timestamp: Some(10)
+        })
+    );
+    // 1/3 is rounded down.
+    assert_eq!(
+        price(&ds, "XFI", "BTC"),
+        Some(Price {
+            price: 333_333_333_333_333_333,
+            timestamp: Some(30)
+        })
+    );
+}
+
+#[test]
+fn test_cross_price() {
+    let ds = CrossRateDataSource::new(data_source(), Some(CrossRateConfig::default()));
+    assert_eq!(
+        price(&ds, "ETH", "USDT"),
+        Some(Price {
+            price: 800 * ONE,
+            timestamp: Some(10)
+        })
+    );
+    assert_eq!(
+        price(&ds, "USDT", "ETH"),
+        Some(Price {
+            price: ONE / 800,
+            timestamp: Some(10)
+        })
+    );
+    assert_eq!(
+        price(&ds, "ETH", "BTC"),
+        Some(Price {
+            price: 133_333_333_333_333_333_200,
+            timestamp: Some(10)
+        })
+    );
+    assert_eq!(price(&ds, "ETH", "DOT"), None);
+}
+
+#[test]
+fn test_custom_base() {
+    let ds = MockDataSource::new();
+    ds.add_price_with_time("DOT", "USDT", 500, 40);
+    ds.add_price_with_time("XFI", "USDT", 200, 20);
+    let ds = CrossRateDataSource::new(ds, Some(CrossRateConfig::new("usdt", 2).unwrap()));
+    assert_eq!(
+        price(&ds, "DOT", "XFI"),
+        Some(Price {
+            price: 250,
+            timestamp: Some(20)
+        })
+    );
+    assert!(CrossRateConfig::new("XFI", 19).is_err());
+}
+
+#[test]
+fn test_unknown_time() {
+    let ds = data_source();
+    ds.add_price("DOT", "XFI", 5 * ONE);
+    let ds = CrossRateDataSource::new(ds, Some(CrossRateConfig::default()));
+    assert_eq!(
+        price(&ds, "DOT", "USDT"),
+        Some(Price {
+            price: 10 * ONE,
+            timestamp: None
+        })
+    );
+}
+
+#[test]
+fn test_mul_div() {
+    assert_eq!(mul_div(6, 7, 2), Some(21));
+    assert_eq!(mul_div(1, 1, 0), None);
+    assert_eq!(
+        mul_div(ONE, ONE, 3),
+        Some(333_333_333_333_333_333_333_333_333_333_333_333)
+    );
+    assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX), Some(u128::MAX));
+    assert_eq!(mul_div(u128::MAX, 2, 1), None);
+    assert_eq!(mul_div(ONE * ONE, ONE * 1000, ONE * ONE), Some(ONE * 1000));
+}
@@ -0,0 +1,130 @@
+use std::env;
+use std::path::PathBuf;
+
+use dvm_data_source::file::{BlockEntry, FileDataSource, Snapshot};
+use dvm_data_source::{Balance, CurrencyInfo, GetCurrencyInfo, Oracle};
+use libra::prelude::*;
+
+fn snapshot_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!(
+        "{}_{}.json",
+        name,
+        hex::encode(AccountAddress::random())
+    ))
+}
+
+#[test]
+fn test_save_and_load_snapshot() {
+    let path = snapshot_path("ds_snapshot");
+    let ds = FileDataSource::open(&path).unwrap();
+    assert_eq!(ds.snapshot(), Snapshot::default());
+
+    let address = AccountAddress::random();
+    let access_path = AccessPath::new(address, vec![0x1, 0x2, 0x3]);
+    ds.insert(access_path.clone(), vec![0xa, 0xb]);
+    ds.add_price("ETH", "BTC", 4078);
+    ds.set_balance(address, "XFI", 1024);
+    ds.set_currency_info(
+        "XFI",
+        CurrencyInfo {
+            denom: b"xfi".to_vec(),
+            decimals: 18,
+            is_token: false,
+            address: CORE_CODE_ADDRESS,
+            total_supply: 100,
+        },
+    );
+    ds.save().unwrap();
+
+    let loaded = FileDataSource::open(&path).unwrap();
+    assert_eq!(loaded.snapshot(), ds.snapshot());
+    assert_eq!(loaded.get(&access_path), Some(vec![0xa, 0xb]));
+    assert_eq!(
+        loaded
+            .get_price("ETH".to_owned(), "BTC".to_owned())
+            .unwrap()
+            .map(|price| price.price),
+        Some(4078)
+    );
+    assert_eq!(
+        loaded.get_balance(address, "XFI".to_owned()).unwrap(),
+        Some(1024)
+    );
+    let info = loaded.get_currency_info("XFI".to_owned()).unwrap().unwrap();
+    assert_eq!(info.denom, b"xfi".to_vec());
+    assert_eq!(info.total_supply, 100);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_merge_write_set() {
+    let path = snapshot_path("ds_write_set");
+    let ds = FileDataSource::open(&path).unwrap();
+    let address = AccountAddress::random();
+    let first = AccessPath::new(address, vec![0x1]);
+    let second = AccessPath::new(address, vec![0x2]);
+    ds.insert(first.clone(), vec![0x1]);
+
+    let ws = WriteSetMut::new(vec![
+        (first.clone(), WriteOp::Deletion),
+        (second.clone(), WriteOp::Value(vec![0x2])),
+    ])
+    .freeze()
+    .unwrap();
+    ds.merge_write_set(ws);
+
+    assert_eq!(ds.get(&first), None);
+    assert_eq!(ds.get(&second), Some(vec![0x2]));
+}
+
+#[test]
+fn test_stdlib_builder_output_is_snapshot() {
+    let snapshot: Snapshot =
+        serde_json::from_str(r#"{"write_set":[{"address":"0x1","path":"00","value":"ff"}]}"#)
+            .unwrap();
+    let ds = FileDataSource::with_snapshot(snapshot_path("ds_stdlib"), snapshot).unwrap();
+    assert_eq!(
+        ds.get(&AccessPath::new(CORE_CODE_ADDRESS, vec![0x0])),
+        Some(vec![0xff])
+    );
+}
+
+#[test]
+fn test_snapshot_deletions() {
+    let ds = FileDataSource::open(snapshot_path("ds_deletions")).unwrap();
+    let first = AccessPath::new(CORE_CODE_ADDRESS, vec![0x1]);
+    let second = AccessPath::new(CORE_CODE_ADDRESS, vec![0x2]);
+    ds.insert(first.clone(), vec![0x1]);
+    ds.insert(second.clone(), vec![0x2]);
+
+    let snapshot: Snapshot = serde_json::from_str(
+        r#"{"write_set":[
+            {"address":"0x1","path":"01","value":""},
+            {"address":"0x1","path":"02","value":null}
+        ]}"#,
+    )
+    .unwrap();
+    ds.apply_snapshot(snapshot).unwrap();
+    assert_eq!(ds.get(&first), None);
+    assert_eq!(ds.get(&second), None);
+}
+
+#[test]
+fn test_next_block() {
+    let next = BlockEntry::default().next();
+    assert_eq!(next.height, 1);
+    assert!(next.timestamp > 0);
+
+    let last = BlockEntry {
+        height: 10,
+        timestamp: u64::MAX - 1,
+    };
+    assert_eq!(
+        last.next(),
+        BlockEntry {
+            height: 11,
+            timestamp: u64::MAX,
+        }
+    );
+}
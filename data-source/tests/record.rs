@@ -0,0 +1,64 @@
+use dvm_data_source::{Balance, MockDataSource, Oracle, Price, RecordingDataSource, ReplayDataSource};
+use libra::prelude::*;
+
+#[test]
+fn test_record_and_replay() {
+    let ds = MockDataSource::new();
+    let address = AccountAddress::random();
+    ds.add_price_with_time("ETH", "XFI", 400, 10);
+    ds.set_balance(address, "XFI", 1_000);
+
+    let recorder = RecordingDataSource::new(ds.clone());
+    let price = recorder
+        .get_price("ETH".to_owned(), "XFI".to_owned())
+        .unwrap();
+    assert_eq!(
+        price,
+        Some(Price {
+            price: 400,
+            timestamp: Some(10)
+        })
+    );
+    assert_eq!(
+        recorder
+            .clone()
+            .get_balance(address, "XFI".to_owned())
+            .unwrap(),
+        Some(1_000)
+    );
+    assert_eq!(
+        recorder
+            .get_price("BTC".to_owned(), "XFI".to_owned())
+            .unwrap(),
+        None
+    );
+
+    let reads = recorder.reads();
+    assert!(!reads.is_incomplete());
+
+    // The replay does not see the state changes.
+    ds.add_price_with_time("ETH", "XFI", 500, 20);
+    ds.set_balance(address, "XFI", 0);
+
+    let replay = ReplayDataSource::new(ds, reads);
+    assert_eq!(
+        replay
+            .get_price("ETH".to_owned(), "XFI".to_owned())
+            .unwrap(),
+        price
+    );
+    assert_eq!(
+        replay.get_balance(address, "XFI".to_owned()).unwrap(),
+        Some(1_000)
+    );
+    assert_eq!(
+        replay
+            .get_price("BTC".to_owned(), "XFI".to_owned())
+            .unwrap(),
+        None
+    );
+    assert!(replay
+        .get_price("XFI".to_owned(), "ETH".to_owned())
+        .is_err());
+    assert!(replay.get_balance(address, "ETH".to_owned()).is_err());
+}
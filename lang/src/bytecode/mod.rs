@@ -3,3 +3,6 @@ pub mod verification;
 
 /// Bytecode metadata.
 pub mod metadata;
+
+/// Resources decoding.
+pub mod resource;
@@ -0,0 +1,244 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+use libra::file_format::{StructFieldInformation, StructHandleIndex};
+use libra::prelude::*;
+
+/// Decodes resources by the struct layouts of their published modules.
+pub struct ResourceViewer<'a, C: RemoteCache> {
+    modules: &'a C,
+}
+
+impl<'a, C: RemoteCache> ResourceViewer<'a, C> {
+    /// Creates a viewer which loads the resource modules from the `modules` cache.
+    pub fn new(modules: &'a C) -> ResourceViewer<'a, C> {
+        ResourceViewer { modules }
+    }
+
+    /// Decodes the resource stored by the access path into the human readable form.
+    /// E.g. `0x1::Block::BlockMetadata { height: 100 }`.
+    pub fn view(&self, path: &AccessPath, blob: &[u8]) -> Result<String> {
+        let tag = resource_tag(path)?;
+        let mut input = blob;
+        let mut out = String::new();
+        self.view_struct(&tag, &mut input, &mut out)?;
+        ensure!(
+            input.is_empty(),
+            "Unexpected {} trailing bytes of the resource.",
+            input.len()
+        );
+        Ok(out)
+    }
+
+    fn view_struct(&self, tag: &StructTag, input: &mut &[u8], out: &mut String) -> Result<()> {
+        write_struct_tag(tag, out)?;
+        out.push_str(" {");
+        for (index, (name, field_type)) in self.fields(tag)?.iter().enumerate() {
+            if index != 0 {
+                out.push(',');
+            }
+            write!(out, " {}: ", name)?;
+            self.view_value(field_type, input, out)?;
+        }
+        out.push_str(" }");
+        Ok(())
+    }
+
+    fn view_value(&self, value_type: &TypeTag, input: &mut &[u8], out: &mut String) -> Result<()> {
+        match value_type {
+            TypeTag::Bool => match read(input, 1)?[0] {
+                0 => out.push_str("false"),
+                1 => out.push_str("true"),
+                val => bail!("Invalid bool value {}.", val),
+            },
+            TypeTag::U8 => write!(out, "{}", read(input, 1)?[0])?,
+            TypeTag::U64 => {
+                let mut buf = [0; 8];
+                buf.copy_from_slice(read(input, 8)?);
+                write!(out, "{}", u64::from_le_bytes(buf))?;
+            }
+            TypeTag::U128 => {
+                let mut buf = [0; 16];
+                buf.copy_from_slice(read(input, 16)?);
+                write!(out, "{}", u128::from_le_bytes(buf))?;
+            }
+            TypeTag::Address => write_address(read(input, AccountAddress::LENGTH)?, out),
+            TypeTag::Signer => bail!("Resources can't contain signer values."),
+            TypeTag::Vector(item_type) => {
+                let len = read_len(input)?;
+                if item_type.as_ref() == &TypeTag::U8 {
+                    write!(out, "0x{}", hex::encode(read(input, len)?))?;
+                } else {
+                    out.push('[');
+                    for index in 0..len {
+                        if index != 0 {
+                            out.push_str(", ");
+                        }
+                        self.view_value(item_type, input, out)?;
+                    }
+                    out.push(']');
+                }
+            }
+            TypeTag::Struct(tag) => self.view_struct(tag, input, out)?,
+        }
+        Ok(())
+    }
+
+    /// Returns names and types of the struct fields.
+    fn fields(&self, tag: &StructTag) -> Result<Vec<(String, TypeTag)>> {
+        let id = ModuleId::new(tag.address, tag.module.clone());
+        let bytecode = self
+            .modules
+            .get_module(&id)
+            .map_err(|err| err.into_vm_status())?
+            .ok_or_else(|| anyhow!("Module 0x{}::{} not found.", id.address(), id.name()))?;
+        let module = CompiledModule::deserialize(&bytecode)
+            .map_err(|err| err.finish(Location::Undefined).into_vm_status())?;
+
+        let def = module
+            .struct_defs()
+            .iter()
+            .find(|def| {
+                module.identifier_at(module.struct_handle_at(def.struct_handle).name)
+                    == tag.name.as_ident_str()
+            })
+            .ok_or_else(|| anyhow!("Struct {} not found.", tag.name))?;
+        match &def.field_information {
+            StructFieldInformation::Native => bail!("Native struct {} can't be decoded.", tag.name),
+            StructFieldInformation::Declared(fields) => fields
+                .iter()
+                .map(|field| {
+                    Ok((
+                        module.identifier_at(field.name).to_string(),
+                        type_tag(&module, &field.signature.0, &tag.type_params)?,
+                    ))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Returns the struct tag of the resource access path.
+fn resource_tag(path: &AccessPath) -> Result<StructTag> {
+    path.path
+        .get(1..)
+        .and_then(|tag| lcs::from_bytes::<StructTag>(tag).ok())
+        .filter(|tag| tag.access_vector() == path.path)
+        .ok_or_else(|| {
+            anyhow!(
+                "Access path {} is not a resource path.",
+                hex::encode(&path.path)
+            )
+        })
+}
+
+/// Makes the type tag of the signature token with the given type parameters.
+fn type_tag(
+    module: &CompiledModule,
+    token: &SignatureToken,
+    type_params: &[TypeTag],
+) -> Result<TypeTag> {
+    Ok(match token {
+        SignatureToken::Bool => TypeTag::Bool,
+        SignatureToken::U8 => TypeTag::U8,
+        SignatureToken::U64 => TypeTag::U64,
+        SignatureToken::U128 => TypeTag::U128,
+        SignatureToken::Address => TypeTag::Address,
+        SignatureToken::Signer => TypeTag::Signer,
+        SignatureToken::Vector(item) => {
+            TypeTag::Vector(Box::new(type_tag(module, item, type_params)?))
+        }
+        SignatureToken::Struct(index) => TypeTag::Struct(struct_tag(module, *index, vec![])),
+        SignatureToken::StructInstantiation(index, tokens) => {
+            let params = tokens
+                .iter()
+                .map(|token| type_tag(module, token, type_params))
+                .collect::<Result<_>>()?;
+            TypeTag::Struct(struct_tag(module, *index, params))
+        }
+        SignatureToken::TypeParameter(index) => type_params
+            .get(*index as usize)
+            .cloned()
+            .ok_or_else(|| anyhow!("Type parameter {} is not provided.", index))?,
+        SignatureToken::Reference(_) | SignatureToken::MutableReference(_) => {
+            bail!("Unexpected reference type.")
+        }
+    })
+}
+
+fn struct_tag(
+    module: &CompiledModule,
+    index: StructHandleIndex,
+    type_params: Vec<TypeTag>,
+) -> StructTag {
+    let handle = module.struct_handle_at(index);
+    let module_handle = module.module_handle_at(handle.module);
+    StructTag {
+        address: *module.address_identifier_at(module_handle.address),
+        module: module.identifier_at(module_handle.name).to_owned(),
+        name: module.identifier_at(handle.name).to_owned(),
+        type_params,
+    }
+}
+
+fn write_struct_tag(tag: &StructTag, out: &mut String) -> Result<()> {
+    write_address(tag.address.as_ref(), out);
+    write!(out, "::{}::{}", tag.module, tag.name)?;
+    if !tag.type_params.is_empty() {
+        out.push('<');
+        for (index, param) in tag.type_params.iter().enumerate() {
+            if index != 0 {
+                out.push_str(", ");
+            }
+            write_type_tag(param, out)?;
+        }
+        out.push('>');
+    }
+    Ok(())
+}
+
+fn write_type_tag(tag: &TypeTag, out: &mut String) -> Result<()> {
+    match tag {
+        TypeTag::Bool => out.push_str("bool"),
+        TypeTag::U8 => out.push_str("u8"),
+        TypeTag::U64 => out.push_str("u64"),
+        TypeTag::U128 => out.push_str("u128"),
+        TypeTag::Address => out.push_str("address"),
+        TypeTag::Signer => out.push_str("signer"),
+        TypeTag::Vector(item) => {
+            out.push_str("vector<");
+            write_type_tag(item, out)?;
+            out.push('>');
+        }
+        TypeTag::Struct(tag) => write_struct_tag(tag, out)?,
+    }
+    Ok(())
+}
+
+/// Writes the address without leading zeros.
+fn write_address(address: &[u8], out: &mut String) {
+    let address = hex::encode(address);
+    let address = address.trim_start_matches('0');
+    out.push_str("0x");
+    out.push_str(if address.is_empty() { "0" } else { address });
+}
+
+fn read<'b>(input: &mut &'b [u8], len: usize) -> Result<&'b [u8]> {
+    ensure!(input.len() >= len, "Unexpected end of the resource.");
+    let (value, rest) = input.split_at(len);
+    *input = rest;
+    Ok(value)
+}
+
+/// Reads ULEB128 encoded vector length.
+fn read_len(input: &mut &[u8]) -> Result<usize> {
+    let mut len = 0;
+    for shift in (0..32).step_by(7) {
+        let byte = read(input, 1)?[0];
+        len |= usize::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(len);
+        }
+    }
+    bail!("Invalid vector length.")
+}
@@ -1,4 +1,4 @@
-use libra::prelude::{CORE_CODE_ADDRESS, SignatureToken};
+use libra::prelude::{CORE_CODE_ADDRESS, SignatureToken, ModuleId, Identifier, RemoteCache};
 use libra::file_format::Kind;
 use compiler::Compiler;
 use ds::MockDataSource;
@@ -229,3 +229,51 @@ fn test_module_metadata() {
         }
     );
 }
+
+#[test]
+fn test_nft_metadata() {
+    // Explorers decode NFT resources with the layout from the module metadata.
+    let ds = MockDataSource::with_write_set(build_std());
+    let module = ds
+        .get_module(&ModuleId::new(
+            CORE_CODE_ADDRESS,
+            Identifier::new("NFT").unwrap(),
+        ))
+        .unwrap()
+        .unwrap();
+
+    let structs = match extract_bytecode_metadata(&module).unwrap() {
+        Metadata::Module { name, structs, .. } => {
+            assert_eq!(name, "NFT");
+            structs
+        }
+        Metadata::Script { .. } => panic!("Expected module metadata."),
+    };
+    let find = |name: &str| structs.iter().find(|s| s.name == name).unwrap().clone();
+
+    let fields = |meta: StructMeta| {
+        meta.fields
+            .into_iter()
+            .map(|f| (f.name, f.f_type))
+            .collect::<Vec<_>>()
+    };
+
+    let token = find("Token");
+    assert!(token.is_resource);
+    assert_eq!(token.type_params, vec!["T".to_owned()]);
+    assert_eq!(
+        fields(token),
+        vec![
+            ("creator".to_owned(), "address".to_owned()),
+            ("id".to_owned(), "u64".to_owned()),
+            ("metadata".to_owned(), "vector<u8>".to_owned()),
+        ]
+    );
+
+    let gallery = find("Gallery");
+    assert!(gallery.is_resource);
+    assert_eq!(
+        fields(gallery),
+        vec![("tokens".to_owned(), "vector<Token<T>>".to_owned())]
+    );
+}
@@ -0,0 +1,110 @@
+use libra::prelude::*;
+use compiler::Compiler;
+use ds::MockDataSource;
+use dvm_lang::bytecode::resource::ResourceViewer;
+use stdlib::build_std;
+
+fn store_tag(type_params: Vec<TypeTag>) -> StructTag {
+    StructTag {
+        address: CORE_CODE_ADDRESS,
+        module: Identifier::new("Store").unwrap(),
+        name: Identifier::new("Item").unwrap(),
+        type_params,
+    }
+}
+
+fn data_source() -> MockDataSource {
+    let ds = MockDataSource::with_write_set(build_std());
+    let compiler = Compiler::new(ds.clone());
+    let module = compiler
+        .compile(
+            r"
+            module Store {
+                struct Flag { is_set: bool }
+
+                resource struct Item<T> {
+                    value: T,
+                    data: vector<u8>,
+                    flags: vector<Flag>,
+                    owner: address,
+                }
+            }
+        ",
+            Some(CORE_CODE_ADDRESS),
+        )
+        .unwrap();
+    ds.publish_module(module).unwrap();
+    ds
+}
+
+#[test]
+fn test_view_resource() {
+    let ds = data_source();
+    let viewer = ResourceViewer::new(&ds);
+
+    let path = AccessPath::new(
+        CORE_CODE_ADDRESS,
+        store_tag(vec![TypeTag::U64]).access_vector(),
+    );
+    let blob =
+        lcs::to_bytes(&(100u64, vec![1u8, 2], vec![true, false], CORE_CODE_ADDRESS)).unwrap();
+    assert_eq!(
+        viewer.view(&path, &blob).unwrap(),
+        "0x1::Store::Item<u64> { value: 100, data: 0x0102, \
+        flags: [0x1::Store::Flag { is_set: true }, 0x1::Store::Flag { is_set: false }], owner: 0x1 }"
+    );
+
+    let tag = store_tag(vec![TypeTag::Struct(store_tag(vec![TypeTag::Bool]))]);
+    let path = AccessPath::new(CORE_CODE_ADDRESS, tag.access_vector());
+    let blob = lcs::to_bytes(&(
+        (
+            true,
+            Vec::<u8>::new(),
+            Vec::<bool>::new(),
+            CORE_CODE_ADDRESS,
+        ),
+        Vec::<u8>::new(),
+        Vec::<bool>::new(),
+        CORE_CODE_ADDRESS,
+    ))
+    .unwrap();
+    assert_eq!(
+        viewer.view(&path, &blob).unwrap(),
+        "0x1::Store::Item<0x1::Store::Item<bool>> { \
+        value: 0x1::Store::Item<bool> { value: true, data: 0x, flags: [], owner: 0x1 }, \
+        data: 0x, flags: [], owner: 0x1 }"
+    );
+}
+
+#[test]
+fn test_invalid_resource() {
+    let ds = data_source();
+    let viewer = ResourceViewer::new(&ds);
+    let path = AccessPath::new(
+        CORE_CODE_ADDRESS,
+        store_tag(vec![TypeTag::U64]).access_vector(),
+    );
+
+    let blob = lcs::to_bytes(&(100u64, vec![1u8, 2])).unwrap();
+    assert!(viewer.view(&path, &blob).is_err());
+
+    let mut blob =
+        lcs::to_bytes(&(100u64, vec![1u8], Vec::<bool>::new(), CORE_CODE_ADDRESS)).unwrap();
+    blob.push(0);
+    assert!(viewer.view(&path, &blob).is_err());
+
+    let blob = lcs::to_bytes(&(
+        100u64,
+        Vec::<u8>::new(),
+        Vec::<bool>::new(),
+        CORE_CODE_ADDRESS,
+    ))
+    .unwrap();
+    let mut unknown = store_tag(vec![TypeTag::U64]);
+    unknown.module = Identifier::new("Unknown").unwrap();
+    let path = AccessPath::new(CORE_CODE_ADDRESS, unknown.access_vector());
+    assert!(viewer.view(&path, &blob).is_err());
+    assert!(viewer
+        .view(&AccessPath::new(CORE_CODE_ADDRESS, vec![0, 1, 2]), &blob)
+        .is_err());
+}
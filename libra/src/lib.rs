@@ -55,7 +55,9 @@ pub mod compiler {
     pub use move_lang::shared::Address;
     pub use move_lang::errors::{FilesSourceText, Errors, output_errors};
     pub use move_lang::name_pool::ConstPool;
-    pub use move_lang::move_check;
+    pub use move_lang::{move_check, move_check_no_report};
+    pub use move_ir_types::location::Loc;
+    pub use codespan::{ByteIndex, Span};
 }
 
 pub mod file_format {
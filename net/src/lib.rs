@@ -10,6 +10,12 @@ pub type StdError = Box<dyn std::error::Error + Send + Sync>;
 pub mod endpoint;
 pub mod serve;
 
+/// Compiler api with the structured diagnostics of the failed compilation.
+/// Wire compatible with the compiler api of the `api` crate.
+pub mod compiler_api {
+    tonic::include_proto!("dfinance.dvm");
+}
+
 #[cfg(unix)]
 mod unix;
 
@@ -28,6 +28,55 @@ pub struct CurrentTimestamp {
 pub struct Price {
     /// Currency price.
     pub price: u128,
+    /// Unix time of the last price update in seconds, empty if it is unknown.
+    pub timestamp: Vec<u64>,
+}
+
+/// Account which pays the transaction fee.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct FeePayer {
+    /// Fee payer address.
+    pub payer: AccountAddress,
+}
+
+/// Registered currency.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct RegisteredCurrency {
+    /// Currency ticker.
+    pub ticker: Vec<u8>,
+    /// Denom.
+    pub denom: Vec<u8>,
+    /// Decimals.
+    pub decimals: u8,
+    /// Is token.
+    pub is_token: bool,
+    /// Owner address.
+    pub owner: AccountAddress,
+    /// Total supply.
+    pub total_supply: u128,
+}
+
+/// Registry of all currencies registered in the chain.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct CurrencyRegistry {
+    /// Registered currencies ordered by ticker.
+    pub currencies: Vec<RegisteredCurrency>,
+}
+
+/// Total supply of currency at the block.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct SupplyRecord {
+    /// Block height.
+    pub height: u64,
+    /// Total supply.
+    pub total_supply: u128,
+}
+
+/// Total supply history of currency.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct SupplyHistory {
+    /// Records ordered by block height.
+    pub records: Vec<SupplyRecord>,
 }
 
 /// Returns oracle metadata struct tag.
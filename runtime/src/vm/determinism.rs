@@ -0,0 +1,115 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use ds::ReadSet;
+use dvm_info::metrics::execution::ExecutionResult as ActionResult;
+use dvm_info::metrics::meter::ScopeMeter;
+
+use crate::vm::types::ExecutionResult;
+
+/// Sampler of transactions for the determinism check.
+///
+/// Every `rate`-th transaction is sampled, `0` disables the check.
+#[derive(Debug, Default)]
+pub struct DeterminismCheck {
+    rate: u64,
+    counter: AtomicU64,
+}
+
+impl DeterminismCheck {
+    /// Create a new sampler which samples every `rate`-th transaction.
+    pub fn new(rate: u64) -> DeterminismCheck {
+        DeterminismCheck {
+            rate,
+            counter: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns `true` if the next transaction must be checked.
+    pub fn is_sampled(&self) -> bool {
+        self.rate != 0 && self.counter.fetch_add(1, Ordering::Relaxed) % self.rate == 0
+    }
+}
+
+/// Returns names of the fields which differ in the execution results.
+pub fn diverged_fields(first: &ExecutionResult, second: &ExecutionResult) -> Vec<&'static str> {
+    let mut fields = vec![];
+    if first.write_set != second.write_set {
+        fields.push("write_set");
+    }
+    if first.events != second.events {
+        fields.push("events");
+    }
+    if first.wallet_ops != second.wallet_ops {
+        fields.push("wallet_ops");
+    }
+    if first.gas_used != second.gas_used {
+        fields.push("gas_used");
+    }
+    if first.status != second.status {
+        fields.push("status");
+    }
+    fields
+}
+
+/// Outcome of the determinism check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Determinism {
+    /// The replay result is equal to the execution result.
+    Deterministic,
+    /// The replay result differs in the fields.
+    Diverged(Vec<&'static str>),
+    /// Some data source reads have failed, so the replay is not equivalent to the execution.
+    Incomplete,
+}
+
+impl Determinism {
+    /// Returns the metric status code of the outcome.
+    fn status_code(&self) -> u64 {
+        match self {
+            Determinism::Deterministic => 200,
+            Determinism::Diverged(_) => 500,
+            Determinism::Incomplete => 424,
+        }
+    }
+}
+
+/// Compares the execution result with its replay and reports divergence.
+///
+/// Divergence is logged as an error, so it is sent to Sentry if the integration is enabled.
+/// The check outcome is stored to the `determinism_check` metric:
+/// `200` if the execution is deterministic, `500` if it diverged
+/// and `424` if the replay was skipped because of the failed reads.
+pub fn check(
+    tx: &str,
+    reads: &ReadSet,
+    result: &ExecutionResult,
+    replay: &ExecutionResult,
+) -> Determinism {
+    let mut meter = ScopeMeter::new("determinism_check");
+    let determinism = if reads.is_incomplete() {
+        // Failed reads make the replay meaningless.
+        warn!("Skip determinism check of {}: incomplete read set.", tx);
+        Determinism::Incomplete
+    } else {
+        let fields = diverged_fields(result, replay);
+        if fields.is_empty() {
+            Determinism::Deterministic
+        } else {
+            error!(
+                "Nondeterministic execution of {}: {} diverged. Result: {:?}. Replay: {:?}",
+                tx,
+                fields.join(", "),
+                result,
+                replay
+            );
+            Determinism::Diverged(fields)
+        }
+    };
+
+    meter.set_result(ActionResult::new(
+        determinism == Determinism::Deterministic,
+        determinism.status_code(),
+        result.gas_used,
+    ));
+    determinism
+}
@@ -1,12 +1,13 @@
 use std::fmt;
 use std::sync::RwLock;
 
-use ds::{BlackListDataSource, DataSource};
+use ds::{BlackListDataSource, DataSource, RecordingDataSource, ReplayDataSource};
 use dvm_info::memory_check::MemoryChecker;
 use libra::{gas::*, prelude::*, vm::*};
 
 use crate::gas_schedule;
-use crate::vm::session::StateViewSession;
+use crate::vm::determinism::{self, DeterminismCheck};
+use crate::vm::session::{ticker, StateViewSession, XFI};
 use crate::vm::types::*;
 
 /// Dfinance virtual machine.
@@ -19,6 +20,12 @@ pub struct Dvm<D: DataSource> {
     cost_table: CostTable,
     /// Dvm memory checker.
     mem_checker: Option<MemoryChecker>,
+    /// Charge transaction fees.
+    charge_fee: bool,
+    /// Determinism check sampler.
+    determinism: DeterminismCheck,
+    /// Max number of script signers.
+    signers_limit: usize,
 }
 
 impl<D> Dvm<D>
@@ -34,22 +41,178 @@ where
             ds,
             cost_table: gas_schedule::cost_table(),
             mem_checker,
+            charge_fee: false,
+            determinism: Default::default(),
+            signers_limit: DEFAULT_SIGNERS_LIMIT,
         }
     }
 
+    /// Sets max number of script signers, `DEFAULT_SIGNERS_LIMIT` by default.
+    /// Scripts with more signers are rejected before execution.
+    pub fn with_signers_limit(mut self, limit: usize) -> Dvm<D> {
+        self.signers_limit = limit;
+        self
+    }
+
+    /// Returns the data source of the vm.
+    pub fn data_source(&self) -> &D {
+        &self.ds
+    }
+
+    /// Returns max number of script signers.
+    /// Script transactions for this vm must be built with this limit.
+    pub fn signers_limit(&self) -> usize {
+        self.signers_limit
+    }
+
+    /// Enables determinism check of every `rate`-th transaction, `0` disables the check.
+    ///
+    /// The sampled transaction is executed with recording of all data source reads,
+    /// then it is executed again on a fresh `MoveVM` with the recorded reads.
+    /// Any difference in write set, events, balance operations, gas or status
+    /// is reported as an error and stored to the `determinism_check` metric.
+    /// The result of the first execution is returned.
+    pub fn with_determinism_check(mut self, rate: u64) -> Dvm<D> {
+        self.determinism = DeterminismCheck::new(rate);
+        self
+    }
+
+    /// Enables transaction fees charging.
+    ///
+    /// The fee is `gas_used * gas_unit_price` in `XFI` coins paid by the transaction fee payer
+    /// (the first sender if the payer is not set).
+    /// Transactions of payers whose native `XFI` balance does not cover the max fee
+    /// (`max_gas_amount * gas_unit_price`) are rejected before execution.
+    /// After execution the balance must cover the fee together with the `XFI` outflow
+    /// of the payer, otherwise the transaction changes are discarded and only the fee is charged.
+    pub fn with_fee(mut self, charge_fee: bool) -> Dvm<D> {
+        self.charge_fee = charge_fee;
+        self
+    }
+
+    /// Checks that `payer` is able to pay the max fee.
+    /// Returns rejected execution result if the balance is insufficient.
+    fn check_fee_balance(
+        &self,
+        payer: AccountAddress,
+        gas: &Gas,
+    ) -> Result<Option<ExecutionResult>, VMStatus> {
+        if !self.charge_fee {
+            return Ok(None);
+        }
+
+        let max_fee = Fee::max(payer, gas);
+        let balance = self.xfi_balance(payer)?;
+        if balance < max_fee.amount {
+            Ok(Some(ExecutionResult::error(
+                0,
+                insufficient_balance(balance, max_fee.amount),
+            )))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Returns native `XFI` balance of `address`.
+    fn xfi_balance(&self, address: AccountAddress) -> Result<u128, VMStatus> {
+        Ok(self
+            .ds
+            .get_balance(address, XFI.to_owned())
+            .map_err(|err| {
+                error!("Failed to get balance:'{}' {:?}", address, err);
+                VMStatus::Error(StatusCode::STORAGE_ERROR)
+            })?
+            .unwrap_or_default())
+    }
+
+    /// Adds fee to the execution result.
+    ///
+    /// The payer balance must cover the fee together with the `XFI` outflow of the payer,
+    /// otherwise the execution result is replaced with the error without chain changes.
+    fn charge(
+        &self,
+        result: ExecutionResult,
+        payer: AccountAddress,
+        gas_unit_price: u64,
+    ) -> Result<ExecutionResult, VMStatus> {
+        if !self.charge_fee {
+            return Ok(result);
+        }
+
+        let fee = Fee::new(payer, result.gas_used, gas_unit_price);
+        let required = xfi_outflow(&result.wallet_ops, payer).saturating_add(fee.amount);
+        let balance = self.xfi_balance(payer)?;
+        let mut result = if balance < required {
+            ExecutionResult::error(result.gas_used, insufficient_balance(balance, required))
+        } else {
+            result
+        };
+        result.fee = Some(fee);
+        Ok(result)
+    }
+
     /// Publishes module to the chain.
     pub fn publish_module(&self, gas: Gas, module: ModuleTx) -> VmResult {
         self.perform_memory_prevention();
 
         let (module, sender) = module.into_inner();
 
+        if let Some(rejected) = self.check_fee_balance(sender, &gas)? {
+            return Ok(rejected);
+        }
+        let gas_unit_price = gas.gas_unit_price();
+
+        let result = if sender == CORE_CODE_ADDRESS {
+            self.publish(gas, &module, sender, |module_id, cost_strategy| {
+                self.ds.remove_module(module_id);
+                self.clear_cache();
+
+                let mut blacklist = BlackListDataSource::new(self.ds.clone());
+                blacklist.add_module(module_id);
+                let vm = self.vm.read().unwrap();
+                publish_session(&vm, &blacklist, &module, sender, cost_strategy)
+            })?
+        } else if self.determinism.is_sampled() {
+            let ds = RecordingDataSource::new(self.ds.clone());
+            let result = self.publish(gas, &module, sender, |_, cost_strategy| {
+                let vm = self.vm.read().unwrap();
+                publish_session(&vm, &ds, &module, sender, cost_strategy)
+            })?;
+
+            let reads = ds.reads();
+            let replay_ds = ReplayDataSource::new(self.ds.clone(), reads.clone());
+            let replay = self.publish(gas, &module, sender, |_, cost_strategy| {
+                publish_session(&MoveVM::new(), &replay_ds, &module, sender, cost_strategy)
+            });
+            match replay {
+                Ok(replay) => {
+                    determinism::check("module", &reads, &result, &replay);
+                }
+                Err(status) => error!("Failed to replay module: {:?}", status),
+            }
+            result
+        } else {
+            self.publish(gas, &module, sender, |_, cost_strategy| {
+                let vm = self.vm.read().unwrap();
+                publish_session(&vm, &self.ds, &module, sender, cost_strategy)
+            })?
+        };
+
+        self.charge(result, sender, gas_unit_price)
+    }
+
+    /// Verifies module and publishes it with `publish` function.
+    fn publish<F>(&self, gas: Gas, module: &[u8], sender: AccountAddress, publish: F) -> VmResult
+    where
+        F: FnOnce(&ModuleId, &mut CostStrategy) -> VMResult<TransactionEffects>,
+    {
         let mut cost_strategy =
             CostStrategy::transaction(&self.cost_table, GasUnits::new(gas.max_gas_amount()));
 
         cost_strategy
             .charge_intrinsic_gas(AbstractMemorySize::new(module.len() as u64))
             .map_err(|err| err.into_vm_status())?;
-        let res = CompiledModule::deserialize(&module)
+        let res = CompiledModule::deserialize(module)
             .map_err(|e| e.finish(Location::Undefined))
             .and_then(|compiled_module| {
                 let module_id = compiled_module.self_id();
@@ -62,37 +225,7 @@ where
 
                 cost_strategy.charge_intrinsic_gas(AbstractMemorySize::new(module.len() as u64))?;
 
-                if sender == CORE_CODE_ADDRESS {
-                    self.ds.remove_module(&module_id);
-                    self.clear_cache();
-
-                    let mut blacklist = BlackListDataSource::new(self.ds.clone());
-                    blacklist.add_module(&module_id);
-                    let vm = self.vm.read().unwrap();
-                    let (sv, bank) = StateViewSession::session(&blacklist, 0, 0);
-                    let mut session = vm.new_session(&sv, bank);
-
-                    session
-                        .publish_module(
-                            module.to_vec(),
-                            sender,
-                            &mut cost_strategy,
-                            &NoContextLog::new(),
-                        )
-                        .and_then(|_| session.finish())
-                } else {
-                    let vm = self.vm.read().unwrap();
-                    let (sv, bank) = StateViewSession::session(&self.ds, 0, 0);
-                    let mut session = vm.new_session(&sv, bank);
-                    session
-                        .publish_module(
-                            module.to_vec(),
-                            sender,
-                            &mut cost_strategy,
-                            &NoContextLog::new(),
-                        )
-                        .and_then(|_| session.finish())
-                }
+                publish(&module_id, &mut cost_strategy)
             });
 
         Ok(ExecutionResult::new(cost_strategy, gas, res))
@@ -115,31 +248,132 @@ where
     /// Executes passed script on the chain.
     pub fn execute_script(&self, gas: Gas, tx: ScriptTx) -> VmResult {
         self.perform_memory_prevention();
-        let vm = self.vm.read().unwrap();
-        let (script, args, type_args, senders, timestamp, block) = tx.into_inner();
+        let payer = tx.fee_payer();
 
-        let (sv, bank) = StateViewSession::session(&self.ds, timestamp, block);
+        if tx.senders().len() > self.signers_limit {
+            return Ok(ExecutionResult::error(
+                0,
+                PartialVMError::new(StatusCode::DATA_FORMAT_ERROR)
+                    .with_message(format!(
+                        "Number of senders must be in the range from 1 to {}",
+                        self.signers_limit
+                    ))
+                    .finish(Location::Undefined),
+            ));
+        }
 
-        let mut session = vm.new_session(&sv, bank);
+        if let Some(rejected) = self.check_fee_balance(payer, &gas)? {
+            return Ok(rejected);
+        }
+        let gas_unit_price = gas.gas_unit_price();
 
-        let mut cost_strategy =
-            CostStrategy::transaction(&self.cost_table, GasUnits::new(gas.max_gas_amount()));
+        let replay_tx = if self.determinism.is_sampled() {
+            tx.try_clone()
+                .map_err(|err| warn!("Failed to copy script transaction: {:?}", err))
+                .ok()
+        } else {
+            None
+        };
 
-        let res = session
-            .execute_script(
-                script,
-                type_args,
-                args,
-                senders,
-                &mut cost_strategy,
-                &NoContextLog::new(),
-            )
-            .and_then(|_| session.finish());
+        let result = match replay_tx {
+            Some(replay_tx) => {
+                let ds = RecordingDataSource::new(self.ds.clone());
+                let result =
+                    execute_session(&self.vm.read().unwrap(), &ds, &self.cost_table, gas, tx);
 
-        Ok(ExecutionResult::new(cost_strategy, gas, res))
+                let reads = ds.reads();
+                let replay_ds = ReplayDataSource::new(self.ds.clone(), reads.clone());
+                let replay =
+                    execute_session(&MoveVM::new(), &replay_ds, &self.cost_table, gas, replay_tx);
+                determinism::check("script", &reads, &result, &replay);
+                result
+            }
+            None => execute_session(
+                &self.vm.read().unwrap(),
+                &self.ds,
+                &self.cost_table,
+                gas,
+                tx,
+            ),
+        };
+
+        self.charge(result, payer, gas_unit_price)
     }
 }
 
+/// Returns error of the insufficient balance for transaction fee.
+fn insufficient_balance(balance: u128, required: u128) -> VMError {
+    PartialVMError::new(StatusCode::INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE)
+        .with_message(format!(
+            "Insufficient {} balance: {} < {}",
+            XFI, balance, required
+        ))
+        .finish(Location::Undefined)
+}
+
+/// Returns net decrease of `XFI` native balance of `address` by the balance operations.
+fn xfi_outflow(wallet_ops: &[(WalletId, BalanceOperation)], address: AccountAddress) -> u128 {
+    let (outflow, inflow) = wallet_ops
+        .iter()
+        .filter(|(wallet_id, _)| {
+            wallet_id.address == address && ticker(wallet_id).as_deref() == Some(XFI)
+        })
+        .fold((0u128, 0u128), |(outflow, inflow), (_, op)| match op {
+            BalanceOperation::Deposit(amount) => (outflow.saturating_add(*amount), inflow),
+            BalanceOperation::Withdraw(amount) => (outflow, inflow.saturating_add(*amount)),
+        });
+    outflow.saturating_sub(inflow)
+}
+
+/// Publishes module in a new session of `vm` with data source `ds`.
+fn publish_session<S: DataSource>(
+    vm: &MoveVM,
+    ds: &S,
+    module: &[u8],
+    sender: AccountAddress,
+    cost_strategy: &mut CostStrategy,
+) -> VMResult<TransactionEffects> {
+    let (sv, bank) = StateViewSession::session(ds, 0, 0);
+    let mut session = vm.new_session(&sv, bank);
+    session
+        .publish_module(module.to_vec(), sender, cost_strategy, &NoContextLog::new())
+        .and_then(|_| session.finish())
+}
+
+/// Executes script in a new session of `vm` with data source `ds`.
+fn execute_session<S: DataSource>(
+    vm: &MoveVM,
+    ds: &S,
+    cost_table: &CostTable,
+    gas: Gas,
+    tx: ScriptTx,
+) -> ExecutionResult {
+    let payer = tx.fee_payer();
+    let signers_auth = tx.signers_auth().clone();
+    let (script, args, type_args, senders, timestamp, block) = tx.into_inner();
+
+    let (sv, bank) = StateViewSession::session(ds, timestamp, block);
+    let sv = sv.with_fee_payer(payer).with_signers_auth(signers_auth);
+
+    let mut session = vm.new_session(&sv, bank);
+
+    let mut cost_strategy =
+        CostStrategy::transaction(cost_table, GasUnits::new(gas.max_gas_amount()));
+
+    let res = session
+        .execute_script(
+            script,
+            type_args,
+            args,
+            senders,
+            &mut cost_strategy,
+            &NoContextLog::new(),
+        )
+        .and_then(|_| session.finish());
+
+    ExecutionResult::new(cost_strategy, gas, res)
+}
+
 impl<D> fmt::Debug for Dvm<D>
 where
     D: DataSource,
@@ -1,3 +1,5 @@
+/// Determinism check of transaction execution.
+pub mod determinism;
 /// Dfinance virtual machine.
 pub mod dvm;
 /// State view session.
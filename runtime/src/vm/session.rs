@@ -1,4 +1,7 @@
+use std::collections::BTreeMap;
+
 use ds::DataSource;
+use serde::Serialize;
 use libra::account::{AccountAddress, CORE_CODE_ADDRESS};
 use libra::ds::{RemoteCache, StructTag, TypeTag};
 use libra::lcs;
@@ -9,12 +12,19 @@ use libra::result::StatusCode;
 use libra::result::VMResult;
 use libra::vm::{NativeBalance, WalletId};
 
+use crate::vm::types::SignerAuth;
+use crate::resources::{
+    CurrencyRegistry, FeePayer, Price, RegisteredCurrency, SupplyHistory, SupplyRecord,
+};
+
 /// Execution session.
 #[derive(Clone)]
 pub struct StateViewSession<'a, D: DataSource> {
     ds: &'a D,
     timestamp: u64,
     block: u64,
+    fee_payer: Option<AccountAddress>,
+    signers_auth: BTreeMap<AccountAddress, SignerAuth>,
 }
 
 impl<'a, D: DataSource> StateViewSession<'a, D> {
@@ -29,10 +39,27 @@ impl<'a, D: DataSource> StateViewSession<'a, D> {
                 ds,
                 timestamp,
                 block,
+                fee_payer: None,
+                signers_auth: Default::default(),
             },
             Box::new(Bank { ds: ds.clone() }),
         )
     }
+
+    /// Sets transaction fee payer.
+    pub fn with_fee_payer(mut self, fee_payer: AccountAddress) -> StateViewSession<'a, D> {
+        self.fee_payer = Some(fee_payer);
+        self
+    }
+
+    /// Sets authorization metadata of the transaction signers.
+    pub fn with_signers_auth(
+        mut self,
+        signers_auth: BTreeMap<AccountAddress, SignerAuth>,
+    ) -> StateViewSession<'a, D> {
+        self.signers_auth = signers_auth;
+        self
+    }
 }
 
 struct Bank<D: DataSource> {
@@ -62,23 +89,38 @@ impl<'a, D: DataSource> RemoteCache for StateViewSession<'a, D> {
         address: &AccountAddress,
         tag: &StructTag,
     ) -> PartialVMResult<Option<Vec<u8>>> {
+        if tag.address == CORE_CODE_ADDRESS
+            && tag.module.as_str() == "Signer"
+            && tag.name.as_str() == "Authorization"
+        {
+            return self.signers_auth.get(address).map(to_bytes).transpose();
+        }
+
         if *address == CORE_CODE_ADDRESS && tag.address == CORE_CODE_ADDRESS {
             match (tag.module.as_str(), tag.name.as_str()) {
                 ("Block", "BlockMetadata") => Ok(Some(self.block.to_le_bytes().to_vec())),
                 ("Time", "CurrentTimestamp") => Ok(Some(self.timestamp.to_le_bytes().to_vec())),
+                ("Transaction", "FeePayer") => self
+                    .fee_payer
+                    .map(|payer| to_bytes(&FeePayer { payer }))
+                    .transpose(),
                 ("Coins", "Price") => {
                     if tag.type_params.len() == 2 {
                         let first_part = extract_name(&tag.type_params[0])
                             .ok_or_else(|| PartialVMError::new(StatusCode::INTERNAL_TYPE_ERROR))?;
                         let second_part = extract_name(&tag.type_params[1])
                             .ok_or_else(|| PartialVMError::new(StatusCode::INTERNAL_TYPE_ERROR))?;
-                        self.ds
-                            .get_price(first_part, second_part)
-                            .map(|price| price.map(|p| p.to_le_bytes().to_vec()))
-                            .map_err(|err| {
-                                PartialVMError::new(StatusCode::MISSING_DEPENDENCY)
-                                    .with_message(err.to_string())
+                        let price = self.ds.get_price(first_part, second_part);
+                        match price {
+                            Ok(Some(price)) => to_bytes(&Price {
+                                price: price.price,
+                                timestamp: price.timestamp.into_iter().collect(),
                             })
+                            .map(Some),
+                            Err(err) => Err(PartialVMError::new(StatusCode::MISSING_DEPENDENCY)
+                                .with_message(err.to_string())),
+                            Ok(None) => Ok(None),
+                        }
                     } else {
                         Err(PartialVMError::new(StatusCode::INTERNAL_TYPE_ERROR))
                     }
@@ -101,6 +143,41 @@ impl<'a, D: DataSource> RemoteCache for StateViewSession<'a, D> {
                         Err(PartialVMError::new(StatusCode::INTERNAL_TYPE_ERROR))
                     }
                 }
+                ("Dfinance", "Registry") => {
+                    if tag.type_params.is_empty() {
+                        self.currency_registry()
+                            .and_then(|registry| to_bytes(&registry))
+                            .map(Some)
+                    } else {
+                        Err(PartialVMError::new(StatusCode::INTERNAL_TYPE_ERROR))
+                    }
+                }
+                ("Dfinance", "SupplyHistory") => {
+                    if tag.type_params.len() == 1 {
+                        let ticker = extract_name(&tag.type_params[0])
+                            .ok_or_else(|| PartialVMError::new(StatusCode::INTERNAL_TYPE_ERROR))?;
+                        let history = self.ds.get_supply_history(ticker, self.block);
+                        let history = history.map_err(|err| {
+                            PartialVMError::new(StatusCode::MISSING_DATA)
+                                .with_message(err.to_string())
+                        })?;
+                        history
+                            .map(|history| {
+                                to_bytes(&SupplyHistory {
+                                    records: history
+                                        .into_iter()
+                                        .map(|record| SupplyRecord {
+                                            height: record.height,
+                                            total_supply: record.total_supply,
+                                        })
+                                        .collect(),
+                                })
+                            })
+                            .transpose()
+                    } else {
+                        Err(PartialVMError::new(StatusCode::INTERNAL_TYPE_ERROR))
+                    }
+                }
                 (_, _) => self.ds.get_resource(address, tag),
             }
         } else {
@@ -109,7 +186,38 @@ impl<'a, D: DataSource> RemoteCache for StateViewSession<'a, D> {
     }
 }
 
-const XFI: &str = "XFI";
+impl<'a, D: DataSource> StateViewSession<'a, D> {
+    /// Returns registry of all registered currencies.
+    fn currency_registry(&self) -> PartialVMResult<CurrencyRegistry> {
+        let mut list = self.ds.get_currency_list().map_err(|err| {
+            PartialVMError::new(StatusCode::MISSING_DATA).with_message(err.to_string())
+        })?;
+        list.sort_by(|a, b| a.0.cmp(&b.0));
+        list.dedup_by(|a, b| a.0 == b.0);
+
+        let currencies = list
+            .into_iter()
+            .map(|(ticker, info)| RegisteredCurrency {
+                ticker: ticker.into_bytes(),
+                denom: info.denom,
+                decimals: info.decimals,
+                is_token: info.is_token,
+                owner: info.address,
+                total_supply: info.total_supply,
+            })
+            .collect();
+        Ok(CurrencyRegistry { currencies })
+    }
+}
+
+fn to_bytes<T: Serialize>(value: &T) -> PartialVMResult<Vec<u8>> {
+    lcs::to_bytes(value).map_err(|err| {
+        PartialVMError::new(StatusCode::VALUE_SERIALIZATION_ERROR).with_message(err.to_string())
+    })
+}
+
+/// Native currency ticker.
+pub const XFI: &str = "XFI";
 const COINS: &str = "Coins";
 
 /// Returns balance ticker.
@@ -1,15 +1,21 @@
 use anyhow::*;
 use std::fmt;
 use libra::{prelude::*, vm::*, gas::*};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use serde_derive::Serialize;
+
+use crate::vm::session::ticker;
 
 /// Result enum for ExecutionResult
 pub type VmResult = Result<ExecutionResult, VMStatus>;
 
 const GAS_AMOUNT_MAX_VALUE: u64 = u64::MAX / 1000;
 
+/// Default max number of script signers.
+pub const DEFAULT_SIGNERS_LIMIT: usize = 8;
+
 /// Stores gas metadata for vm execution.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct Gas {
     /// Max gas units to be used in transaction execution.
     max_gas_amount: u64,
@@ -44,16 +50,23 @@ impl Gas {
 }
 
 /// Result of transaction execution.
+///
+/// All collections have the canonical order which is the same on every node:
+/// - write set entries are ordered by access path: by address, then by path bytes;
+/// - events are ordered as they were emitted;
+/// - native balance operations are ordered by address, then by ticker.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ExecutionResult {
     /// Changes to the chain.
     pub write_set: WriteSet,
     /// Emitted events.
     pub events: Vec<ContractEvent>,
-    /// Native balance operation.
-    pub wallet_ops: HashMap<WalletId, BalanceOperation>,
+    /// Native balance operations.
+    pub wallet_ops: Vec<(WalletId, BalanceOperation)>,
     /// Number of gas units used for execution.
     pub gas_used: u64,
+    /// Transaction fee charged by vm.
+    pub fee: Option<Fee>,
     /// Status of execution.
     pub status: VMError,
 }
@@ -75,20 +88,85 @@ impl ExecutionResult {
                     PartialVMError::new(err.status_code()).finish(Location::Undefined)
                 })
             })
+            .and_then(|(write_set, events, wallet_ops)| {
+                sort_write_set(write_set)
+                    .map(|write_set| (write_set, events, sort_wallet_ops(wallet_ops)))
+            })
             .map(|(write_set, events, wallet_ops)| ExecutionResult {
                 write_set,
                 events,
                 wallet_ops,
                 gas_used,
+                fee: None,
                 status: PartialVMError::new(StatusCode::EXECUTED).finish(Location::Undefined),
             })
-            .unwrap_or_else(|status| ExecutionResult {
-                write_set: WriteSetMut::default().freeze().expect("Impossible error."),
-                events: vec![],
-                wallet_ops: Default::default(),
-                gas_used,
-                status,
-            })
+            .unwrap_or_else(|status| ExecutionResult::error(gas_used, status))
+    }
+
+    /// Creates `ExecutionResult` without chain changes with error `status`.
+    pub fn error(gas_used: u64, status: VMError) -> ExecutionResult {
+        ExecutionResult {
+            write_set: WriteSetMut::default().freeze().expect("Impossible error."),
+            events: vec![],
+            wallet_ops: Default::default(),
+            gas_used,
+            fee: None,
+            status,
+        }
+    }
+}
+
+/// Returns write set ordered by address and path.
+fn sort_write_set(write_set: WriteSet) -> VMResult<WriteSet> {
+    let mut ops = write_set.into_iter().collect::<Vec<_>>();
+    ops.sort_by(|(left, _), (right, _)| {
+        (left.address, &left.path).cmp(&(right.address, &right.path))
+    });
+    WriteSetMut::new(ops).freeze().map_err(|err| {
+        PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
+            .with_message(err.to_string())
+            .finish(Location::Undefined)
+    })
+}
+
+/// Returns balance operations ordered by address and ticker.
+fn sort_wallet_ops(
+    wallet_ops: HashMap<WalletId, BalanceOperation>,
+) -> Vec<(WalletId, BalanceOperation)> {
+    let mut ops = wallet_ops.into_iter().collect::<Vec<_>>();
+    ops.sort_by_cached_key(|(wallet_id, _)| {
+        (
+            wallet_id.address,
+            ticker(wallet_id),
+            wallet_id.tag.to_string(),
+        )
+    });
+    ops
+}
+
+/// Transaction fee in `XFI` coins.
+///
+/// Withdrawn from the native balance of the payer in addition to `wallet_ops`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Fee {
+    /// Fee payer.
+    pub payer: AccountAddress,
+    /// Fee amount: `gas_used * gas_unit_price`.
+    pub amount: u128,
+}
+
+impl Fee {
+    /// Returns fee for `gas_used` gas units.
+    pub fn new(payer: AccountAddress, gas_used: u64, gas_unit_price: u64) -> Fee {
+        Fee {
+            payer,
+            amount: gas_used as u128 * gas_unit_price as u128,
+        }
+    }
+
+    /// Returns max fee which can be charged for transaction with `gas` limits.
+    pub fn max(payer: AccountAddress, gas: &Gas) -> Fee {
+        Fee::new(payer, gas.max_gas_amount(), gas.gas_unit_price())
     }
 }
 
@@ -125,6 +203,24 @@ impl fmt::Debug for ModuleTx {
     }
 }
 
+/// Ed25519 key scheme.
+pub const KEY_SCHEME_ED25519: u8 = 0;
+/// Secp256k1 key scheme.
+pub const KEY_SCHEME_SECP256K1: u8 = 1;
+/// Multisig key scheme.
+pub const KEY_SCHEME_MULTISIG: u8 = 2;
+
+/// Signer authorization metadata verified by the node.
+///
+/// Exposed to scripts as `0x1::Signer::Authorization` resource of the signer.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignerAuth {
+    /// Key scheme of the signature: `KEY_SCHEME_*`.
+    pub key_scheme: u8,
+    /// Multisig threshold is satisfied. Always `true` for single key schemes.
+    pub threshold_satisfied: bool,
+}
+
 /// Script bytecode + passed arguments and type parameters.
 pub struct ScriptTx {
     code: Vec<u8>,
@@ -133,11 +229,14 @@ pub struct ScriptTx {
     senders: Vec<AccountAddress>,
     timestamp: u64,
     block: u64,
+    fee_payer: Option<AccountAddress>,
+    signers_auth: BTreeMap<AccountAddress, SignerAuth>,
 }
 
 /// Script transaction.
 impl ScriptTx {
     /// Constructor.
+    /// The number of `senders` must be in the range from 1 to `signers_limit`.
     pub fn new(
         code: Vec<u8>,
         args: Vec<Value>,
@@ -145,10 +244,12 @@ impl ScriptTx {
         senders: Vec<AccountAddress>,
         timestamp: u64,
         block: u64,
+        signers_limit: usize,
     ) -> Result<Self> {
         ensure!(
-            !senders.is_empty(),
-            "senders value must be in the range from 0 to ",
+            !senders.is_empty() && senders.len() <= signers_limit,
+            "Number of senders must be in the range from 1 to {}",
+            signers_limit
         );
         Ok(ScriptTx {
             code,
@@ -157,6 +258,65 @@ impl ScriptTx {
             senders,
             timestamp,
             block,
+            fee_payer: None,
+            signers_auth: Default::default(),
+        })
+    }
+
+    /// Sets authorization metadata of the `signer`.
+    pub fn with_signer_auth(
+        mut self,
+        signer: AccountAddress,
+        auth: SignerAuth,
+    ) -> Result<ScriptTx> {
+        ensure!(
+            self.senders.contains(&signer),
+            "{} is not a signer of the script",
+            signer
+        );
+        self.signers_auth.insert(signer, auth);
+        Ok(self)
+    }
+
+    /// Returns script senders.
+    pub fn senders(&self) -> &[AccountAddress] {
+        &self.senders
+    }
+
+    /// Returns authorization metadata of the signers.
+    pub fn signers_auth(&self) -> &BTreeMap<AccountAddress, SignerAuth> {
+        &self.signers_auth
+    }
+
+    /// Sets fee payer.
+    ///
+    /// The payer covers the transaction fee instead of the first sender.
+    /// The payer signature must be verified by the caller.
+    pub fn with_fee_payer(mut self, fee_payer: AccountAddress) -> ScriptTx {
+        self.fee_payer = Some(fee_payer);
+        self
+    }
+
+    /// Returns account which pays the transaction fee: the fee payer if set, otherwise the first sender.
+    pub fn fee_payer(&self) -> AccountAddress {
+        self.fee_payer.unwrap_or(self.senders[0])
+    }
+
+    /// Returns a copy of the transaction.
+    pub fn try_clone(&self) -> PartialVMResult<ScriptTx> {
+        Ok(ScriptTx {
+            code: self.code.clone(),
+            args: self
+                .args
+                .iter()
+                .map(|arg| arg.copy_value())
+                .collect::<PartialVMResult<_>>()?,
+            type_args: self.type_args.clone(),
+            senders: self.senders.clone(),
+            timestamp: self.timestamp,
+            block: self.block,
+            fee_payer: self.fee_payer,
+            signers_auth: self.signers_auth.clone(),
         })
     }
 
@@ -199,6 +359,8 @@ impl fmt::Debug for ScriptTx {
             .field("args", &self.args)
             .field("type_args", &self.type_args)
             .field("senders", &self.senders)
+            .field("fee_payer", &self.fee_payer)
+            .field("signers_auth", &self.signers_auth)
             .finish()
     }
 }
@@ -1,9 +1,12 @@
 use compiler::Compiler;
-use ds::MockDataSource;
-use lang::{stdlib::zero_std};
+use ds::{MockDataSource, ReadSet, RecordingDataSource, ReplayDataSource};
+use lang::stdlib::{build_std, zero_std};
 use libra::{prelude::*, vm::*};
+use dvm_runtime::vm::determinism::{self, Determinism};
 use dvm_runtime::vm::dvm::Dvm;
-use dvm_runtime::vm::types::{Gas, ModuleTx, ScriptTx};
+use dvm_runtime::vm::types::{
+    Fee, Gas, ModuleTx, ScriptTx, SignerAuth, DEFAULT_SIGNERS_LIMIT, KEY_SCHEME_MULTISIG,
+};
 use dvm_runtime::resources::U64Store;
 
 #[test]
@@ -77,6 +80,7 @@ fn test_execute_script() {
                 vec![CORE_CODE_ADDRESS, CORE_CODE_ADDRESS],
                 0,
                 0,
+                DEFAULT_SIGNERS_LIMIT,
             )
             .unwrap(),
         )
@@ -90,3 +94,340 @@ fn test_execute_script() {
         unreachable!();
     }
 }
+
+#[test]
+fn test_fee() {
+    let ds = MockDataSource::with_write_set(zero_std());
+    let compiler = Compiler::new(ds.clone());
+    let vm = Dvm::new(ds.clone(), None).with_fee(true);
+    let account = AccountAddress::random();
+
+    let script = compiler
+        .compile("script { fun main() {} }", Some(account))
+        .unwrap();
+    let tx = || {
+        ScriptTx::new(
+            script.clone(),
+            vec![],
+            vec![],
+            vec![account],
+            0,
+            0,
+            DEFAULT_SIGNERS_LIMIT,
+        )
+        .unwrap()
+    };
+
+    // max fee is 1_000 * 2 = 2_000
+    ds.set_balance(account, "XFI", 1_999);
+    let result = vm
+        .execute_script(Gas::new(1_000, 2).unwrap(), tx())
+        .unwrap();
+    assert_eq!(
+        result.status.major_status(),
+        StatusCode::INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE
+    );
+    assert_eq!(result.gas_used, 0);
+    assert_eq!(result.fee, None);
+
+    ds.set_balance(account, "XFI", 2_000);
+    let result = vm
+        .execute_script(Gas::new(1_000, 2).unwrap(), tx())
+        .unwrap();
+    assert_eq!(result.status.major_status(), StatusCode::EXECUTED);
+    assert_ne!(result.gas_used, 0);
+    assert_eq!(result.fee, Some(Fee::new(account, result.gas_used, 2)));
+
+    let vm = Dvm::new(ds.clone(), None);
+    ds.set_balance(account, "XFI", 0);
+    let result = vm
+        .execute_script(Gas::new(1_000, 2).unwrap(), tx())
+        .unwrap();
+    assert_eq!(result.status.major_status(), StatusCode::EXECUTED);
+    assert_eq!(result.fee, None);
+}
+
+#[test]
+fn test_fee_with_balance_changes() {
+    let ds = MockDataSource::with_write_set(build_std());
+    let compiler = Compiler::new(ds.clone());
+    let vm = Dvm::new(ds.clone(), None).with_fee(true);
+    let account = AccountAddress::random();
+    let receiver = AccountAddress::random();
+
+    let script = compiler
+        .compile(
+            "
+                script {
+                use 0x1::Account;
+                use 0x1::XFI;
+                fun main(account: &signer, receiver: &signer, amount: u128) {
+                    let xfi = Account::deposit_native<XFI::T>(account, amount);
+                    Account::withdraw_native<XFI::T>(receiver, xfi);
+                }
+                }
+            ",
+            Some(account),
+        )
+        .unwrap();
+    let tx = |amount| {
+        ScriptTx::new(
+            script.clone(),
+            vec![Value::u128(amount)],
+            vec![],
+            vec![account, receiver],
+            0,
+            0,
+            DEFAULT_SIGNERS_LIMIT,
+        )
+        .unwrap()
+    };
+
+    // the balance covers the max fee, but not the fee with the transferred coins.
+    ds.set_balance(account, "XFI", 12_000);
+    let result = vm
+        .execute_script(Gas::new(10_000, 1).unwrap(), tx(11_999))
+        .unwrap();
+    assert_eq!(
+        result.status.major_status(),
+        StatusCode::INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE
+    );
+    assert_ne!(result.gas_used, 0);
+    assert!(result.wallet_ops.is_empty());
+    assert_eq!(result.fee, Some(Fee::new(account, result.gas_used, 1)));
+
+    let result = vm
+        .execute_script(Gas::new(10_000, 1).unwrap(), tx(1_000))
+        .unwrap();
+    assert_eq!(result.status.major_status(), StatusCode::EXECUTED);
+    assert_eq!(result.wallet_ops.len(), 2);
+    assert_eq!(result.fee, Some(Fee::new(account, result.gas_used, 1)));
+}
+
+#[test]
+fn test_fee_payer() {
+    let ds = MockDataSource::with_write_set(zero_std());
+    let compiler = Compiler::new(ds.clone());
+    let vm = Dvm::new(ds.clone(), None).with_fee(true);
+    let sender = AccountAddress::random();
+    let payer = AccountAddress::random();
+
+    let script = compiler
+        .compile("script { fun main() {} }", Some(sender))
+        .unwrap();
+    let tx = || {
+        ScriptTx::new(
+            script.clone(),
+            vec![],
+            vec![],
+            vec![sender],
+            0,
+            0,
+            DEFAULT_SIGNERS_LIMIT,
+        )
+        .unwrap()
+        .with_fee_payer(payer)
+    };
+
+    // only the payer balance is checked.
+    ds.set_balance(sender, "XFI", 2_000);
+    let result = vm
+        .execute_script(Gas::new(1_000, 2).unwrap(), tx())
+        .unwrap();
+    assert_eq!(
+        result.status.major_status(),
+        StatusCode::INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE
+    );
+
+    ds.set_balance(sender, "XFI", 0);
+    ds.set_balance(payer, "XFI", 2_000);
+    let result = vm
+        .execute_script(Gas::new(1_000, 2).unwrap(), tx())
+        .unwrap();
+    assert_eq!(result.status.major_status(), StatusCode::EXECUTED);
+    assert_eq!(result.fee, Some(Fee::new(payer, result.gas_used, 2)));
+}
+
+#[test]
+fn test_determinism_check() {
+    let ds = MockDataSource::with_write_set(zero_std());
+    let compiler = Compiler::new(ds.clone());
+    let vm = Dvm::new(ds.clone(), None).with_determinism_check(1);
+    let account = AccountAddress::random();
+
+    let module = include_str!("../../test-kit/tests/resources/store.move");
+    let module = ModuleTx::new(compiler.compile(module, Some(account)).unwrap(), account);
+    let result = vm
+        .publish_module(Gas::new(1_000_000, 1).unwrap(), module)
+        .unwrap();
+    assert_eq!(result.status.major_status(), StatusCode::EXECUTED);
+    ds.merge_write_set(result.write_set);
+
+    let script = format!(
+        "
+            script {{
+            use 0x{}::Store;
+            fun main(account: &signer, val: u64) {{
+                Store::store_u64(account, val);
+            }}
+            }}
+        ",
+        account
+    );
+    let script = compiler.compile(&script, Some(account)).unwrap();
+    let tx = ScriptTx::new(
+        script,
+        vec![Value::u64(100)],
+        vec![],
+        vec![account],
+        0,
+        0,
+        DEFAULT_SIGNERS_LIMIT,
+    )
+    .unwrap();
+    let checked = vm
+        .execute_script(Gas::new(1_000_000, 1).unwrap(), tx.try_clone().unwrap())
+        .unwrap();
+    let unchecked = Dvm::new(ds.clone(), None)
+        .execute_script(Gas::new(1_000_000, 1).unwrap(), tx)
+        .unwrap();
+    assert_eq!(checked.status.major_status(), StatusCode::EXECUTED);
+    assert_eq!(checked, unchecked);
+}
+
+#[test]
+fn test_determinism_divergence() {
+    let ds = MockDataSource::with_write_set(build_std());
+    let compiler = Compiler::new(ds.clone());
+    let account = AccountAddress::random();
+
+    let module = include_str!("../../test-kit/tests/resources/store.move");
+    let module = ModuleTx::new(compiler.compile(module, Some(account)).unwrap(), account);
+    ds.merge_write_set(
+        Dvm::new(ds.clone(), None)
+            .publish_module(Gas::new(1_000_000, 1).unwrap(), module)
+            .unwrap()
+            .write_set,
+    );
+    ds.add_price("ETH", "BTC", 100);
+
+    let script = format!(
+        "
+            script {{
+            use 0x1::Coins;
+            use 0x{}::Store;
+            fun main(account: &signer) {{
+                Store::store_u64(account, (Coins::get_price<Coins::ETH, Coins::BTC>() as u64));
+            }}
+            }}
+        ",
+        account
+    );
+    let script = compiler.compile(&script, Some(account)).unwrap();
+    let tx = || {
+        ScriptTx::new(
+            script.clone(),
+            vec![],
+            vec![],
+            vec![account],
+            0,
+            0,
+            DEFAULT_SIGNERS_LIMIT,
+        )
+        .unwrap()
+    };
+    let gas = Gas::new(1_000_000, 1).unwrap();
+
+    let recording = RecordingDataSource::new(ds.clone());
+    let result = Dvm::new(recording.clone(), None)
+        .execute_script(gas, tx())
+        .unwrap();
+    assert_eq!(result.status.major_status(), StatusCode::EXECUTED);
+    let reads = recording.reads();
+    assert_eq!(
+        determinism::check("script", &reads, &result, &result),
+        Determinism::Deterministic
+    );
+
+    // the replay data source returns a different price.
+    let replay_ds = MockDataSource::with_write_set(ds.to_write_set().unwrap());
+    replay_ds.add_price("ETH", "BTC", 200);
+    let replay = Dvm::new(replay_ds, None).execute_script(gas, tx()).unwrap();
+    match determinism::check("script", &reads, &result, &replay) {
+        Determinism::Diverged(fields) => assert!(fields.contains(&"write_set")),
+        determinism => panic!("Unexpected determinism check result: {:?}", determinism),
+    }
+
+    // failed reads are not recorded.
+    let recording = RecordingDataSource::new(ReplayDataSource::new(ds.clone(), ReadSet::default()));
+    let result = Dvm::new(recording.clone(), None)
+        .execute_script(gas, tx())
+        .unwrap();
+    let reads = recording.reads();
+    assert!(reads.is_incomplete());
+    assert_eq!(
+        determinism::check("script", &reads, &result, &result),
+        Determinism::Incomplete
+    );
+}
+
+#[test]
+fn test_signers_limit() {
+    let ds = MockDataSource::with_write_set(zero_std());
+    let compiler = Compiler::new(ds.clone());
+    let code = compiler.compile("script { fun main() {} }", None).unwrap();
+    let senders = |count| (0..count).map(|_| AccountAddress::random()).collect();
+    let tx =
+        |count, limit| ScriptTx::new(code.clone(), vec![], vec![], senders(count), 0, 0, limit);
+    let status = |vm: &Dvm<MockDataSource>, count| {
+        vm.execute_script(
+            Gas::new(1_000_000, 1).unwrap(),
+            tx(count, DEFAULT_SIGNERS_LIMIT).unwrap(),
+        )
+        .unwrap()
+        .status
+        .major_status()
+    };
+    assert!(tx(0, DEFAULT_SIGNERS_LIMIT).is_err());
+    assert!(tx(DEFAULT_SIGNERS_LIMIT, DEFAULT_SIGNERS_LIMIT).is_ok());
+    assert!(tx(DEFAULT_SIGNERS_LIMIT + 1, DEFAULT_SIGNERS_LIMIT).is_err());
+    assert!(tx(3, 2).is_err());
+
+    let vm = Dvm::new(ds.clone(), None);
+    assert_ne!(
+        status(&vm, DEFAULT_SIGNERS_LIMIT),
+        StatusCode::DATA_FORMAT_ERROR
+    );
+
+    // transactions built with a greater limit are rejected by the vm.
+    let vm = Dvm::new(ds, None).with_signers_limit(2);
+    assert_eq!(vm.signers_limit(), 2);
+    assert_ne!(status(&vm, 2), StatusCode::DATA_FORMAT_ERROR);
+    assert_eq!(status(&vm, 3), StatusCode::DATA_FORMAT_ERROR);
+}
+
+#[test]
+fn test_signer_auth() {
+    let signer = AccountAddress::random();
+    let auth = SignerAuth {
+        key_scheme: KEY_SCHEME_MULTISIG,
+        threshold_satisfied: true,
+    };
+    let tx = ScriptTx::new(
+        vec![],
+        vec![],
+        vec![],
+        vec![signer],
+        0,
+        0,
+        DEFAULT_SIGNERS_LIMIT,
+    )
+    .unwrap();
+    assert!(tx
+        .try_clone()
+        .unwrap()
+        .with_signer_auth(AccountAddress::random(), auth)
+        .is_err());
+    let tx = tx.with_signer_auth(signer, auth).unwrap();
+    assert_eq!(tx.signers_auth().get(&signer), Some(&auth));
+}
@@ -1,12 +1,14 @@
 use anyhow::Result;
 use libra::prelude::*;
-use crate::{tonic, api};
+use crate::tonic;
 use tonic::{Request, Response, Status};
 
-use api::grpc::dvm_compiler_server::DvmCompiler;
-use api::grpc::{CompilationResult, SourceFiles, CompiledUnit};
+use dvm_net::compiler_api::dvm_compiler_server::DvmCompiler;
+use dvm_net::compiler_api::{self as api, CompilationResult, SourceFiles, CompiledUnit};
+use std::collections::BTreeMap;
 use std::convert::TryFrom;
 use compiler::Compiler;
+use compiler::diagnostics::{CompilationError, Diagnostic, Severity};
 use info::metrics::meter::ScopeMeter;
 use info::metrics::execution::ExecutionResult;
 
@@ -29,20 +31,91 @@ where
     }
 }
 
+/// Request metadata key with the named addresses referenced in the sources as `@Name`.
+/// The value is a comma separated list of `Name=address` pairs,
+/// e.g. `Treasury=wallet1...,Oracle=0x1`.
+pub const NAMED_ADDRESSES: &str = "named-addresses";
+
 /// Convert address from bytes.
 fn convert_address(addr: &[u8]) -> Result<AccountAddress, Status> {
     AccountAddress::try_from(addr).map_err(|err| Status::invalid_argument(err.to_string()))
 }
 
+/// Parse named addresses from the request metadata.
+fn named_addresses<T>(request: &Request<T>) -> Result<BTreeMap<String, String>, Status> {
+    let value = match request.metadata().get(NAMED_ADDRESSES) {
+        Some(value) => value
+            .to_str()
+            .map_err(|err| Status::invalid_argument(err.to_string()))?,
+        None => return Ok(BTreeMap::new()),
+    };
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let mut parts = pair.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some(name), Some(address)) => {
+                    Ok((name.trim().to_owned(), address.trim().to_owned()))
+                }
+                _ => Err(Status::invalid_argument(format!(
+                    "Invalid named address '{}'. Expected Name=address.",
+                    pair
+                ))),
+            }
+        })
+        .collect()
+}
+
+/// Convert the compiler diagnostic to the api one.
+fn convert_diagnostic(diagnostic: Diagnostic) -> api::Diagnostic {
+    let convert_span = |span: compiler::diagnostics::Span| api::Span {
+        start: Some(api::Position {
+            byte: span.start.byte as u64,
+            line: span.start.line as u64,
+            column: span.start.column as u64,
+        }),
+        end: Some(api::Position {
+            byte: span.end.byte as u64,
+            line: span.end.line as u64,
+            column: span.end.column as u64,
+        }),
+    };
+    let severity = match diagnostic.severity {
+        Severity::Error => api::diagnostic::Severity::Error,
+        Severity::Warning => api::diagnostic::Severity::Warning,
+    };
+
+    api::Diagnostic {
+        severity: severity as i32,
+        message: diagnostic.message,
+        file: diagnostic.file.unwrap_or_default(),
+        span: diagnostic.span.map(convert_span),
+        labels: diagnostic
+            .labels
+            .into_iter()
+            .map(|label| api::Label {
+                file: label.file,
+                span: Some(convert_span(label.span)),
+                message: label.message,
+            })
+            .collect(),
+    }
+}
+
 impl<C> CompilerService<C>
 where
     C: RemoteCache + Clone + Send + Sync + 'static,
 {
     /// Compiler source codes.
+    /// Named addresses are taken from the `named-addresses` request metadata.
+    /// Returns structured diagnostics with the rendered errors if the compilation failed.
     pub async fn compile(
         &self,
         request: Request<SourceFiles>,
-    ) -> Result<Result<Vec<CompiledUnit>, String>, Status> {
+    ) -> Result<Result<Vec<CompiledUnit>, CompilationError>, Status> {
+        let addresses = named_addresses(&request)?;
         let request = request.into_inner();
         let address = convert_address(&request.address)?;
         let source_map = request
@@ -53,8 +126,8 @@ where
 
         Ok(self
             .compiler
-            .compile_source_map(source_map, Some(address))
-            .map_err(|err| err.to_string())
+            .compile_source_map_with_addresses(source_map, Some(address), addresses)
+            .map_err(CompilationError::from)
             .map(|map| {
                 map.into_iter()
                     .map(|(name, bytecode)| CompiledUnit { name, bytecode })
@@ -69,6 +142,7 @@ where
     C: RemoteCache + Clone + Send + Sync + 'static,
 {
     /// Compiler source codes.
+    /// Diagnostics of the failed compilation are returned in the `diagnostics` field.
     async fn compile(
         &self,
         request: Request<SourceFiles>,
@@ -81,13 +155,19 @@ where
                 Ok(Response::new(CompilationResult {
                     units,
                     errors: vec![],
+                    diagnostics: vec![],
                 }))
             }
-            Ok(Err(errors)) => {
+            Ok(Err(err)) => {
                 meter.set_result(ExecutionResult::new(false, 400, 0));
                 Ok(Response::new(CompilationResult {
                     units: vec![],
-                    errors: vec![errors],
+                    errors: vec![err.rendered],
+                    diagnostics: err
+                        .diagnostics
+                        .into_iter()
+                        .map(convert_diagnostic)
+                        .collect(),
                 }))
             }
             Err(status) => {
@@ -0,0 +1,227 @@
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Error;
+use tonic::{Request, Response, Status};
+
+use data_source::file::BlockEntry;
+use data_source::{Balance, FileDataSource};
+use dvm_net::api::grpc::vm_module_publisher_server::VmModulePublisher;
+use dvm_net::api::grpc::vm_script_executor_server::VmScriptExecutor;
+use dvm_net::api::grpc::*;
+use dvm_net::api::tonic::Code;
+use info::metrics::execution::ExecutionResult as ActionResult;
+use info::metrics::meter::ScopeMeter;
+use libra::{prelude::*, vm::*};
+use runtime::vm::session::{ticker, XFI};
+use runtime::vm::{dvm::*, types::*};
+
+use crate::tonic;
+use crate::vm::{
+    make_vm_error, store_metric, vm_result_to_execute_response, ExecuteScript, PublishModule,
+};
+
+/// Virtual machine service for the local devnet.
+///
+/// Executes transactions against the `FileDataSource` and commits their write sets,
+/// native balance changes and fees to it.
+/// Scripts are executed in the block passed in the request. Requests with zero block height
+/// are executed in a new block: the height is incremented and the timestamp is taken from the local clock.
+#[derive(Clone)]
+pub struct DevnetService {
+    vm: Arc<Dvm<FileDataSource>>,
+    ds: FileDataSource,
+    /// Transactions are applied one by one.
+    lock: Arc<Mutex<()>>,
+}
+
+unsafe impl Send for DevnetService {}
+
+unsafe impl Sync for DevnetService {}
+
+impl DevnetService {
+    /// Creates a new devnet service with the given virtual machine.
+    /// Results are committed to the data source of the `vm`.
+    pub fn new(vm: Dvm<FileDataSource>) -> DevnetService {
+        DevnetService {
+            ds: vm.data_source().clone(),
+            vm: Arc::new(vm),
+            lock: Default::default(),
+        }
+    }
+
+    /// Returns devnet data source.
+    pub fn data_source(&self) -> &FileDataSource {
+        &self.ds
+    }
+
+    /// Returns metadata of the block to execute the script in.
+    /// Zero `height` means the next block, otherwise the requested block is used:
+    /// its height and timestamp must not be less than the last ones.
+    fn block(&self, height: u64, timestamp: u64) -> Result<BlockEntry, Error> {
+        let last = self.ds.block();
+        if height == 0 {
+            return Ok(last.next());
+        }
+        ensure!(
+            height >= last.height && timestamp >= last.timestamp,
+            "Block {} with timestamp {} is behind the last block {} with timestamp {}.",
+            height,
+            timestamp,
+            last.height,
+            last.timestamp
+        );
+        Ok(BlockEntry { height, timestamp })
+    }
+
+    /// Commits successful execution result to the data source.
+    /// All balance changes are validated before the state is changed,
+    /// so the result is either committed completely or not at all.
+    fn commit(&self, result: &VmResult) -> Result<(), Error> {
+        let result = match result {
+            Ok(result) => result,
+            Err(_) => return Ok(()),
+        };
+        let executed = result.status.major_status() == StatusCode::EXECUTED;
+
+        let mut balances = BTreeMap::new();
+        if let Some(fee) = &result.fee {
+            self.change_balance(
+                &mut balances,
+                fee.payer,
+                XFI,
+                &BalanceOperation::Deposit(fee.amount),
+            )?;
+        }
+        if executed {
+            for (wallet_id, op) in &result.wallet_ops {
+                if let Some(ticker) = ticker(wallet_id) {
+                    self.change_balance(&mut balances, wallet_id.address, &ticker, op)?;
+                }
+            }
+            self.ds.merge_write_set(result.write_set.clone());
+        }
+        for ((address, ticker), balance) in balances {
+            self.ds.set_balance(address, &ticker, balance);
+        }
+        Ok(())
+    }
+
+    /// Applies balance operation to the pending native balances.
+    fn change_balance(
+        &self,
+        balances: &mut BTreeMap<(AccountAddress, String), u128>,
+        address: AccountAddress,
+        ticker: &str,
+        op: &BalanceOperation,
+    ) -> Result<(), Error> {
+        let key = (address, ticker.to_owned());
+        let balance = match balances.get(&key) {
+            Some(balance) => *balance,
+            None => self
+                .ds
+                .get_balance(address, ticker.to_owned())?
+                .unwrap_or_default(),
+        };
+        // Deposit moves coins from the native balance to the Move one.
+        let balance = match op {
+            BalanceOperation::Deposit(amount) => balance
+                .checked_sub(*amount)
+                .ok_or_else(|| anyhow!("Insufficient {} balance.", ticker))?,
+            BalanceOperation::Withdraw(amount) => balance
+                .checked_add(*amount)
+                .ok_or_else(|| anyhow!("{} balance overflow.", ticker))?,
+        };
+        balances.insert(key, balance);
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl VmScriptExecutor for DevnetService {
+    async fn execute_script(
+        &self,
+        request: Request<VmExecuteScript>,
+    ) -> Result<Response<VmExecuteResponse>, Status> {
+        let mut meter = ScopeMeter::new("execute_script");
+
+        let contract = match ExecuteScript::from_request(request, self.vm.signers_limit()) {
+            Ok(contract) => contract,
+            Err(err) => {
+                meter.set_result(ActionResult::new(false, Code::InvalidArgument as u64, 0));
+                return Ok(Response::new(make_vm_error(err)));
+            }
+        };
+
+        let _guard = self.lock.lock().unwrap();
+        let fee_payer = contract.script.fee_payer();
+        let signers_auth = contract.script.signers_auth().clone();
+        let (code, args, type_args, senders, timestamp, height) = contract.script.into_inner();
+        let block = self
+            .block(height, timestamp)
+            .map_err(|err| Status::invalid_argument(err.to_string()))?;
+        let script = ScriptTx::new(
+            code,
+            args,
+            type_args,
+            senders,
+            block.timestamp,
+            block.height,
+            self.vm.signers_limit(),
+        )
+        .and_then(|script| {
+            signers_auth
+                .into_iter()
+                .try_fold(script, |script, (signer, auth)| {
+                    script.with_signer_auth(signer, auth)
+                })
+        })
+        .map_err(|err| Status::invalid_argument(err.to_string()))?
+        .with_fee_payer(fee_payer);
+
+        let response = self.vm.execute_script(contract.gas, script);
+        self.commit(&response)
+            .map_err(|err| Status::internal(err.to_string()))?;
+        self.ds.set_block(block);
+        self.ds
+            .save()
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        Ok(Response::new(store_metric(
+            vm_result_to_execute_response(response),
+            meter,
+        )))
+    }
+}
+
+#[tonic::async_trait]
+impl VmModulePublisher for DevnetService {
+    async fn publish_module(
+        &self,
+        request: Request<VmPublishModule>,
+    ) -> Result<Response<VmExecuteResponse>, Status> {
+        let mut meter = ScopeMeter::new("publish_module");
+
+        let contract = match PublishModule::try_from(request.into_inner()) {
+            Ok(contract) => contract,
+            Err(err) => {
+                meter.set_result(ActionResult::new(false, Code::InvalidArgument as u64, 0));
+                return Ok(Response::new(make_vm_error(err)));
+            }
+        };
+
+        let _guard = self.lock.lock().unwrap();
+        let response = self.vm.publish_module(contract.gas, contract.module);
+        self.commit(&response)
+            .map_err(|err| Status::internal(err.to_string()))?;
+        self.ds
+            .save()
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        Ok(Response::new(store_metric(
+            vm_result_to_execute_response(response),
+            meter,
+        )))
+    }
+}
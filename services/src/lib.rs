@@ -10,6 +10,9 @@ use dvm_net::{api, tonic};
 /// gRPC service for compiler.
 pub mod compiler;
 
+/// gRPC vm service for the local devnet.
+pub mod devnet;
+
 /// gRPC service for script signature parameters.
 pub mod metadata;
 
@@ -1,4 +1,3 @@
-use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::sync::Arc;
 
@@ -17,10 +16,20 @@ use info::metrics::execution::ExecutionResult as ActionResult;
 use info::metrics::meter::ScopeMeter;
 use libra::{prelude::*, vm::*};
 use runtime::vm::{dvm::*, types::*};
-use runtime::vm::session::ticker;
+use runtime::vm::session::{ticker, XFI};
 
 use crate::{api, tonic};
 
+/// Request metadata key with the hex address of the account which pays the transaction fee,
+/// e.g. `0x1`. The payer signature must be verified by the node.
+/// The first sender pays the fee if the key is not set.
+pub const FEE_PAYER: &str = "fee-payer";
+
+/// Request metadata key with the authorization metadata of the script signers verified by the node.
+/// The value is a comma separated list of `address:key_scheme:threshold_satisfied` entries,
+/// e.g. `0x1:2:true,0x2:0:true`. Signers without the entry have no authorization metadata.
+pub const SIGNERS_AUTH: &str = "signers-auth";
+
 /// Virtual machine service.
 #[derive(Clone)]
 pub struct VmService<D: DataSource> {
@@ -56,7 +65,7 @@ where
     ) -> Result<Response<VmExecuteResponse>, Status> {
         let mut meter = ScopeMeter::new("execute_script");
 
-        let contract = match ExecuteScript::try_from(request.into_inner()) {
+        let contract = match ExecuteScript::from_request(request, self.vm.signers_limit()) {
             Ok(contract) => contract,
             Err(err) => {
                 meter.set_result(ActionResult::new(false, Code::InvalidArgument as u64, 0));
@@ -74,14 +83,16 @@ where
 }
 
 /// Converts execution result to api response.
-fn vm_result_to_execute_response(res: Result<ExecutionResult, VMStatus>) -> VmExecuteResponse {
+pub(crate) fn vm_result_to_execute_response(
+    res: Result<ExecutionResult, VMStatus>,
+) -> VmExecuteResponse {
     match res {
         Ok(res) => VmExecuteResponse {
             gas_used: res.gas_used,
             events: convert_events(res.events),
             write_set: convert_write_set(res.write_set),
             status: Some(convert_vm_error_status(res.status)),
-            balance_change_set: convert_wallet_ops(res.wallet_ops),
+            balance_change_set: convert_balance_changes(res.wallet_ops, res.fee),
         },
         Err(err) => {
             // This is't execution error!
@@ -97,7 +108,7 @@ fn vm_result_to_execute_response(res: Result<ExecutionResult, VMStatus>) -> VmEx
 }
 
 /// Makes `VmExecuteResponse` with data format error.
-fn make_vm_error(err: Error) -> VmExecuteResponse {
+pub(crate) fn make_vm_error(err: Error) -> VmExecuteResponse {
     VmExecuteResponse {
         write_set: vec![],
         events: vec![],
@@ -120,22 +131,56 @@ fn convert_vm_error_status(status: VMError) -> VmStatus {
     convert_status(status.into_vm_status(), msg)
 }
 
-fn convert_wallet_ops(wallet_ops: HashMap<WalletId, BalanceOperation>) -> Vec<VmBalanceChange> {
-    wallet_ops
+/// Converts native balance operations and transaction fee into gRPC balance changes.
+/// Balance changes are ordered by address, then by ticker.
+/// Operations of distinct wallets with the same ticker are kept as separate changes.
+/// The fee is a `Deposit` (native balance decrease) of `XFI` coins,
+/// it is merged into the first `XFI` balance change of the payer.
+/// Changes with zero amount are dropped.
+fn convert_balance_changes(
+    wallet_ops: Vec<(WalletId, BalanceOperation)>,
+    fee: Option<Fee>,
+) -> Vec<VmBalanceChange> {
+    let mut ops = wallet_ops
         .into_iter()
         .filter_map(|(wallet_id, op)| {
-            ticker(&wallet_id).map(|ticker| (wallet_id.address, ticker, op))
+            ticker(&wallet_id).map(|ticker| ((wallet_id.address, ticker), op))
         })
-        .map(|(address, ticker, op)| {
+        .collect::<Vec<_>>();
+
+    if let Some(fee) = fee {
+        let key = (fee.payer, XFI.to_owned());
+        match ops.iter_mut().find(|(op_key, _)| op_key == &key) {
+            Some((_, op)) => {
+                *op = match *op {
+                    BalanceOperation::Deposit(amount) => {
+                        BalanceOperation::Deposit(amount.saturating_add(fee.amount))
+                    }
+                    BalanceOperation::Withdraw(amount) if amount >= fee.amount => {
+                        BalanceOperation::Withdraw(amount - fee.amount)
+                    }
+                    BalanceOperation::Withdraw(amount) => {
+                        BalanceOperation::Deposit(fee.amount - amount)
+                    }
+                }
+            }
+            None => ops.push((key, BalanceOperation::Deposit(fee.amount))),
+        }
+    }
+    ops.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    ops.into_iter()
+        .filter_map(|((address, ticker), op)| {
             let op = match op {
+                BalanceOperation::Deposit(0) | BalanceOperation::Withdraw(0) => return None,
                 BalanceOperation::Deposit(amount) => Op::Deposit(amount.into()),
                 BalanceOperation::Withdraw(amount) => Op::Withdraw(amount.into()),
             };
-            VmBalanceChange {
+            Some(VmBalanceChange {
                 address: address.to_vec(),
                 ticker,
                 op: Some(op),
-            }
+            })
         })
         .collect()
 }
@@ -190,7 +235,7 @@ fn convert_abort_location(location: AbortLoc) -> Option<AbortLocation> {
     }
 }
 
-/// Converts `WriteSet`.into a vec of `VmValue` for gRPC
+/// Converts `WriteSet`.into a vec of `VmValue` for gRPC keeping the order of entries.
 fn convert_write_set(ws: WriteSet) -> Vec<VmValue> {
     ws.into_iter()
         .map(|(access_path, write_op)| {
@@ -271,7 +316,10 @@ fn convert_event_tag(type_tag: &TypeTag) -> LcsTag {
 }
 
 /// Store execution result to 'scope_meter'.
-fn store_metric(result: VmExecuteResponse, mut scope_meter: ScopeMeter) -> VmExecuteResponse {
+pub(crate) fn store_metric(
+    result: VmExecuteResponse,
+    mut scope_meter: ScopeMeter,
+) -> VmExecuteResponse {
     let status = match &result.status {
         Some(status) => match &status.error {
             Some(vm_status::Error::Abort(_)) => StatusCode::ABORTED as u64,
@@ -297,15 +345,77 @@ fn store_metric(result: VmExecuteResponse, mut scope_meter: ScopeMeter) -> VmExe
 
 /// Data for script execution.
 #[derive(Debug)]
-struct ExecuteScript {
-    gas: Gas,
-    script: ScriptTx,
+pub(crate) struct ExecuteScript {
+    pub(crate) gas: Gas,
+    pub(crate) script: ScriptTx,
 }
 
-impl TryFrom<VmExecuteScript> for ExecuteScript {
-    type Error = Error;
+impl ExecuteScript {
+    /// Creates script execution data from the request and its metadata.
+    /// The fee payer and the signers authorization are taken from the `fee-payer`
+    /// and `signers-auth` request metadata.
+    /// Scripts with more than `signers_limit` senders are rejected.
+    pub(crate) fn from_request(
+        request: Request<VmExecuteScript>,
+        signers_limit: usize,
+    ) -> Result<ExecuteScript, Error> {
+        let fee_payer = fee_payer(&request)?;
+        let signers_auth = signers_auth(&request)?;
+        let mut contract = ExecuteScript::from_script(request.into_inner(), signers_limit)?;
+        if let Some(fee_payer) = fee_payer {
+            contract.script = contract.script.with_fee_payer(fee_payer);
+        }
+        for (signer, auth) in signers_auth {
+            contract.script = contract.script.with_signer_auth(signer, auth)?;
+        }
+        Ok(contract)
+    }
+}
+
+/// Parses signers authorization metadata from the request metadata.
+fn signers_auth<T>(request: &Request<T>) -> Result<Vec<(AccountAddress, SignerAuth)>, Error> {
+    let value = match request.metadata().get(SIGNERS_AUTH) {
+        Some(value) => value.to_str()?,
+        None => return Ok(vec![]),
+    };
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let parts = entry.split(':').collect::<Vec<_>>();
+            ensure!(
+                parts.len() == 3,
+                "Invalid signer authorization '{}'. Expected address:key_scheme:threshold_satisfied.",
+                entry
+            );
+            let signer = AccountAddress::from_hex_literal(parts[0])
+                .map_err(|err| anyhow!("Invalid signer '{}': {}", parts[0], err))?;
+            let auth = SignerAuth {
+                key_scheme: parts[1].parse()?,
+                threshold_satisfied: parts[2].parse()?,
+            };
+            Ok((signer, auth))
+        })
+        .collect()
+}
+
+/// Parses fee payer from the request metadata.
+fn fee_payer<T>(request: &Request<T>) -> Result<Option<AccountAddress>, Error> {
+    request
+        .metadata()
+        .get(FEE_PAYER)
+        .map(|value| {
+            let value = value.to_str()?.trim();
+            AccountAddress::from_hex_literal(value)
+                .map_err(|err| anyhow!("Invalid fee payer '{}': {}", value, err))
+        })
+        .transpose()
+}
 
-    fn try_from(req: VmExecuteScript) -> Result<Self, Error> {
+impl ExecuteScript {
+    /// Creates script execution data from the request.
+    fn from_script(req: VmExecuteScript, signers_limit: usize) -> Result<Self, Error> {
         let args = req
             .args
             .into_iter()
@@ -399,7 +509,15 @@ impl TryFrom<VmExecuteScript> for ExecuteScript {
 
         Ok(ExecuteScript {
             gas: Gas::new(req.max_gas_amount, req.gas_unit_price)?,
-            script: ScriptTx::new(req.code, args, type_args, senders, req.timestamp, req.block)?,
+            script: ScriptTx::new(
+                req.code,
+                args,
+                type_args,
+                senders,
+                req.timestamp,
+                req.block,
+                signers_limit,
+            )?,
         })
     }
 }
@@ -433,9 +551,9 @@ where
 
 /// Data for module publication.
 #[derive(Debug)]
-struct PublishModule {
-    gas: Gas,
-    module: ModuleTx,
+pub(crate) struct PublishModule {
+    pub(crate) gas: Gas,
+    pub(crate) module: ModuleTx,
 }
 
 impl TryFrom<VmPublishModule> for PublishModule {
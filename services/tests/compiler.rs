@@ -3,9 +3,11 @@ use dvm_net::tonic;
 use tonic::Request;
 use lang::{stdlib::build_std};
 use compiler::Compiler;
+use compiler::diagnostics::{CompilationError, Severity};
 use data_source::MockDataSource;
-use dvm_services::compiler::CompilerService;
-use dvm_net::api::grpc::{SourceFiles, CompilationUnit, CompiledUnit as Unit};
+use dvm_services::compiler::{CompilerService, NAMED_ADDRESSES};
+use dvm_net::compiler_api::dvm_compiler_server::DvmCompiler;
+use dvm_net::compiler_api::{diagnostic, SourceFiles, CompilationUnit, CompiledUnit as Unit};
 
 fn new_source_file(source: &str, address: &AccountAddress) -> SourceFiles {
     SourceFiles {
@@ -23,7 +25,7 @@ fn new_source_file_request(source_text: &str) -> Request<SourceFiles> {
     Request::new(source_file)
 }
 
-async fn compile_source_file(source_text: &str) -> Result<Vec<Unit>, String> {
+async fn compile_source_file(source_text: &str) -> Result<Vec<Unit>, CompilationError> {
     let source_file_request = new_source_file_request(source_text);
 
     let compiler = Compiler::new(MockDataSource::with_write_set(build_std()));
@@ -117,9 +119,11 @@ async fn test_required_libracoin_dependency_is_not_available() {
         .unwrap()
         .unwrap_err();
     assert_eq!(
-        error,
+        error.rendered,
         r#"Module '0x0000000000000000000000000000000000000001::Coin' not found"#
-    )
+    );
+    assert_eq!(error.diagnostics.len(), 1);
+    assert!(error.diagnostics[0].span.is_none());
 }
 
 #[tokio::test]
@@ -171,5 +175,89 @@ async fn test_compilation_error_on_expected_an_expression_term() {
             }
         "#;
     let error = compile_source_file(source_text).await.unwrap_err();
-    assert!(error.contains("Unused local 'a'"));
+    assert!(error.rendered.contains("Unused local 'a'"));
+}
+
+#[tokio::test]
+async fn test_compilation_error_diagnostics() {
+    let source_text = "script {
+    fun main() {
+        let _ = wallet1me0cdn52672y7feddy7tgcj6j4dkzq2su745vh; let a: u128;
+        return;
+    }
+}";
+    let error = compile_source_file(source_text).await.unwrap_err();
+    let diagnostic = error
+        .diagnostics
+        .iter()
+        .find(|diagnostic| diagnostic.message.contains("Unused local 'a'"))
+        .unwrap();
+    assert_eq!(diagnostic.severity, Severity::Error);
+    assert_eq!(diagnostic.file.as_deref(), Some("src"));
+
+    let span = diagnostic.span.unwrap();
+    assert_eq!(span.start.line, 3);
+    assert_eq!(span.start.column, 68);
+    assert_eq!(&source_text[span.start.byte..span.end.byte], "a");
+}
+
+#[tokio::test]
+async fn test_response_diagnostics() {
+    let compiler = Compiler::new(MockDataSource::with_write_set(build_std()));
+    let compiler_service = CompilerService::new(compiler);
+
+    let source_text = "script { fun main() { let a: u128; return; } }";
+    let request = new_source_file_request(source_text);
+    let response = DvmCompiler::compile(&compiler_service, request)
+        .await
+        .unwrap();
+    let result = response.into_inner();
+    let diagnostic = result
+        .diagnostics
+        .iter()
+        .find(|diagnostic| diagnostic.message.contains("Unused local 'a'"))
+        .unwrap();
+    assert_eq!(diagnostic.severity, diagnostic::Severity::Error as i32);
+    assert_eq!(diagnostic.file, "src");
+    let span = diagnostic.span.as_ref().unwrap();
+    let (start, end) = (span.start.as_ref().unwrap(), span.end.as_ref().unwrap());
+    assert_eq!(start.line, 1);
+    assert_eq!(&source_text[start.byte as usize..end.byte as usize], "a");
+    assert_eq!(result.errors.len(), 1);
+
+    let request = new_source_file_request("script { fun main() {} }");
+    let response = DvmCompiler::compile(&compiler_service, request)
+        .await
+        .unwrap();
+    assert!(response.get_ref().diagnostics.is_empty());
+    assert_eq!(response.get_ref().units.len(), 1);
+}
+
+#[tokio::test]
+async fn test_named_addresses() {
+    let source_text = r"
+            script {
+            use @Std::Time;
+            fun main() {
+                Time::now();
+            }
+            }
+        ";
+    let compiler = Compiler::new(MockDataSource::with_write_set(build_std()));
+    let compiler_service = CompilerService::new(compiler);
+
+    let mut request = new_source_file_request(source_text);
+    request
+        .metadata_mut()
+        .insert(NAMED_ADDRESSES, "Std=0x1, Treasury=0x2".parse().unwrap());
+    compiler_service.compile(request).await.unwrap().unwrap();
+
+    let error = compile_source_file(source_text).await.unwrap_err();
+    assert!(error.diagnostics[0].message.contains("Unknown name '@Std'"));
+
+    let mut request = new_source_file_request(source_text);
+    request
+        .metadata_mut()
+        .insert(NAMED_ADDRESSES, "Std".parse().unwrap());
+    assert!(compiler_service.compile(request).await.is_err());
 }
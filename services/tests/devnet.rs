@@ -0,0 +1,202 @@
+use std::env;
+
+use compiler::Compiler;
+use data_source::{Balance, FileDataSource};
+use dvm_net::api::grpc::vm_module_publisher_server::VmModulePublisher;
+use dvm_net::api::grpc::vm_script_executor_server::VmScriptExecutor;
+use dvm_net::api::grpc::{VmExecuteScript, VmPublishModule};
+use dvm_net::tonic;
+use dvm_services::devnet::DevnetService;
+use lang::stdlib::build_std;
+use libra::prelude::*;
+use runtime::vm::dvm::Dvm;
+use tonic::Request;
+
+fn devnet() -> DevnetService {
+    devnet_with(|vm| vm)
+}
+
+fn devnet_with<F>(config: F) -> DevnetService
+where
+    F: FnOnce(Dvm<FileDataSource>) -> Dvm<FileDataSource>,
+{
+    let path = env::temp_dir().join(format!(
+        "devnet_{}.json",
+        hex::encode(AccountAddress::random())
+    ));
+    let ds = FileDataSource::open(path).unwrap();
+    ds.merge_write_set(build_std());
+    DevnetService::new(config(Dvm::new(ds, None)))
+}
+
+fn script(code: Vec<u8>, sender: AccountAddress) -> Request<VmExecuteScript> {
+    Request::new(VmExecuteScript {
+        senders: vec![sender.to_vec()],
+        max_gas_amount: 1_000_000,
+        gas_unit_price: 1,
+        block: 0,
+        timestamp: 0,
+        code,
+        type_params: vec![],
+        args: vec![],
+    })
+}
+
+#[tokio::test]
+async fn test_devnet_blocks() {
+    let devnet = devnet();
+    let ds = devnet.data_source().clone();
+    let compiler = Compiler::new(ds.clone());
+    let sender = AccountAddress::random();
+
+    let code = compiler
+        .compile(
+            r"
+            script {
+                use 0x1::Block;
+
+                fun main() {
+                    assert(Block::get_current_block_height() == 1, 1);
+                }
+            }
+        ",
+            Some(sender),
+        )
+        .unwrap();
+    let res = devnet
+        .execute_script(script(code, sender))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(res.status.unwrap().error.is_none());
+    assert_eq!(ds.block().height, 1);
+    let timestamp = ds.block().timestamp;
+
+    let code = compiler
+        .compile(
+            r"
+            script {
+                use 0x1::Block;
+
+                fun main() {
+                    assert(Block::get_current_block_height() == 2, 1);
+                }
+            }
+        ",
+            Some(sender),
+        )
+        .unwrap();
+    let res = devnet
+        .execute_script(script(code, sender))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(res.status.unwrap().error.is_none());
+    assert_eq!(ds.block().height, 2);
+    assert!(ds.block().timestamp > timestamp);
+
+    let loaded = FileDataSource::open(ds.path()).unwrap();
+    assert_eq!(loaded.snapshot(), ds.snapshot());
+    std::fs::remove_file(ds.path()).unwrap();
+}
+
+#[tokio::test]
+async fn test_devnet_request_block() {
+    let devnet = devnet();
+    let ds = devnet.data_source().clone();
+    let sender = AccountAddress::random();
+    let code = Compiler::new(ds.clone())
+        .compile(
+            r"
+            script {
+                use 0x1::Block;
+                use 0x1::Time;
+
+                fun main() {
+                    assert(Block::get_current_block_height() == 10, 1);
+                    assert(Time::now() == 1000, 2);
+                }
+            }
+        ",
+            Some(sender),
+        )
+        .unwrap();
+
+    let mut request = script(code.clone(), sender);
+    request.get_mut().block = 10;
+    request.get_mut().timestamp = 1000;
+    let res = devnet.execute_script(request).await.unwrap().into_inner();
+    assert!(res.status.unwrap().error.is_none());
+    assert_eq!(ds.block().height, 10);
+    assert_eq!(ds.block().timestamp, 1000);
+
+    // blocks behind the last one are rejected.
+    let mut request = script(code, sender);
+    request.get_mut().block = 9;
+    request.get_mut().timestamp = 1000;
+    assert!(devnet.execute_script(request).await.is_err());
+    assert_eq!(ds.block().height, 10);
+    std::fs::remove_file(ds.path()).unwrap();
+}
+
+#[tokio::test]
+async fn test_devnet_fee() {
+    let devnet = devnet_with(|vm| vm.with_fee(true));
+    let ds = devnet.data_source().clone();
+    let sender = AccountAddress::random();
+    ds.set_balance(sender, "XFI", 2_000_000);
+    let code = Compiler::new(ds.clone())
+        .compile("script { fun main() {} }", Some(sender))
+        .unwrap();
+
+    let res = devnet
+        .execute_script(script(code, sender))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(res.status.unwrap().error.is_none());
+    assert_ne!(res.gas_used, 0);
+    assert_eq!(
+        ds.get_balance(sender, "XFI".to_owned()).unwrap(),
+        Some(2_000_000 - res.gas_used as u128)
+    );
+    std::fs::remove_file(ds.path()).unwrap();
+}
+
+#[tokio::test]
+async fn test_devnet_publish_module() {
+    let devnet = devnet();
+    let ds = devnet.data_source().clone();
+    let sender = AccountAddress::random();
+
+    let module = Compiler::new(ds.clone())
+        .compile(
+            r"
+            module Foo {
+                public fun foo(): u64 {
+                    1
+                }
+            }
+        ",
+            Some(sender),
+        )
+        .unwrap();
+    let res = devnet
+        .publish_module(Request::new(VmPublishModule {
+            sender: sender.to_vec(),
+            max_gas_amount: 1_000_000,
+            gas_unit_price: 1,
+            code: module.clone(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(res.status.unwrap().error.is_none());
+
+    let id = ModuleId::new(sender, Identifier::new("Foo").unwrap());
+    assert_eq!(ds.get(&AccessPath::from(&id)), Some(module.clone()));
+
+    let loaded = FileDataSource::open(ds.path()).unwrap();
+    assert_eq!(loaded.get(&AccessPath::from(&id)), Some(module));
+    std::fs::remove_file(ds.path()).unwrap();
+}
@@ -0,0 +1,275 @@
+use byteorder::{ByteOrder, LittleEndian};
+use compiler::Compiler;
+use data_source::MockDataSource;
+use dvm_net::api::grpc::vm_balance_change::Op;
+use dvm_net::api::grpc::vm_script_executor_server::VmScriptExecutor;
+use dvm_net::api::grpc::vm_status::Error;
+use dvm_net::api::grpc::{VmArgs, VmBalanceChange, VmExecuteResponse, VmExecuteScript, VmTypeTag};
+use dvm_net::tonic;
+use dvm_services::vm::{VmService, FEE_PAYER, SIGNERS_AUTH};
+use lang::stdlib::build_std;
+use libra::prelude::*;
+use runtime::vm::dvm::Dvm;
+use tonic::Request;
+
+const TRANSFER: &str = "
+    script {
+        use 0x1::Account;
+        use 0x1::XFI;
+
+        fun main(payer: &signer, receiver: &signer, amount: u128) {
+            let xfi = Account::deposit_native<XFI::T>(payer, amount);
+            Account::withdraw_native<XFI::T>(receiver, xfi);
+        }
+    }
+";
+
+const RECEIVE: &str = "
+    script {
+        use 0x1::Account;
+        use 0x1::XFI;
+
+        fun main(receiver: &signer, payer: &signer, amount: u128) {
+            let xfi = Account::deposit_native<XFI::T>(payer, amount);
+            Account::withdraw_native<XFI::T>(receiver, xfi);
+        }
+    }
+";
+
+fn u128_arg(val: u128) -> VmArgs {
+    let mut buf = vec![0; 16];
+    LittleEndian::write_u128(&mut buf, val);
+    VmArgs {
+        r#type: VmTypeTag::U128 as i32,
+        value: buf,
+    }
+}
+
+fn script(code: Vec<u8>, senders: &[AccountAddress], args: Vec<VmArgs>) -> VmExecuteScript {
+    VmExecuteScript {
+        senders: senders.iter().map(|sender| sender.to_vec()).collect(),
+        max_gas_amount: 1_000_000,
+        gas_unit_price: 1,
+        block: 0,
+        timestamp: 0,
+        code,
+        type_params: vec![],
+        args,
+    }
+}
+
+fn status_code(response: &VmExecuteResponse) -> Option<u64> {
+    match response.status.as_ref().unwrap().error.as_ref()? {
+        Error::MoveError(error) => Some(error.status_code),
+        error => panic!("Unexpected error: {:?}", error),
+    }
+}
+
+fn amount(change: &VmBalanceChange) -> (bool, u128) {
+    match change.op.as_ref().unwrap() {
+        Op::Deposit(amount) => (true, amount.clone().into()),
+        Op::Withdraw(amount) => (false, amount.clone().into()),
+    }
+}
+
+#[tokio::test]
+async fn test_fee_is_merged_into_balance_changes() {
+    let ds = MockDataSource::with_write_set(build_std());
+    let compiler = Compiler::new(ds.clone());
+    let service = VmService::new(Dvm::new(ds.clone(), None).with_fee(true), None);
+
+    let payer = AccountAddress::new([1; AccountAddress::LENGTH]);
+    let receiver = AccountAddress::new([2; AccountAddress::LENGTH]);
+    ds.set_balance(payer, "XFI", 2_000_000);
+    let code = compiler.compile(TRANSFER, Some(payer)).unwrap();
+
+    let response = service
+        .execute_script(Request::new(script(
+            code.clone(),
+            &[payer, receiver],
+            vec![u128_arg(1_000)],
+        )))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.status.unwrap().error.is_none());
+    let changes = &response.balance_change_set;
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[0].address, payer.to_vec());
+    assert_eq!(changes[0].ticker, "XFI");
+    assert_eq!(
+        amount(&changes[0]),
+        (true, 1_000 + response.gas_used as u128)
+    );
+    assert_eq!(changes[1].address, receiver.to_vec());
+    assert_eq!(amount(&changes[1]), (false, 1_000));
+
+    // the fee is paid out of the received coins.
+    let code = compiler.compile(RECEIVE, Some(receiver)).unwrap();
+    ds.set_balance(receiver, "XFI", 2_000_000);
+    let response = service
+        .execute_script(Request::new(script(
+            code.clone(),
+            &[receiver, payer],
+            vec![u128_arg(1_000_000)],
+        )))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.status.unwrap().error.is_none());
+    let changes = &response.balance_change_set;
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[0].address, payer.to_vec());
+    assert_eq!(amount(&changes[0]), (true, 1_000_000));
+    assert_eq!(changes[1].address, receiver.to_vec());
+    assert_eq!(
+        amount(&changes[1]),
+        (false, 1_000_000 - response.gas_used as u128)
+    );
+
+    let response = service
+        .execute_script(Request::new(script(
+            code.clone(),
+            &[receiver, payer],
+            vec![u128_arg(1)],
+        )))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.status.unwrap().error.is_none());
+    let changes = &response.balance_change_set;
+    assert_eq!(changes.len(), 2);
+    assert_eq!(amount(&changes[1]), (true, response.gas_used as u128 - 1));
+
+    // the received coins cover exactly the fee: no zero change of the receiver.
+    let gas_used = response.gas_used;
+    let response = service
+        .execute_script(Request::new(script(
+            code,
+            &[receiver, payer],
+            vec![u128_arg(gas_used as u128)],
+        )))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.status.unwrap().error.is_none());
+    assert_eq!(response.gas_used, gas_used);
+    let changes = &response.balance_change_set;
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].address, payer.to_vec());
+}
+
+#[tokio::test]
+async fn test_fee_payer_metadata() {
+    let ds = MockDataSource::with_write_set(build_std());
+    let compiler = Compiler::new(ds.clone());
+    let service = VmService::new(Dvm::new(ds.clone(), None).with_fee(true), None);
+
+    let sender = AccountAddress::random();
+    let payer = AccountAddress::random();
+    ds.set_balance(payer, "XFI", 1_000_000);
+    let code = compiler
+        .compile("script { fun main() {} }", Some(sender))
+        .unwrap();
+
+    let response = service
+        .execute_script(Request::new(script(code.clone(), &[sender], vec![])))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(
+        status_code(&response),
+        Some(StatusCode::INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE as u64)
+    );
+
+    let mut request = Request::new(script(code.clone(), &[sender], vec![]));
+    request
+        .metadata_mut()
+        .insert(FEE_PAYER, format!("0x{}", payer).parse().unwrap());
+    let response = service.execute_script(request).await.unwrap().into_inner();
+    assert_eq!(status_code(&response), None);
+    let changes = &response.balance_change_set;
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].address, payer.to_vec());
+    assert_eq!(amount(&changes[0]), (true, response.gas_used as u128));
+
+    let mut request = Request::new(script(code, &[sender], vec![]));
+    request
+        .metadata_mut()
+        .insert(FEE_PAYER, "wallet1".parse().unwrap());
+    let response = service.execute_script(request).await.unwrap().into_inner();
+    assert_eq!(
+        status_code(&response),
+        Some(StatusCode::DATA_FORMAT_ERROR as u64)
+    );
+}
+
+#[tokio::test]
+async fn test_signers_auth_metadata() {
+    let ds = MockDataSource::with_write_set(build_std());
+    let compiler = Compiler::new(ds.clone());
+    let service = VmService::new(Dvm::new(ds, None), None);
+
+    let account_1 = AccountAddress::random();
+    let account_2 = AccountAddress::random();
+    let code = compiler
+        .compile(
+            "
+            script {
+                use 0x1::Signer;
+
+                fun main(account_1: &signer, account_2: &signer) {
+                    assert(Signer::key_scheme(account_1) == Signer::ed25519_scheme(), 1);
+                    assert(Signer::is_threshold_satisfied(account_1), 2);
+                    assert(Signer::is_multisig(account_2), 3);
+                    assert(!Signer::is_threshold_satisfied(account_2), 4);
+                }
+            }
+        ",
+            None,
+        )
+        .unwrap();
+    let request = |auth: Option<String>| {
+        let mut request = Request::new(script(code.clone(), &[account_1, account_2], vec![]));
+        if let Some(auth) = auth {
+            request
+                .metadata_mut()
+                .insert(SIGNERS_AUTH, auth.parse().unwrap());
+        }
+        request
+    };
+
+    let response = service
+        .execute_script(request(Some(format!(
+            "0x{}:0:true,0x{}:2:false",
+            account_1, account_2
+        ))))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(status_code(&response), None);
+
+    // no authorization metadata.
+    let response = service
+        .execute_script(request(None))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.status.unwrap().error.is_some());
+
+    for auth in &[
+        format!("0x{}:0", account_1),
+        format!("0x{}:0:yes", account_1),
+        format!("0x{}:0:true", AccountAddress::random()),
+    ] {
+        let response = service
+            .execute_script(request(Some(auth.to_owned())))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(
+            status_code(&response),
+            Some(StatusCode::DATA_FORMAT_ERROR as u64)
+        );
+    }
+}
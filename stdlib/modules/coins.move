@@ -4,18 +4,39 @@ address 0x1 {
 /// officially supported by blockchain (or peg-zone specifically) is added here.
 /// Ideally this module should be auto-generated and rarely updated via consensus
 module Coins {
+    use 0x1::Vector;
+
+    const ERR_UNKNOWN_PRICE_TIME: u64 = 1;
+
     struct ETH {}
     struct BTC {}
     struct USDT {}
 
+    /// Price with unix time of its last update in seconds.
+    /// Timestamp is empty if the update time is unknown.
     resource struct Price<Curr1, Curr2> {
-        value: u128
+        value: u128,
+        timestamp: vector<u64>
     }
 
     public fun get_price<Curr1, Curr2>(): u128 acquires Price {
         borrow_global<Price<Curr1, Curr2>>(0x1).value
     }
 
+    /// Returns price and unix time of its last update in seconds.
+    /// Aborts if the update time is unknown.
+    public fun get_price_with_time<Curr1, Curr2>(): (u128, u64) acquires Price {
+        let price = borrow_global<Price<Curr1, Curr2>>(0x1);
+        assert(!Vector::is_empty(&price.timestamp), ERR_UNKNOWN_PRICE_TIME);
+        (price.value, *Vector::borrow(&price.timestamp, 0))
+    }
+
+    /// Returns true if the time of the last price update is known.
+    public fun has_price_time<Curr1, Curr2>(): bool acquires Price {
+        has_price<Curr1, Curr2>()
+            && !Vector::is_empty(&borrow_global<Price<Curr1, Curr2>>(0x1).timestamp)
+    }
+
     public fun has_price<Curr1, Curr2>(): bool {
         exists<Price<Curr1, Curr2>>(0x1)
     }
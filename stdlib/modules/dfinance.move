@@ -7,12 +7,14 @@ module Dfinance {
 
     use 0x1::Signer;
     use 0x1::Event;
+    use 0x1::Vector;
 
     const ERR_INSUFFICIENT_PRIVILLEGES: u64 = 101;
     const ERR_TOKEN_ALREADY_REGISTERED: u64 = 102;
     const ERR_DECIMALS_OUT_OF_BOUNDS: u64 = 103;
     const ERR_CANT_WITHDRAW: u64 = 104;
     const ERR_NON_ZERO_DEPOSIT: u64 = 105;
+    const ERR_UNKNOWN_CURRENCY: u64 = 106;
 
     resource struct T<Coin> {
         value: u128
@@ -95,6 +97,105 @@ module Dfinance {
         borrow_global<Info<Coin>>(0x1).owner
     }
 
+    /// Registered currency description.
+    struct Currency {
+        ticker: vector<u8>,
+        denom: vector<u8>,
+        decimals: u8,
+        is_token: bool,
+        owner: address,
+        total_supply: u128
+    }
+
+    /// Registry of all currencies registered in the chain. Provided by VM.
+    resource struct Registry {
+        currencies: vector<Currency>
+    }
+
+    /// Total supply of the currency at the block.
+    struct SupplyRecord {
+        height: u64,
+        total_supply: u128
+    }
+
+    /// Total supply history of the currency. Provided by VM.
+    resource struct SupplyHistory<Coin> {
+        records: vector<SupplyRecord>
+    }
+
+    /// list of all registered currencies ordered by ticker
+    public fun currencies(): vector<Currency> acquires Registry {
+        *&borrow_global<Registry>(0x1).currencies
+    }
+
+    /// check whether currency with the ticker is registered
+    public fun is_registered(ticker: &vector<u8>): bool acquires Registry {
+        let (found, _) = find_currency(ticker);
+        found
+    }
+
+    /// getter for currency description by ticker, aborts if currency is not registered
+    public fun currency(ticker: &vector<u8>): Currency acquires Registry {
+        let (found, i) = find_currency(ticker);
+        assert(found, ERR_UNKNOWN_CURRENCY);
+        *Vector::borrow(&borrow_global<Registry>(0x1).currencies, i)
+    }
+
+    fun find_currency(ticker: &vector<u8>): (bool, u64) acquires Registry {
+        let currencies = &borrow_global<Registry>(0x1).currencies;
+        let i = 0;
+        let len = Vector::length(currencies);
+        while (i < len) {
+            if (&Vector::borrow(currencies, i).ticker == ticker) {
+                return (true, i)
+            };
+            i = i + 1;
+        };
+        (false, 0)
+    }
+
+    public fun currency_ticker(currency: &Currency): vector<u8> {
+        *&currency.ticker
+    }
+
+    public fun currency_denom(currency: &Currency): vector<u8> {
+        *&currency.denom
+    }
+
+    public fun currency_decimals(currency: &Currency): u8 {
+        currency.decimals
+    }
+
+    public fun currency_is_token(currency: &Currency): bool {
+        currency.is_token
+    }
+
+    public fun currency_owner(currency: &Currency): address {
+        currency.owner
+    }
+
+    public fun currency_total_supply(currency: &Currency): u128 {
+        currency.total_supply
+    }
+
+    /// total supply history of the currency ordered by block height
+    public fun supply_history<Coin>(): vector<SupplyRecord> acquires SupplyHistory {
+        *&borrow_global<SupplyHistory<Coin>>(0x1).records
+    }
+
+    /// check whether total supply history of the currency is available
+    public fun has_supply_history<Coin>(): bool {
+        exists<SupplyHistory<Coin>>(0x1)
+    }
+
+    public fun record_height(record: &SupplyRecord): u64 {
+        record.height
+    }
+
+    public fun record_total_supply(record: &SupplyRecord): u128 {
+        record.total_supply
+    }
+
     /// only 0x1 address and add denom descriptions, 0x1 holds information resource
     public fun register_coin<Coin>(account: &signer, denom: vector<u8>, decimals: u8) {
         assert_can_register_coin(account);
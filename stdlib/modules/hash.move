@@ -0,0 +1,12 @@
+address 0x1 {
+
+/// Cryptographic hashes:
+/// - SHA2-256 (FIPS 180-4)
+/// - SHA3-256 (FIPS 202)
+/// Keccak-256 (the pre-FIPS 202 padding used by Ethereum) is not provided:
+/// it needs a native in the libra fork.
+module Hash {
+    native public fun sha2_256(data: vector<u8>): vector<u8>;
+    native public fun sha3_256(data: vector<u8>): vector<u8>;
+}
+}
@@ -0,0 +1,303 @@
+address 0x1 {
+
+/// MultiSig is an N-of-M multisignature wallet. Wallet holds coins of a single
+/// currency and is published under the account which created it. Any owner
+/// can propose an action, other owners approve it, and once the number of
+/// approvals reaches the threshold any owner can execute it.
+///
+/// Proposals may have an expiration time (by Time::now()), expired proposals
+/// can be neither approved nor executed and can be removed by anyone.
+/// Wallet is funded from and pays to Account balances.
+module MultiSig {
+
+    use 0x1::Account;
+    use 0x1::Dfinance;
+    use 0x1::Event;
+    use 0x1::Signer;
+    use 0x1::Time;
+    use 0x1::Vector;
+
+    const ACTION_TRANSFER: u8 = 0;
+    const ACTION_SET_THRESHOLD: u8 = 1;
+
+    const ERR_WALLET_EXISTS: u64 = 201;
+    const ERR_INVALID_THRESHOLD: u64 = 202;
+    const ERR_DUPLICATE_OWNER: u64 = 203;
+    const ERR_NOT_OWNER: u64 = 204;
+    const ERR_UNKNOWN_PROPOSAL: u64 = 205;
+    const ERR_ALREADY_APPROVED: u64 = 206;
+    const ERR_NOT_ENOUGH_APPROVALS: u64 = 207;
+    const ERR_EXPIRED: u64 = 208;
+    const ERR_NOT_EXPIRED: u64 = 209;
+    const ERR_WRONG_EXPIRATION: u64 = 210;
+    const ERR_ZERO_AMOUNT: u64 = 211;
+
+    resource struct Wallet<Token> {
+        coin: Dfinance::T<Token>,
+        owners: vector<address>,
+        threshold: u64,
+        proposals: vector<Proposal>,
+        next_id: u64,
+    }
+
+    /// Proposed action with its approvals.
+    struct Proposal {
+        id: u64,
+        action: u8,
+        // for transfer
+        payee: address,
+        amount: u128,
+        // for threshold change
+        threshold: u64,
+        approvals: vector<address>,
+        // 0 - never expires
+        expiration: u64,
+    }
+
+    struct ProposalCreatedEvent {
+        wallet: address,
+        id: u64,
+        action: u8,
+        proposer: address,
+        expiration: u64,
+    }
+
+    struct ProposalApprovedEvent {
+        wallet: address,
+        id: u64,
+        owner: address,
+        approvals: u64,
+    }
+
+    struct ProposalExecutedEvent {
+        wallet: address,
+        id: u64,
+        action: u8,
+        executor: address,
+    }
+
+    /// Create wallet for <Token> under the sender account. `threshold` is the number
+    /// of `owners` approvals required to execute an action.
+    public fun create<Token>(account: &signer, owners: vector<address>, threshold: u64) {
+        assert(!exists<Wallet<Token>>(Signer::address_of(account)), ERR_WALLET_EXISTS);
+        check_threshold(&owners, threshold);
+        check_unique(&owners);
+
+        move_to(account, Wallet<Token> {
+            coin: Dfinance::zero<Token>(),
+            owners,
+            threshold,
+            proposals: Vector::empty<Proposal>(),
+            next_id: 0,
+        });
+    }
+
+    /// Put coins into the wallet.
+    public fun deposit<Token>(wallet: address, coin: Dfinance::T<Token>) acquires Wallet {
+        Dfinance::deposit(&mut borrow_global_mut<Wallet<Token>>(wallet).coin, coin);
+    }
+
+    /// Move `amount` of coins from the sender balance into the wallet.
+    public fun deposit_from_sender<Token>(
+        account: &signer,
+        wallet: address,
+        amount: u128
+    ) acquires Wallet {
+        deposit<Token>(wallet, Account::withdraw_from_sender<Token>(account, amount));
+    }
+
+    /// Propose to transfer `amount` of wallet coins to the `payee` balance.
+    /// Returns proposal id. Proposer approves it automatically.
+    public fun propose_transfer<Token>(
+        account: &signer,
+        wallet: address,
+        payee: address,
+        amount: u128,
+        expiration: u64
+    ): u64 acquires Wallet {
+        assert(amount > 0, ERR_ZERO_AMOUNT);
+        propose<Token>(account, wallet, ACTION_TRANSFER, payee, amount, 0, expiration)
+    }
+
+    /// Propose to change the number of approvals required to execute an action.
+    /// Returns proposal id. Proposer approves it automatically.
+    public fun propose_threshold<Token>(
+        account: &signer,
+        wallet: address,
+        threshold: u64,
+        expiration: u64
+    ): u64 acquires Wallet {
+        propose<Token>(account, wallet, ACTION_SET_THRESHOLD, wallet, 0, threshold, expiration)
+    }
+
+    /// Approve proposal `id` of the wallet.
+    public fun approve<Token>(account: &signer, wallet: address, id: u64) acquires Wallet {
+        let owner = Signer::address_of(account);
+        let w = borrow_global_mut<Wallet<Token>>(wallet);
+        assert(Vector::contains(&w.owners, &owner), ERR_NOT_OWNER);
+
+        let idx = find(&w.proposals, id);
+        let proposal = Vector::borrow_mut(&mut w.proposals, idx);
+        assert(!is_expired(proposal), ERR_EXPIRED);
+        assert(!Vector::contains(&proposal.approvals, &owner), ERR_ALREADY_APPROVED);
+        Vector::push_back(&mut proposal.approvals, owner);
+
+        Event::emit(account, ProposalApprovedEvent {
+            wallet,
+            id,
+            owner,
+            approvals: Vector::length(&proposal.approvals),
+        });
+    }
+
+    /// Execute approved proposal `id` of the wallet and remove it.
+    public fun execute<Token>(account: &signer, wallet: address, id: u64) acquires Wallet {
+        let executor = Signer::address_of(account);
+        let w = borrow_global_mut<Wallet<Token>>(wallet);
+        assert(Vector::contains(&w.owners, &executor), ERR_NOT_OWNER);
+
+        let idx = find(&w.proposals, id);
+        let proposal = Vector::remove(&mut w.proposals, idx);
+        assert(!is_expired(&proposal), ERR_EXPIRED);
+        assert(Vector::length(&proposal.approvals) >= w.threshold, ERR_NOT_ENOUGH_APPROVALS);
+
+        if (proposal.action == ACTION_TRANSFER) {
+            let coin = Dfinance::withdraw(&mut w.coin, proposal.amount);
+            Account::deposit<Token>(account, proposal.payee, coin);
+        } else {
+            check_threshold(&w.owners, proposal.threshold);
+            w.threshold = proposal.threshold;
+        };
+
+        Event::emit(account, ProposalExecutedEvent {
+            wallet,
+            id,
+            action: proposal.action,
+            executor,
+        });
+    }
+
+    /// Remove expired proposal `id` of the wallet.
+    public fun remove_expired<Token>(wallet: address, id: u64) acquires Wallet {
+        let w = borrow_global_mut<Wallet<Token>>(wallet);
+        let idx = find(&w.proposals, id);
+        assert(is_expired(Vector::borrow(&w.proposals, idx)), ERR_NOT_EXPIRED);
+        Vector::remove(&mut w.proposals, idx);
+    }
+
+    public fun has_wallet<Token>(wallet: address): bool {
+        exists<Wallet<Token>>(wallet)
+    }
+
+    public fun balance<Token>(wallet: address): u128 acquires Wallet {
+        Dfinance::value(&borrow_global<Wallet<Token>>(wallet).coin)
+    }
+
+    public fun owners<Token>(wallet: address): vector<address> acquires Wallet {
+        *&borrow_global<Wallet<Token>>(wallet).owners
+    }
+
+    public fun threshold<Token>(wallet: address): u64 acquires Wallet {
+        borrow_global<Wallet<Token>>(wallet).threshold
+    }
+
+    public fun has_proposal<Token>(wallet: address, id: u64): bool acquires Wallet {
+        let proposals = &borrow_global<Wallet<Token>>(wallet).proposals;
+        let i = 0;
+        let len = Vector::length(proposals);
+        while (i < len) {
+            if (Vector::borrow(proposals, i).id == id) {
+                return true
+            };
+            i = i + 1;
+        };
+        false
+    }
+
+    /// Number of approvals of proposal `id`.
+    public fun approvals<Token>(wallet: address, id: u64): u64 acquires Wallet {
+        let proposals = &borrow_global<Wallet<Token>>(wallet).proposals;
+        let idx = find(proposals, id);
+        Vector::length(&Vector::borrow(proposals, idx).approvals)
+    }
+
+    fun propose<Token>(
+        account: &signer,
+        wallet: address,
+        action: u8,
+        payee: address,
+        amount: u128,
+        threshold: u64,
+        expiration: u64
+    ): u64 acquires Wallet {
+        if (expiration != 0) {
+            assert(expiration > Time::now(), ERR_WRONG_EXPIRATION);
+        };
+
+        let proposer = Signer::address_of(account);
+        let w = borrow_global_mut<Wallet<Token>>(wallet);
+        assert(Vector::contains(&w.owners, &proposer), ERR_NOT_OWNER);
+        if (action == ACTION_SET_THRESHOLD) {
+            check_threshold(&w.owners, threshold);
+        };
+
+        let id = w.next_id;
+        w.next_id = id + 1;
+
+        let approvals = Vector::empty<address>();
+        Vector::push_back(&mut approvals, proposer);
+        Vector::push_back(&mut w.proposals, Proposal {
+            id,
+            action,
+            payee,
+            amount,
+            threshold,
+            approvals,
+            expiration,
+        });
+
+        Event::emit(account, ProposalCreatedEvent {
+            wallet,
+            id,
+            action,
+            proposer,
+            expiration,
+        });
+
+        id
+    }
+
+    fun find(proposals: &vector<Proposal>, id: u64): u64 {
+        let i = 0;
+        let len = Vector::length(proposals);
+        while (i < len) {
+            if (Vector::borrow(proposals, i).id == id) {
+                return i
+            };
+            i = i + 1;
+        };
+        abort ERR_UNKNOWN_PROPOSAL
+    }
+
+    fun is_expired(proposal: &Proposal): bool {
+        proposal.expiration != 0 && proposal.expiration <= Time::now()
+    }
+
+    fun check_threshold(owners: &vector<address>, threshold: u64) {
+        assert(threshold > 0 && threshold <= Vector::length(owners), ERR_INVALID_THRESHOLD);
+    }
+
+    fun check_unique(owners: &vector<address>) {
+        let len = Vector::length(owners);
+        let i = 0;
+        while (i < len) {
+            let j = i + 1;
+            while (j < len) {
+                assert(*Vector::borrow(owners, i) != *Vector::borrow(owners, j), ERR_DUPLICATE_OWNER);
+                j = j + 1;
+            };
+            i = i + 1;
+        };
+    }
+}
+}
@@ -0,0 +1,287 @@
+address 0x1 {
+
+/// NFT is a standard for non-fungible tokens. Collection of tokens is identified
+/// by the creator address and the `T` type, token is identified by the
+/// collection and its id which is unique within the collection.
+///
+/// Tokens owned by the account are stored in its `Gallery<T>`.
+/// Token `metadata` is opaque for Move; by convention it is a UTF-8 JSON document
+/// (e.g. `{"name":"...","uri":"..."}`). Explorers can find the layout of `Token`
+/// and `Gallery` with the bytecode metadata of the `0x1::NFT` module and decode
+/// the stored resources with it.
+///
+/// Transfer is a two-step flow: the owner offers a token to the recipient and
+/// the recipient accepts it. The owner can cancel an offer until it is accepted.
+module NFT {
+
+    use 0x1::Event;
+    use 0x1::Signer;
+    use 0x1::Vector;
+
+    const ERR_COLLECTION_EXISTS: u64 = 401;
+    const ERR_NO_COLLECTION: u64 = 402;
+    const ERR_NO_TOKEN: u64 = 403;
+    const ERR_NO_OFFER: u64 = 404;
+    const ERR_NOT_RECIPIENT: u64 = 405;
+
+    const MAX_U64: u64 = 18446744073709551615;
+
+    /// Collection info, stored under the creator account.
+    resource struct Collection<T> {
+        name: vector<u8>,
+        metadata: vector<u8>,
+        next_id: u64,
+        // number of minted and not burned tokens
+        supply: u64,
+    }
+
+    resource struct Token<T> {
+        creator: address,
+        id: u64,
+        metadata: vector<u8>,
+    }
+
+    /// Tokens of the account.
+    resource struct Gallery<T> {
+        tokens: vector<Token<T>>,
+    }
+
+    /// Tokens offered by the account.
+    resource struct Offers<T> {
+        offers: vector<Offer<T>>,
+    }
+
+    resource struct Offer<T> {
+        token: Token<T>,
+        to: address,
+    }
+
+    struct MintEvent {
+        creator: address,
+        id: u64,
+        metadata: vector<u8>,
+    }
+
+    struct TransferEvent {
+        creator: address,
+        id: u64,
+        from: address,
+        to: address,
+    }
+
+    struct BurnEvent {
+        creator: address,
+        id: u64,
+        owner: address,
+    }
+
+    /// Create collection of <T> tokens under the sender account.
+    public fun create_collection<T>(
+        account: &signer,
+        name: vector<u8>,
+        metadata: vector<u8>
+    ) {
+        assert(!exists<Collection<T>>(Signer::address_of(account)), ERR_COLLECTION_EXISTS);
+        move_to(account, Collection<T> {
+            name,
+            metadata,
+            next_id: 0,
+            supply: 0,
+        });
+    }
+
+    /// Mint a new token of the sender collection to the sender gallery.
+    /// Returns id of the token.
+    public fun mint<T>(
+        account: &signer,
+        metadata: vector<u8>
+    ): u64 acquires Collection, Gallery {
+        let creator = Signer::address_of(account);
+        assert(exists<Collection<T>>(creator), ERR_NO_COLLECTION);
+
+        let collection = borrow_global_mut<Collection<T>>(creator);
+        let id = collection.next_id;
+        collection.next_id = id + 1;
+        collection.supply = collection.supply + 1;
+
+        put(account, Token<T> {
+            creator,
+            id,
+            metadata: copy metadata,
+        });
+
+        Event::emit(account, MintEvent {
+            creator,
+            id,
+            metadata,
+        });
+
+        id
+    }
+
+    /// Offer the token to the `to` account. The token leaves the sender gallery.
+    public fun offer<T>(
+        account: &signer,
+        creator: address,
+        id: u64,
+        to: address
+    ) acquires Gallery, Offers {
+        let token = take<T>(Signer::address_of(account), creator, id);
+        if (!exists<Offers<T>>(Signer::address_of(account))) {
+            move_to(account, Offers<T> { offers: Vector::empty<Offer<T>>() });
+        };
+
+        let offers = &mut borrow_global_mut<Offers<T>>(Signer::address_of(account)).offers;
+        Vector::push_back(offers, Offer<T> { token, to });
+    }
+
+    /// Accept the token offered by `from`.
+    public fun accept<T>(
+        account: &signer,
+        from: address,
+        creator: address,
+        id: u64
+    ) acquires Gallery, Offers {
+        let to = Signer::address_of(account);
+        let Offer { token, to: recipient } = take_offer<T>(from, creator, id);
+        assert(recipient == to, ERR_NOT_RECIPIENT);
+        put(account, token);
+
+        Event::emit(account, TransferEvent {
+            creator,
+            id,
+            from,
+            to,
+        });
+    }
+
+    /// Cancel the offer and return the token to the sender gallery.
+    public fun cancel_offer<T>(
+        account: &signer,
+        creator: address,
+        id: u64
+    ) acquires Gallery, Offers {
+        let Offer { token, to: _ } = take_offer<T>(Signer::address_of(account), creator, id);
+        put(account, token);
+    }
+
+    /// Burn the token of the sender gallery.
+    public fun burn<T>(
+        account: &signer,
+        creator: address,
+        id: u64
+    ) acquires Collection, Gallery {
+        let owner = Signer::address_of(account);
+        let Token { creator: _, id: _, metadata: _ } = take<T>(owner, creator, id);
+
+        let collection = borrow_global_mut<Collection<T>>(creator);
+        collection.supply = collection.supply - 1;
+
+        Event::emit(account, BurnEvent {
+            creator,
+            id,
+            owner,
+        });
+    }
+
+    public fun has_collection<T>(creator: address): bool {
+        exists<Collection<T>>(creator)
+    }
+
+    public fun collection_name<T>(creator: address): vector<u8> acquires Collection {
+        *&borrow_global<Collection<T>>(creator).name
+    }
+
+    public fun collection_metadata<T>(creator: address): vector<u8> acquires Collection {
+        *&borrow_global<Collection<T>>(creator).metadata
+    }
+
+    /// Number of existing tokens of the collection.
+    public fun supply<T>(creator: address): u64 acquires Collection {
+        borrow_global<Collection<T>>(creator).supply
+    }
+
+    /// Number of tokens of the collection owned by `owner`.
+    public fun balance<T>(owner: address): u64 acquires Gallery {
+        if (exists<Gallery<T>>(owner)) {
+            Vector::length(&borrow_global<Gallery<T>>(owner).tokens)
+        } else {
+            0
+        }
+    }
+
+    public fun owns<T>(owner: address, creator: address, id: u64): bool acquires Gallery {
+        exists<Gallery<T>>(owner)
+            && index_of(&borrow_global<Gallery<T>>(owner).tokens, creator, id) < MAX_U64
+    }
+
+    public fun token_metadata<T>(
+        owner: address,
+        creator: address,
+        id: u64
+    ): vector<u8> acquires Gallery {
+        let tokens = &borrow_global<Gallery<T>>(owner).tokens;
+        let idx = index_of(tokens, creator, id);
+        assert(idx < MAX_U64, ERR_NO_TOKEN);
+        *&Vector::borrow(tokens, idx).metadata
+    }
+
+    /// Returns true if `from` offers the token.
+    public fun has_offer<T>(from: address, creator: address, id: u64): bool acquires Offers {
+        exists<Offers<T>>(from)
+            && offer_index_of(&borrow_global<Offers<T>>(from).offers, creator, id) < MAX_U64
+    }
+
+    fun put<T>(account: &signer, token: Token<T>) acquires Gallery {
+        let owner = Signer::address_of(account);
+        if (!exists<Gallery<T>>(owner)) {
+            move_to(account, Gallery<T> { tokens: Vector::empty<Token<T>>() });
+        };
+        Vector::push_back(&mut borrow_global_mut<Gallery<T>>(owner).tokens, token);
+    }
+
+    fun take<T>(owner: address, creator: address, id: u64): Token<T> acquires Gallery {
+        assert(exists<Gallery<T>>(owner), ERR_NO_TOKEN);
+        let tokens = &mut borrow_global_mut<Gallery<T>>(owner).tokens;
+        let idx = index_of(tokens, creator, id);
+        assert(idx < MAX_U64, ERR_NO_TOKEN);
+        Vector::remove(tokens, idx)
+    }
+
+    fun take_offer<T>(from: address, creator: address, id: u64): Offer<T> acquires Offers {
+        assert(exists<Offers<T>>(from), ERR_NO_OFFER);
+        let offers = &mut borrow_global_mut<Offers<T>>(from).offers;
+        let idx = offer_index_of(offers, creator, id);
+        assert(idx < MAX_U64, ERR_NO_OFFER);
+        Vector::remove(offers, idx)
+    }
+
+    /// Returns index of the token or MAX_U64 if there is no such token.
+    fun index_of<T>(tokens: &vector<Token<T>>, creator: address, id: u64): u64 {
+        let i = 0;
+        let len = Vector::length(tokens);
+        while (i < len) {
+            let token = Vector::borrow(tokens, i);
+            if (token.creator == creator && token.id == id) {
+                return i
+            };
+            i = i + 1;
+        };
+        MAX_U64
+    }
+
+    /// Returns index of the offer or MAX_U64 if there is no such offer.
+    fun offer_index_of<T>(offers: &vector<Offer<T>>, creator: address, id: u64): u64 {
+        let i = 0;
+        let len = Vector::length(offers);
+        while (i < len) {
+            let token = &Vector::borrow(offers, i).token;
+            if (token.creator == creator && token.id == id) {
+                return i
+            };
+            i = i + 1;
+        };
+        MAX_U64
+    }
+}
+}
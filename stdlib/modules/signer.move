@@ -14,6 +14,55 @@ module Signer {
         *borrow_address(s)
     }
 
+    const KEY_SCHEME_ED25519: u8 = 0;
+    const KEY_SCHEME_SECP256K1: u8 = 1;
+    const KEY_SCHEME_MULTISIG: u8 = 2;
+
+    const ERR_NO_AUTHORIZATION: u64 = 1;
+
+    // Authorization metadata of the transaction signer verified by the node.
+    // Published by the VM for the current transaction signers only.
+    resource struct Authorization {
+        key_scheme: u8,
+        threshold_satisfied: bool,
+    }
+
+    // Checks whether the authorization metadata of the signer is known
+    public fun has_authorization(s: &signer): bool {
+        exists<Authorization>(address_of(s))
+    }
+
+    // Returns key scheme of the signer signature
+    public fun key_scheme(s: &signer): u8 acquires Authorization {
+        let addr = address_of(s);
+        assert(exists<Authorization>(addr), ERR_NO_AUTHORIZATION);
+        borrow_global<Authorization>(addr).key_scheme
+    }
+
+    // Checks whether the multisig threshold of the signer is satisfied
+    public fun is_threshold_satisfied(s: &signer): bool acquires Authorization {
+        let addr = address_of(s);
+        assert(exists<Authorization>(addr), ERR_NO_AUTHORIZATION);
+        borrow_global<Authorization>(addr).threshold_satisfied
+    }
+
+    // Checks whether the signer is authorized with the multisig key scheme
+    public fun is_multisig(s: &signer): bool acquires Authorization {
+        key_scheme(s) == KEY_SCHEME_MULTISIG
+    }
+
+    public fun ed25519_scheme(): u8 {
+        KEY_SCHEME_ED25519
+    }
+
+    public fun secp256k1_scheme(): u8 {
+        KEY_SCHEME_SECP256K1
+    }
+
+    public fun multisig_scheme(): u8 {
+        KEY_SCHEME_MULTISIG
+    }
+
     spec module {
         native define get_address(account: signer): address;
     }
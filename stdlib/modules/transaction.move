@@ -0,0 +1,22 @@
+address 0x1 {
+
+module Transaction {
+    use 0x1::Signer;
+
+    /// A singleton resource holding the account which pays the current transaction fee.
+    resource struct FeePayer {
+        payer: address,
+    }
+
+    /// Get the address of the account paying the current transaction fee.
+    /// It is the first sender unless the transaction is sponsored.
+    public fun fee_payer(): address acquires FeePayer {
+        borrow_global<FeePayer>(0x1).payer
+    }
+
+    /// Check if the transaction fee is paid by an account other than `account`.
+    public fun is_sponsored(account: &signer): bool acquires FeePayer {
+        fee_payer() != Signer::address_of(account)
+    }
+}
+}
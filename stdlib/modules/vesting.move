@@ -0,0 +1,293 @@
+address 0x1 {
+
+/// Vesting escrows coins for a beneficiary and releases them by schedule.
+/// Grants are stored under the admin account which created them, one grant
+/// per beneficiary and currency.
+///
+/// Schedule starts at `start` and releases nothing until `start + cliff`,
+/// then it releases coins linearly until `start + duration`. Zero duration
+/// means cliff-only schedule: everything is released at `start + cliff`.
+/// Schedule is keyed either on Time::now() or on the current block height.
+module Vesting {
+
+    use 0x1::Account;
+    use 0x1::Block;
+    use 0x1::Dfinance;
+    use 0x1::Event;
+    use 0x1::Signer;
+    use 0x1::Time;
+    use 0x1::Vector;
+
+    const CLOCK_TIME: u8 = 0;
+    const CLOCK_BLOCK: u8 = 1;
+
+    const ERR_GRANT_EXISTS: u64 = 301;
+    const ERR_NO_GRANT: u64 = 302;
+    const ERR_ZERO_AMOUNT: u64 = 303;
+    const ERR_INVALID_SCHEDULE: u64 = 304;
+    const ERR_INVALID_CLOCK: u64 = 305;
+    const ERR_NOTHING_TO_CLAIM: u64 = 306;
+
+    /// Grants of the admin account.
+    resource struct Vesting<Coin> {
+        grants: vector<Grant<Coin>>,
+    }
+
+    resource struct Grant<Coin> {
+        beneficiary: address,
+        coin: Dfinance::T<Coin>,
+        schedule: Schedule,
+    }
+
+    struct Schedule {
+        total: u128,
+        claimed: u128,
+        start: u64,
+        cliff: u64,
+        duration: u64,
+        clock: u8,
+    }
+
+    struct GrantCreatedEvent {
+        admin: address,
+        beneficiary: address,
+        amount: u128,
+        start: u64,
+        cliff: u64,
+        duration: u64,
+        clock: u8,
+    }
+
+    struct ClaimedEvent {
+        admin: address,
+        beneficiary: address,
+        amount: u128,
+    }
+
+    struct RevokedEvent {
+        admin: address,
+        beneficiary: address,
+        // vested coins paid to the beneficiary
+        paid: u128,
+        // locked coins returned to the admin
+        returned: u128,
+    }
+
+    /// Clock which uses Time::now().
+    public fun time_clock(): u8 {
+        CLOCK_TIME
+    }
+
+    /// Clock which uses the current block height.
+    public fun block_clock(): u8 {
+        CLOCK_BLOCK
+    }
+
+    /// Lock `coin` for `beneficiary` by schedule. `start`, `cliff` and `duration`
+    /// are measured in seconds or blocks depending on the `clock`.
+    public fun create<Coin>(
+        admin: &signer,
+        beneficiary: address,
+        coin: Dfinance::T<Coin>,
+        start: u64,
+        cliff: u64,
+        duration: u64,
+        clock: u8
+    ) acquires Vesting {
+        let amount = Dfinance::value(&coin);
+        assert(amount > 0, ERR_ZERO_AMOUNT);
+        assert(clock == CLOCK_TIME || clock == CLOCK_BLOCK, ERR_INVALID_CLOCK);
+        assert(duration == 0 || cliff <= duration, ERR_INVALID_SCHEDULE);
+
+        let admin_addr = Signer::address_of(admin);
+        if (!exists<Vesting<Coin>>(admin_addr)) {
+            move_to(admin, Vesting<Coin> { grants: Vector::empty<Grant<Coin>>() });
+        };
+
+        let grants = &mut borrow_global_mut<Vesting<Coin>>(admin_addr).grants;
+        assert(!contains(grants, beneficiary), ERR_GRANT_EXISTS);
+        Vector::push_back(grants, Grant<Coin> {
+            beneficiary,
+            coin,
+            schedule: Schedule {
+                total: amount,
+                claimed: 0,
+                start,
+                cliff,
+                duration,
+                clock,
+            },
+        });
+
+        Event::emit(admin, GrantCreatedEvent {
+            admin: admin_addr,
+            beneficiary,
+            amount,
+            start,
+            cliff,
+            duration,
+            clock,
+        });
+    }
+
+    /// Lock `amount` of the sender coins for `beneficiary` by schedule.
+    public fun create_from_sender<Coin>(
+        admin: &signer,
+        beneficiary: address,
+        amount: u128,
+        start: u64,
+        cliff: u64,
+        duration: u64,
+        clock: u8
+    ) acquires Vesting {
+        let coin = Account::withdraw_from_sender<Coin>(admin, amount);
+        create<Coin>(admin, beneficiary, coin, start, cliff, duration, clock)
+    }
+
+    /// Move released coins of the grant by `admin` to the sender balance.
+    /// Grant is removed once it is fully claimed. Returns claimed amount.
+    public fun claim<Coin>(account: &signer, admin: address): u128 acquires Vesting {
+        let beneficiary = Signer::address_of(account);
+        assert(exists<Vesting<Coin>>(admin), ERR_NO_GRANT);
+        let grants = &mut borrow_global_mut<Vesting<Coin>>(admin).grants;
+        let idx = find(grants, beneficiary);
+
+        let grant = Vector::borrow_mut(grants, idx);
+        let amount = vested(&grant.schedule) - grant.schedule.claimed;
+        assert(amount > 0, ERR_NOTHING_TO_CLAIM);
+        grant.schedule.claimed = grant.schedule.claimed + amount;
+        let coin = Dfinance::withdraw(&mut grant.coin, amount);
+
+        if (grant.schedule.claimed == grant.schedule.total) {
+            destroy(Vector::remove(grants, idx));
+        };
+        Account::deposit_to_sender<Coin>(account, coin);
+
+        Event::emit(account, ClaimedEvent {
+            admin,
+            beneficiary,
+            amount,
+        });
+
+        amount
+    }
+
+    /// Revoke the grant of `beneficiary`. Released but not claimed coins are paid
+    /// to the beneficiary, locked coins are returned to the admin.
+    public fun revoke<Coin>(admin: &signer, beneficiary: address) acquires Vesting {
+        let admin_addr = Signer::address_of(admin);
+        assert(exists<Vesting<Coin>>(admin_addr), ERR_NO_GRANT);
+        let grants = &mut borrow_global_mut<Vesting<Coin>>(admin_addr).grants;
+        let idx = find(grants, beneficiary);
+
+        let grant = Vector::remove(grants, idx);
+        let paid = vested(&grant.schedule) - grant.schedule.claimed;
+        let returned = grant.schedule.total - grant.schedule.claimed - paid;
+
+        if (paid > 0) {
+            let coin = Dfinance::withdraw(&mut grant.coin, paid);
+            Account::deposit<Coin>(admin, beneficiary, coin);
+        };
+        if (returned > 0) {
+            let coin = Dfinance::withdraw(&mut grant.coin, returned);
+            Account::deposit_to_sender<Coin>(admin, coin);
+        };
+        destroy(grant);
+
+        Event::emit(admin, RevokedEvent {
+            admin: admin_addr,
+            beneficiary,
+            paid,
+            returned,
+        });
+    }
+
+    public fun has_grant<Coin>(admin: address, beneficiary: address): bool acquires Vesting {
+        exists<Vesting<Coin>>(admin)
+            && contains(&borrow_global<Vesting<Coin>>(admin).grants, beneficiary)
+    }
+
+    /// Total amount of the grant.
+    public fun total<Coin>(admin: address, beneficiary: address): u128 acquires Vesting {
+        let schedule = get_schedule<Coin>(admin, beneficiary);
+        schedule.total
+    }
+
+    /// Amount already claimed by the beneficiary.
+    public fun claimed<Coin>(admin: address, beneficiary: address): u128 acquires Vesting {
+        let schedule = get_schedule<Coin>(admin, beneficiary);
+        schedule.claimed
+    }
+
+    /// Amount released by the schedule including already claimed coins.
+    public fun vested_amount<Coin>(admin: address, beneficiary: address): u128 acquires Vesting {
+        let schedule = get_schedule<Coin>(admin, beneficiary);
+        vested(&schedule)
+    }
+
+    /// Amount the beneficiary can claim now.
+    public fun claimable<Coin>(admin: address, beneficiary: address): u128 acquires Vesting {
+        let schedule = get_schedule<Coin>(admin, beneficiary);
+        vested(&schedule) - schedule.claimed
+    }
+
+    /// Amount which is still locked by the schedule.
+    public fun locked<Coin>(admin: address, beneficiary: address): u128 acquires Vesting {
+        let schedule = get_schedule<Coin>(admin, beneficiary);
+        schedule.total - vested(&schedule)
+    }
+
+    fun get_schedule<Coin>(admin: address, beneficiary: address): Schedule acquires Vesting {
+        let grants = &borrow_global<Vesting<Coin>>(admin).grants;
+        *&Vector::borrow(grants, find(grants, beneficiary)).schedule
+    }
+
+    fun vested(schedule: &Schedule): u128 {
+        let now = if (schedule.clock == CLOCK_BLOCK) {
+            Block::get_current_block_height()
+        } else {
+            Time::now()
+        };
+
+        if (now < schedule.start || now - schedule.start < schedule.cliff) {
+            0
+        } else if (schedule.duration == 0 || now - schedule.start >= schedule.duration) {
+            schedule.total
+        } else {
+            // split `total * elapsed / duration` to avoid the multiplication overflow.
+            let elapsed = ((now - schedule.start) as u128);
+            let duration = (schedule.duration as u128);
+            schedule.total / duration * elapsed + schedule.total % duration * elapsed / duration
+        }
+    }
+
+    fun destroy<Coin>(grant: Grant<Coin>) {
+        let Grant { beneficiary: _, coin, schedule: _ } = grant;
+        Dfinance::destroy_zero(coin);
+    }
+
+    fun contains<Coin>(grants: &vector<Grant<Coin>>, beneficiary: address): bool {
+        let i = 0;
+        let len = Vector::length(grants);
+        while (i < len) {
+            if (Vector::borrow(grants, i).beneficiary == beneficiary) {
+                return true
+            };
+            i = i + 1;
+        };
+        false
+    }
+
+    fun find<Coin>(grants: &vector<Grant<Coin>>, beneficiary: address): u64 {
+        let i = 0;
+        let len = Vector::length(grants);
+        while (i < len) {
+            if (Vector::borrow(grants, i).beneficiary == beneficiary) {
+                return i
+            };
+            i = i + 1;
+        };
+        abort ERR_NO_GRANT
+    }
+}
+}
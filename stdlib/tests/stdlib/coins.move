@@ -30,4 +30,50 @@ script {
         assert(Coins::get_price<Coins::ETH, Coins::BTC>() == 4078, 1);
         assert(Coins::get_price<Coins::BTC, XFI::T>() == 20, 2);
     }
-}
\ No newline at end of file
+}
+//#eth_btc:4078
+//#eth_btc_time:25.06.2020T10:38:10
+script {
+    use 0x01::Coins;
+
+    fun test_get_price_with_time() {
+        let (price, timestamp) = Coins::get_price_with_time<Coins::ETH, Coins::BTC>();
+        assert(price == 4078, 1);
+        assert(timestamp == 1593081490, 2);
+    }
+}
+
+//#time:25.06.2020T10:38:10
+//#eth_btc:4078
+script {
+    use 0x01::Coins;
+
+    fun test_price_time_defaults_to_block_time() {
+        let (_, timestamp) = Coins::get_price_with_time<Coins::ETH, Coins::BTC>();
+        assert(timestamp == 1593081490, 1);
+    }
+}
+
+//#eth_btc:4078
+//#eth_btc_time:unknown
+script {
+    use 0x01::Coins;
+
+    fun test_unknown_price_time() {
+        assert(Coins::has_price<Coins::ETH, Coins::BTC>(), 1);
+        assert(!Coins::has_price_time<Coins::ETH, Coins::BTC>(), 2);
+        assert(!Coins::has_price_time<Coins::ETH, Coins::USDT>(), 3);
+        assert(Coins::get_price<Coins::ETH, Coins::BTC>() == 4078, 4);
+    }
+}
+
+//#eth_btc:4078
+//#eth_btc_time:unknown
+//#error:1
+script {
+    use 0x01::Coins;
+
+    fun test_get_unknown_price_time() {
+        Coins::get_price_with_time<Coins::ETH, Coins::BTC>();
+    }
+}
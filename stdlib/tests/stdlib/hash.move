@@ -0,0 +1,17 @@
+script {
+    use 0x01::Hash;
+
+    fun test_sha2_256() {
+        assert(Hash::sha2_256(x"") == x"e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855", 1);
+        assert(Hash::sha2_256(b"abc") == x"ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad", 2);
+    }
+}
+
+script {
+    use 0x01::Hash;
+
+    fun test_sha3_256() {
+        assert(Hash::sha3_256(x"") == x"a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a", 1);
+        assert(Hash::sha3_256(b"abc") == x"3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532", 2);
+    }
+}
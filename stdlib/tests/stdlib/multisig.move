@@ -0,0 +1,276 @@
+//#sender:0x100
+script {
+    use 0x01::MultiSig;
+    use 0x01::Vector;
+    use 0x01::XFI;
+
+    fun create_wallet(account: &signer) {
+        let owners = Vector::empty<address>();
+        Vector::push_back(&mut owners, 0x101);
+        Vector::push_back(&mut owners, 0x102);
+        Vector::push_back(&mut owners, 0x103);
+        MultiSig::create<XFI::T>(account, owners, 2);
+
+        assert(MultiSig::has_wallet<XFI::T>(0x100), 1);
+        assert(MultiSig::threshold<XFI::T>(0x100) == 2, 2);
+        assert(Vector::length(&MultiSig::owners<XFI::T>(0x100)) == 3, 3);
+        assert(MultiSig::balance<XFI::T>(0x100) == 0, 4);
+    }
+}
+
+//#sender:0x100
+//#error:201
+script {
+    use 0x01::MultiSig;
+    use 0x01::Vector;
+    use 0x01::XFI;
+
+    fun wallet_exists(account: &signer) {
+        let owners = Vector::empty<address>();
+        Vector::push_back(&mut owners, 0x101);
+        MultiSig::create<XFI::T>(account, owners, 1);
+    }
+}
+
+//#sender:0x106
+//#error:202
+script {
+    use 0x01::MultiSig;
+    use 0x01::Vector;
+    use 0x01::XFI;
+
+    fun invalid_threshold(account: &signer) {
+        let owners = Vector::empty<address>();
+        Vector::push_back(&mut owners, 0x101);
+        Vector::push_back(&mut owners, 0x102);
+        MultiSig::create<XFI::T>(account, owners, 3);
+    }
+}
+
+//#sender:0x106
+//#error:203
+script {
+    use 0x01::MultiSig;
+    use 0x01::Vector;
+    use 0x01::XFI;
+
+    fun duplicate_owner(account: &signer) {
+        let owners = Vector::empty<address>();
+        Vector::push_back(&mut owners, 0x101);
+        Vector::push_back(&mut owners, 0x102);
+        Vector::push_back(&mut owners, 0x101);
+        MultiSig::create<XFI::T>(account, owners, 2);
+    }
+}
+
+//#sender:0x101
+//#balance:0x101:XFI:1000
+script {
+    use 0x01::Account;
+    use 0x01::MultiSig;
+    use 0x01::XFI;
+
+    fun fund_wallet(account: &signer) {
+        MultiSig::deposit_from_sender<XFI::T>(account, 0x100, 600);
+
+        assert(MultiSig::balance<XFI::T>(0x100) == 600, 1);
+        assert(Account::balance<XFI::T>(account) == 400, 2);
+    }
+}
+
+//#sender:0x101
+script {
+    use 0x01::MultiSig;
+    use 0x01::XFI;
+
+    fun propose_transfer(account: &signer) {
+        let id = MultiSig::propose_transfer<XFI::T>(account, 0x100, 0x104, 250, 0);
+
+        assert(id == 0, 1);
+        assert(MultiSig::has_proposal<XFI::T>(0x100, 0), 2);
+        assert(MultiSig::approvals<XFI::T>(0x100, 0) == 1, 3);
+    }
+}
+
+//#sender:0x105
+//#error:204
+script {
+    use 0x01::MultiSig;
+    use 0x01::XFI;
+
+    fun propose_not_owner(account: &signer) {
+        MultiSig::propose_transfer<XFI::T>(account, 0x100, 0x105, 250, 0);
+    }
+}
+
+//#sender:0x101
+//#error:207
+script {
+    use 0x01::MultiSig;
+    use 0x01::XFI;
+
+    fun execute_not_enough_approvals(account: &signer) {
+        MultiSig::execute<XFI::T>(account, 0x100, 0);
+    }
+}
+
+//#sender:0x105
+//#error:204
+script {
+    use 0x01::MultiSig;
+    use 0x01::XFI;
+
+    fun approve_not_owner(account: &signer) {
+        MultiSig::approve<XFI::T>(account, 0x100, 0);
+    }
+}
+
+//#sender:0x101
+//#error:206
+script {
+    use 0x01::MultiSig;
+    use 0x01::XFI;
+
+    fun approve_twice(account: &signer) {
+        MultiSig::approve<XFI::T>(account, 0x100, 0);
+    }
+}
+
+//#sender:0x102
+//#error:205
+script {
+    use 0x01::MultiSig;
+    use 0x01::XFI;
+
+    fun approve_unknown(account: &signer) {
+        MultiSig::approve<XFI::T>(account, 0x100, 42);
+    }
+}
+
+//#sender:0x102
+script {
+    use 0x01::MultiSig;
+    use 0x01::XFI;
+
+    fun approve(account: &signer) {
+        MultiSig::approve<XFI::T>(account, 0x100, 0);
+
+        assert(MultiSig::approvals<XFI::T>(0x100, 0) == 2, 1);
+    }
+}
+
+//#sender:0x103
+//#balance:0x104:XFI:0
+script {
+    use 0x01::Account;
+    use 0x01::MultiSig;
+    use 0x01::XFI;
+
+    fun execute_transfer(account: &signer) {
+        MultiSig::execute<XFI::T>(account, 0x100, 0);
+
+        assert(!MultiSig::has_proposal<XFI::T>(0x100, 0), 1);
+        assert(MultiSig::balance<XFI::T>(0x100) == 350, 2);
+        assert(Account::balance_for<XFI::T>(0x104) == 250, 3);
+    }
+}
+
+//#sender:0x101
+//#time:25.06.2020T10:38:10
+//#error:210
+script {
+    use 0x01::MultiSig;
+    use 0x01::XFI;
+
+    fun propose_expired(account: &signer) {
+        MultiSig::propose_threshold<XFI::T>(account, 0x100, 3, 1593081490);
+    }
+}
+
+//#sender:0x101
+//#time:25.06.2020T10:38:10
+script {
+    use 0x01::MultiSig;
+    use 0x01::XFI;
+
+    fun propose_with_expiration(account: &signer) {
+        // expires in a minute
+        let id = MultiSig::propose_threshold<XFI::T>(account, 0x100, 3, 1593081550);
+
+        assert(id == 1, 1);
+        assert(MultiSig::has_proposal<XFI::T>(0x100, 1), 2);
+    }
+}
+
+//#sender:0x101
+//#time:25.06.2020T10:38:50
+//#error:209
+script {
+    use 0x01::MultiSig;
+    use 0x01::XFI;
+
+    fun remove_not_expired() {
+        MultiSig::remove_expired<XFI::T>(0x100, 1);
+    }
+}
+
+//#sender:0x102
+//#time:25.06.2020T10:39:10
+//#error:208
+script {
+    use 0x01::MultiSig;
+    use 0x01::XFI;
+
+    fun approve_expired(account: &signer) {
+        MultiSig::approve<XFI::T>(account, 0x100, 1);
+    }
+}
+
+//#sender:0x105
+//#time:25.06.2020T10:39:10
+script {
+    use 0x01::MultiSig;
+    use 0x01::XFI;
+
+    fun remove_expired() {
+        MultiSig::remove_expired<XFI::T>(0x100, 1);
+
+        assert(!MultiSig::has_proposal<XFI::T>(0x100, 1), 1);
+    }
+}
+
+//#sender:0x101
+//#error:202
+script {
+    use 0x01::MultiSig;
+    use 0x01::XFI;
+
+    fun propose_invalid_threshold(account: &signer) {
+        MultiSig::propose_threshold<XFI::T>(account, 0x100, 4, 0);
+    }
+}
+
+//#sender:0x101
+script {
+    use 0x01::MultiSig;
+    use 0x01::XFI;
+
+    fun propose_threshold(account: &signer) {
+        let id = MultiSig::propose_threshold<XFI::T>(account, 0x100, 3, 0);
+
+        assert(id == 2, 1);
+    }
+}
+
+//#sender:0x103
+script {
+    use 0x01::MultiSig;
+    use 0x01::XFI;
+
+    fun approve_and_execute_threshold(account: &signer) {
+        MultiSig::approve<XFI::T>(account, 0x100, 2);
+        MultiSig::execute<XFI::T>(account, 0x100, 2);
+
+        assert(MultiSig::threshold<XFI::T>(0x100) == 3, 1);
+    }
+}
@@ -0,0 +1,147 @@
+address 0x02 {
+    //#sender:0x02
+    module Art {
+        struct T {}
+    }
+}
+
+//#sender:0x101
+script {
+    use 0x01::NFT;
+    use 0x02::Art;
+
+    fun create_collection(account: &signer) {
+        NFT::create_collection<Art::T>(account, b"Art", b"{\"uri\":\"https://art\"}");
+
+        assert(NFT::has_collection<Art::T>(0x101), 1);
+        assert(NFT::collection_name<Art::T>(0x101) == b"Art", 2);
+        assert(NFT::supply<Art::T>(0x101) == 0, 3);
+    }
+}
+
+//#sender:0x101
+//#error:401
+script {
+    use 0x01::NFT;
+    use 0x02::Art;
+
+    fun collection_exists(account: &signer) {
+        NFT::create_collection<Art::T>(account, b"Art", b"");
+    }
+}
+
+//#sender:0x102
+//#error:402
+script {
+    use 0x01::NFT;
+    use 0x02::Art;
+
+    fun mint_without_collection(account: &signer) {
+        NFT::mint<Art::T>(account, b"");
+    }
+}
+
+//#sender:0x101
+script {
+    use 0x01::NFT;
+    use 0x02::Art;
+
+    fun mint(account: &signer) {
+        assert(NFT::mint<Art::T>(account, b"{\"name\":\"first\"}") == 0, 1);
+        assert(NFT::mint<Art::T>(account, b"{\"name\":\"second\"}") == 1, 2);
+
+        assert(NFT::supply<Art::T>(0x101) == 2, 3);
+        assert(NFT::balance<Art::T>(0x101) == 2, 4);
+        assert(NFT::owns<Art::T>(0x101, 0x101, 1), 5);
+        assert(NFT::token_metadata<Art::T>(0x101, 0x101, 0) == b"{\"name\":\"first\"}", 6);
+    }
+}
+
+//#sender:0x101
+script {
+    use 0x01::NFT;
+    use 0x02::Art;
+
+    fun offer(account: &signer) {
+        NFT::offer<Art::T>(account, 0x101, 0, 0x102);
+
+        assert(NFT::has_offer<Art::T>(0x101, 0x101, 0), 1);
+        assert(!NFT::owns<Art::T>(0x101, 0x101, 0), 2);
+        assert(NFT::balance<Art::T>(0x101) == 1, 3);
+    }
+}
+
+//#sender:0x103
+//#error:405
+script {
+    use 0x01::NFT;
+    use 0x02::Art;
+
+    fun accept_not_recipient(account: &signer) {
+        NFT::accept<Art::T>(account, 0x101, 0x101, 0);
+    }
+}
+
+//#sender:0x102
+script {
+    use 0x01::NFT;
+    use 0x02::Art;
+
+    fun accept(account: &signer) {
+        NFT::accept<Art::T>(account, 0x101, 0x101, 0);
+
+        assert(NFT::owns<Art::T>(0x102, 0x101, 0), 1);
+        assert(!NFT::has_offer<Art::T>(0x101, 0x101, 0), 2);
+        assert(NFT::token_metadata<Art::T>(0x102, 0x101, 0) == b"{\"name\":\"first\"}", 3);
+    }
+}
+
+//#sender:0x102
+//#error:404
+script {
+    use 0x01::NFT;
+    use 0x02::Art;
+
+    fun accept_twice(account: &signer) {
+        NFT::accept<Art::T>(account, 0x101, 0x101, 0);
+    }
+}
+
+//#sender:0x102
+//#error:403
+script {
+    use 0x01::NFT;
+    use 0x02::Art;
+
+    fun offer_not_owned(account: &signer) {
+        NFT::offer<Art::T>(account, 0x101, 1, 0x103);
+    }
+}
+
+//#sender:0x101
+script {
+    use 0x01::NFT;
+    use 0x02::Art;
+
+    fun cancel_offer(account: &signer) {
+        NFT::offer<Art::T>(account, 0x101, 1, 0x103);
+        NFT::cancel_offer<Art::T>(account, 0x101, 1);
+
+        assert(!NFT::has_offer<Art::T>(0x101, 0x101, 1), 1);
+        assert(NFT::owns<Art::T>(0x101, 0x101, 1), 2);
+    }
+}
+
+//#sender:0x102
+script {
+    use 0x01::NFT;
+    use 0x02::Art;
+
+    fun burn(account: &signer) {
+        NFT::burn<Art::T>(account, 0x101, 0);
+
+        assert(NFT::supply<Art::T>(0x101) == 1, 1);
+        assert(NFT::balance<Art::T>(0x102) == 0, 2);
+        assert(!NFT::owns<Art::T>(0x102, 0x101, 0), 3);
+    }
+}
@@ -0,0 +1,28 @@
+//#senders:0x101,0x102
+//#signer_auth:0x101:0:true
+//#signer_auth:0x102:2:false
+script {
+    use 0x01::Signer;
+
+    fun multi_signer(account_1: &signer, account_2: &signer) {
+        assert(Signer::has_authorization(account_1), 1);
+        assert(Signer::key_scheme(account_1) == Signer::ed25519_scheme(), 2);
+        assert(Signer::is_threshold_satisfied(account_1), 3);
+        assert(!Signer::is_multisig(account_1), 4);
+
+        assert(Signer::has_authorization(account_2), 5);
+        assert(Signer::is_multisig(account_2), 6);
+        assert(!Signer::is_threshold_satisfied(account_2), 7);
+    }
+}
+
+//#sender:0x101
+//#error:1
+script {
+    use 0x01::Signer;
+
+    fun no_authorization(account: &signer) {
+        assert(!Signer::has_authorization(account), 2);
+        Signer::key_scheme(account);
+    }
+}
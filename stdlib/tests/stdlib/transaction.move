@@ -0,0 +1,20 @@
+//#sender:0x101
+script {
+    use 0x01::Transaction;
+
+    fun not_sponsored(account: &signer) {
+        assert(Transaction::fee_payer() == 0x101, 1);
+        assert(!Transaction::is_sponsored(account), 2);
+    }
+}
+
+//#sender:0x101
+//#fee_payer:0x102
+script {
+    use 0x01::Transaction;
+
+    fun sponsored(account: &signer) {
+        assert(Transaction::fee_payer() == 0x102, 1);
+        assert(Transaction::is_sponsored(account), 2);
+    }
+}
@@ -0,0 +1,212 @@
+//#sender:0x100
+//#time:25.06.2020T10:38:10
+//#block:100
+//#balance:0x100:XFI:1300
+script {
+    use 0x01::Account;
+    use 0x01::Vesting;
+    use 0x01::XFI;
+
+    fun create_grants(account: &signer) {
+        // linear by time: 1000 coins over 1000 seconds with 100 seconds cliff
+        Vesting::create_from_sender<XFI::T>(account, 0x101, 1000, 1593081490, 100, 1000, Vesting::time_clock());
+        // linear by blocks: 200 coins over 100 blocks with 10 blocks cliff
+        Vesting::create_from_sender<XFI::T>(account, 0x102, 200, 100, 10, 100, Vesting::block_clock());
+        // cliff only
+        Vesting::create_from_sender<XFI::T>(account, 0x103, 50, 1593081490, 0, 0, Vesting::time_clock());
+
+        assert(Account::balance<XFI::T>(account) == 50, 1);
+        assert(Vesting::has_grant<XFI::T>(0x100, 0x101), 2);
+        assert(Vesting::total<XFI::T>(0x100, 0x101) == 1000, 3);
+        assert(Vesting::claimable<XFI::T>(0x100, 0x101) == 0, 4);
+        assert(Vesting::locked<XFI::T>(0x100, 0x102) == 200, 5);
+        assert(Vesting::claimable<XFI::T>(0x100, 0x103) == 50, 6);
+        assert(!Vesting::has_grant<XFI::T>(0x100, 0x104), 7);
+    }
+}
+
+//#sender:0x100
+//#balance:0x100:XFI:100
+//#error:301
+script {
+    use 0x01::Vesting;
+    use 0x01::XFI;
+
+    fun grant_exists(account: &signer) {
+        Vesting::create_from_sender<XFI::T>(account, 0x101, 100, 0, 0, 0, Vesting::time_clock());
+    }
+}
+
+//#sender:0x100
+//#error:303
+script {
+    use 0x01::Dfinance;
+    use 0x01::Vesting;
+    use 0x01::XFI;
+
+    fun zero_amount(account: &signer) {
+        Vesting::create<XFI::T>(account, 0x104, Dfinance::zero<XFI::T>(), 0, 0, 0, Vesting::time_clock());
+    }
+}
+
+//#sender:0x100
+//#balance:0x100:XFI:100
+//#error:304
+script {
+    use 0x01::Vesting;
+    use 0x01::XFI;
+
+    fun cliff_after_end(account: &signer) {
+        Vesting::create_from_sender<XFI::T>(account, 0x104, 100, 0, 20, 10, Vesting::time_clock());
+    }
+}
+
+//#sender:0x100
+//#balance:0x100:XFI:100
+//#error:305
+script {
+    use 0x01::Vesting;
+    use 0x01::XFI;
+
+    fun invalid_clock(account: &signer) {
+        Vesting::create_from_sender<XFI::T>(account, 0x104, 100, 0, 0, 10, 2);
+    }
+}
+
+//#sender:0x101
+//#time:25.06.2020T10:39:00
+//#error:306
+script {
+    use 0x01::Vesting;
+    use 0x01::XFI;
+
+    fun claim_before_cliff(account: &signer) {
+        Vesting::claim<XFI::T>(account, 0x100);
+    }
+}
+
+//#sender:0x104
+//#error:302
+script {
+    use 0x01::Vesting;
+    use 0x01::XFI;
+
+    fun claim_without_grant(account: &signer) {
+        Vesting::claim<XFI::T>(account, 0x100);
+    }
+}
+
+//#sender:0x101
+//#time:25.06.2020T10:46:30
+//#balance:0x101:XFI:0
+script {
+    use 0x01::Account;
+    use 0x01::Vesting;
+    use 0x01::XFI;
+
+    fun claim_half(account: &signer) {
+        assert(Vesting::vested_amount<XFI::T>(0x100, 0x101) == 500, 1);
+        assert(Vesting::claim<XFI::T>(account, 0x100) == 500, 2);
+
+        assert(Account::balance<XFI::T>(account) == 500, 3);
+        assert(Vesting::claimed<XFI::T>(0x100, 0x101) == 500, 4);
+        assert(Vesting::claimable<XFI::T>(0x100, 0x101) == 0, 5);
+        assert(Vesting::locked<XFI::T>(0x100, 0x101) == 500, 6);
+    }
+}
+
+//#sender:0x101
+//#time:25.06.2020T10:55:00
+//#balance:0x101:XFI:500
+script {
+    use 0x01::Account;
+    use 0x01::Vesting;
+    use 0x01::XFI;
+
+    fun claim_rest(account: &signer) {
+        assert(Vesting::claim<XFI::T>(account, 0x100) == 500, 1);
+
+        assert(Account::balance<XFI::T>(account) == 1000, 2);
+        assert(!Vesting::has_grant<XFI::T>(0x100, 0x101), 3);
+    }
+}
+
+//#sender:0x103
+//#time:25.06.2020T10:38:10
+//#balance:0x103:XFI:0
+script {
+    use 0x01::Account;
+    use 0x01::Vesting;
+    use 0x01::XFI;
+
+    fun claim_cliff_only(account: &signer) {
+        assert(Vesting::claim<XFI::T>(account, 0x100) == 50, 1);
+
+        assert(Account::balance<XFI::T>(account) == 50, 2);
+        assert(!Vesting::has_grant<XFI::T>(0x100, 0x103), 3);
+    }
+}
+
+//#sender:0x102
+//#block:105
+//#error:306
+script {
+    use 0x01::Vesting;
+    use 0x01::XFI;
+
+    fun claim_before_block_cliff(account: &signer) {
+        Vesting::claim<XFI::T>(account, 0x100);
+    }
+}
+
+//#sender:0x105
+//#error:302
+script {
+    use 0x01::Vesting;
+    use 0x01::XFI;
+
+    fun revoke_not_admin(account: &signer) {
+        Vesting::revoke<XFI::T>(account, 0x102);
+    }
+}
+
+//#sender:0x100
+//#block:150
+//#balance:0x100:XFI:0
+//#balance:0x102:XFI:0
+script {
+    use 0x01::Account;
+    use 0x01::Vesting;
+    use 0x01::XFI;
+
+    fun revoke(account: &signer) {
+        assert(Vesting::vested_amount<XFI::T>(0x100, 0x102) == 100, 1);
+        Vesting::revoke<XFI::T>(account, 0x102);
+
+        assert(!Vesting::has_grant<XFI::T>(0x100, 0x102), 2);
+        assert(Account::balance<XFI::T>(account) == 100, 3);
+        assert(Account::balance_for<XFI::T>(0x102) == 100, 4);
+    }
+}
+
+//#sender:0x200
+//#block:500
+//#balance:0x200:XFI:170141183460469231731687303715884105000
+script {
+    use 0x01::Vesting;
+    use 0x01::XFI;
+
+    fun large_total(account: &signer) {
+        Vesting::create_from_sender<XFI::T>(
+            account,
+            0x201,
+            170141183460469231731687303715884105000,
+            0,
+            0,
+            1000,
+            Vesting::block_clock()
+        );
+        assert(Vesting::vested_amount<XFI::T>(0x200, 0x201) == 85070591730234615865843651857942052500, 1);
+        assert(Vesting::locked<XFI::T>(0x200, 0x201) == 85070591730234615865843651857942052500, 2);
+    }
+}
@@ -13,6 +13,7 @@ use std::sync::{Arc, Mutex};
 use compiler::Compiler;
 use data_source::Balance;
 use data_source::CurrencyInfo;
+use data_source::SupplyRecord;
 use data_source::MockDataSource;
 use dvm_net::api::grpc::{StructIdent, VmExecuteScript};
 pub use genesis::genesis_write_set;
@@ -88,6 +89,11 @@ impl TestKit {
         self.data_source.set_currency_info(ticker, info)
     }
 
+    /// Add total supply record to the currency history.
+    pub fn add_supply_record(&self, ticker: &str, record: SupplyRecord) {
+        self.data_source.add_supply_record(ticker, record)
+    }
+
     /// Creates a new test kit with given write set.
     pub fn with_genesis(ws: WriteSet) -> TestKit {
         let data_source = MockDataSource::with_write_set(ws);
@@ -4,18 +4,40 @@ mod runner;
 use lang::{
     stdlib::{build_std},
 };
-use std::collections::HashMap;
+use libra::prelude::*;
+use std::collections::{BTreeMap, HashMap};
 use crate::test_suite::runner::TestState;
 use termion::color;
 
+/// Test suite results.
+#[derive(Debug, Default)]
+pub struct TestReport {
+    /// Names of the passed tests.
+    pub passed: Vec<String>,
+    /// Names of the failed tests with errors.
+    pub failed: Vec<(String, anyhow::Error)>,
+}
+
+impl TestReport {
+    /// Returns `true` if all tests passed.
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
 /// Runs move test suite.
 pub fn run_test_suite(suite: HashMap<String, String>) {
-    let stdlib = build_std();
+    if !perform_test_suite(build_std(), suite).is_success() {
+        panic!("Test failed.");
+    }
+}
 
-    let mut has_error = false;
-    for (test_name, content) in suite {
-        let test = TestState::new(stdlib.clone(), test_name.clone(), content);
-        match test.perform() {
+/// Runs move test suite over the genesis write set and prints the test results.
+/// Tests are performed in the name order.
+pub fn perform_test_suite(genesis: WriteSet, suite: HashMap<String, String>) -> TestReport {
+    let mut report = TestReport::default();
+    for (test_name, content) in suite.into_iter().collect::<BTreeMap<_, _>>() {
+        match perform_test(genesis.clone(), test_name.clone(), content) {
             Ok(_) => {
                 println!(
                     "{}Test: {} - {}OK",
@@ -23,6 +45,7 @@ pub fn run_test_suite(suite: HashMap<String, String>) {
                     test_name,
                     color::Fg(color::Green)
                 );
+                report.passed.push(test_name);
             }
             Err(err) => {
                 println!(
@@ -32,11 +55,14 @@ pub fn run_test_suite(suite: HashMap<String, String>) {
                     color::Fg(color::Red)
                 );
                 println!("{}", err);
-                has_error = true;
+                report.failed.push((test_name, err));
             }
         }
     }
-    if has_error {
-        panic!("Test failed.");
-    }
+    report
+}
+
+/// Runs a single test over the genesis write set.
+pub fn perform_test(genesis: WriteSet, test_name: String, content: String) -> anyhow::Result<()> {
+    TestState::new(genesis, test_name, content).perform()
 }
@@ -2,13 +2,14 @@ use anyhow::{Result, Error};
 use lazy_static::lazy_static;
 use regex::Regex;
 use libra::prelude::*;
+use runtime::vm::types::SignerAuth;
 use std::convert::TryFrom;
 use chrono::{Utc, TimeZone};
 use std::vec::IntoIter;
 use std::mem;
 
 lazy_static! {
-    static ref META_RE: Regex = Regex::new("\\#([A-Za-z0-9_]+)[:|=]?([A-Za-z0-9.:]*)").unwrap();
+    static ref META_RE: Regex = Regex::new("\\#([A-Za-z0-9_]+)[:|=]?([A-Za-z0-9.:,]*)").unwrap();
     static ref MODULE_RE: Regex = Regex::new("\\s*module\\s*([A-Za-z0-9_]+)").unwrap();
     static ref SCRIPT_RE: Regex = Regex::new("^\\s*script\\s*").unwrap();
     static ref FUNCTION_RE: Regex = Regex::new("\\s*fun\\s*([A-Za-z0-9_]+)").unwrap();
@@ -113,6 +114,26 @@ fn handle_meta_tag(
         MetaTag::Address(addr) => {
             meta.senders.push(addr);
         }
+        MetaTag::Senders(mut senders) => {
+            meta.senders.append(&mut senders);
+        }
+        MetaTag::SignerAuth((signer, auth)) => {
+            meta.signers_auth.push((signer, auth));
+        }
+        MetaTag::FeePayer(payer) => {
+            if meta.fee_payer.is_none() {
+                meta.fee_payer = Some(payer);
+            } else {
+                return Err(anyhow!(
+                    "Fee payer is already set. Error location [{}:{}]",
+                    file_name,
+                    line_number
+                ));
+            }
+        }
+        MetaTag::Balance(balance) => {
+            meta.balances.push(balance);
+        }
         MetaTag::Gas(g) => {
             if meta.gas == None {
                 meta.gas = Some(g)
@@ -183,6 +204,14 @@ fn handle_meta_tag(
                 list.push(price);
             }
         }
+        MetaTag::PriceTime(time) => {
+            if meta.oracle_price_time_list.is_none() {
+                meta.oracle_price_time_list = Some(vec![]);
+            }
+            if let Some(list) = meta.oracle_price_time_list.as_mut() {
+                list.push(time);
+            }
+        }
         MetaTag::Success => {
             if meta.expected_result.is_none() {
                 meta.expected_result = Some(ExecutionResult::Success)
@@ -224,22 +253,30 @@ impl TestStep {
 #[derive(Debug, PartialEq, Default)]
 struct MetaState {
     senders: Vec<AccountAddress>,
+    fee_payer: Option<AccountAddress>,
+    signers_auth: Vec<(AccountAddress, SignerAuth)>,
+    balances: Vec<(AccountAddress, String, u128)>,
     gas: Option<u64>,
     expected_result: Option<ExecutionResult>,
     block: Option<u64>,
     time: Option<u64>,
     oracle_price_list: Option<Vec<((String, String), u128)>>,
+    oracle_price_time_list: Option<Vec<((String, String), Option<u64>)>>,
 }
 
 /// Test metadata.
 #[derive(Debug, PartialEq)]
 pub struct TestMeta {
     pub senders: Vec<AccountAddress>,
+    pub fee_payer: Option<AccountAddress>,
+    pub signers_auth: Vec<(AccountAddress, SignerAuth)>,
+    pub balances: Vec<(AccountAddress, String, u128)>,
     pub gas: u64,
     pub expected_result: ExecutionResult,
     pub block: u64,
     pub time: u64,
     pub oracle_price_list: Vec<((String, String), u128)>,
+    pub oracle_price_time_list: Vec<((String, String), Option<u64>)>,
 }
 
 impl TestMeta {
@@ -254,6 +291,9 @@ impl TestMeta {
         };
         TestMeta {
             senders,
+            fee_payer: meta.fee_payer.take(),
+            signers_auth: mem::take(&mut meta.signers_auth),
+            balances: mem::take(&mut meta.balances),
             gas: meta.gas.take().unwrap_or(100_000_000),
             expected_result: meta
                 .expected_result
@@ -265,6 +305,7 @@ impl TestMeta {
                 .take()
                 .unwrap_or_else(|| Utc::now().timestamp() as u64),
             oracle_price_list: meta.oracle_price_list.take().unwrap_or_else(Vec::new),
+            oracle_price_time_list: meta.oracle_price_time_list.take().unwrap_or_else(Vec::new),
         }
     }
 }
@@ -288,6 +329,14 @@ pub enum MetaTag {
     Error(Option<u64>),
     /// Execution account address.
     Address(AccountAddress),
+    /// Transaction fee payer address.
+    FeePayer(AccountAddress),
+    /// Comma separated execution account addresses.
+    Senders(Vec<AccountAddress>),
+    /// Signer authorization metadata. Format address:key_scheme:threshold_satisfied.
+    SignerAuth((AccountAddress, SignerAuth)),
+    /// Native balance. Format address:ticker:amount.
+    Balance((AccountAddress, String, u128)),
     /// Max gas.
     Gas(u64),
     /// Error main status.
@@ -298,6 +347,8 @@ pub enum MetaTag {
     Block(u64),
     /// Oracle price.
     Price(((String, String), u128)),
+    /// Oracle price update time. Format dd.MM.yyyyTHH:mm:ss or `unknown`.
+    PriceTime(((String, String), Option<u64>)),
 }
 
 impl TryFrom<(&str, &str)> for MetaTag {
@@ -314,6 +365,39 @@ impl TryFrom<(&str, &str)> for MetaTag {
                 }
             }
             "sender" => Ok(MetaTag::Address(AccountAddress::from_hex_literal(value)?)),
+            "fee_payer" => Ok(MetaTag::FeePayer(AccountAddress::from_hex_literal(value)?)),
+            "senders" => Ok(MetaTag::Senders(
+                value
+                    .split(',')
+                    .map(AccountAddress::from_hex_literal)
+                    .collect::<Result<_, _>>()?,
+            )),
+            "signer_auth" => {
+                let mut split = value.split(':');
+                let mut next = || {
+                    split
+                        .next()
+                        .ok_or_else(|| Error::msg("Failed to parse signer authorization."))
+                };
+                let signer = AccountAddress::from_hex_literal(next()?)?;
+                let auth = SignerAuth {
+                    key_scheme: next()?.parse()?,
+                    threshold_satisfied: next()?.parse()?,
+                };
+                Ok(MetaTag::SignerAuth((signer, auth)))
+            }
+            "balance" => {
+                let mut split = value.split(':');
+                let mut next = || {
+                    split
+                        .next()
+                        .ok_or_else(|| Error::msg("Failed to parse balance."))
+                };
+                let address = AccountAddress::from_hex_literal(next()?)?;
+                let ticker = next()?.to_uppercase();
+                let amount = next()?.parse()?;
+                Ok(MetaTag::Balance((address, ticker, amount)))
+            }
             "gas" => Ok(MetaTag::Gas(value.parse()?)),
             "status" => Ok(MetaTag::Status(value.parse()?)),
             "block" => Ok(MetaTag::Block(value.parse()?)),
@@ -332,7 +416,20 @@ impl TryFrom<(&str, &str)> for MetaTag {
                     .next()
                     .ok_or_else(|| Error::msg("Failed to parse oracle price."))?
                     .to_uppercase();
-                Ok(MetaTag::Price(((first, second), value.parse()?)))
+                match split.next() {
+                    None => Ok(MetaTag::Price(((first, second), value.parse()?))),
+                    Some("time") if value == "unknown" => {
+                        Ok(MetaTag::PriceTime(((first, second), None)))
+                    }
+                    Some("time") => {
+                        let date_time = Utc.datetime_from_str(value, "%d.%m.%YT%H:%M:%S")?;
+                        Ok(MetaTag::PriceTime((
+                            (first, second),
+                            Some(date_time.timestamp() as u64),
+                        )))
+                    }
+                    Some(_) => Err(Error::msg("Failed to parse oracle price.")),
+                }
             }
         }
     }
@@ -342,6 +439,7 @@ impl TryFrom<(&str, &str)> for MetaTag {
 pub mod tests {
     use libra::prelude::*;
     use crate::test_suite::pipeline::{MetaTag, TestPipeline, TestStep, TestMeta, ExecutionResult};
+    use runtime::vm::types::SignerAuth;
     use std::convert::TryFrom;
 
     #[test]
@@ -387,6 +485,13 @@ pub mod tests {
             script {
                 fun main5() {}
             }
+            //#time:24.06.2020T16:51:1
+            //#senders:0x01,0x02
+            //#signer_auth:0x02:2:false
+            //#balance:0x01:xfi:1000
+            script {
+                fun main6() {}
+            }
        ";
         let pipeline = TestPipeline::new("pipeline", content, CORE_CODE_ADDRESS).unwrap();
         assert_eq!(
@@ -396,28 +501,39 @@ pub mod tests {
                     TestStep::PublishModule((
                         TestMeta {
                             senders: vec![CORE_CODE_ADDRESS],
+                            fee_payer: None,
+                            signers_auth: vec![],
+                            balances: vec![],
                             gas: 100000000,
                             expected_result: ExecutionResult::Success,
                             block: 100,
                             time: 1593017400,
                             oracle_price_list: vec![],
+                            oracle_price_time_list: vec![],
                         },
                         "Test1".to_owned()
                     )),
                     TestStep::ExecuteScript((
                         TestMeta {
                             senders: vec![CORE_CODE_ADDRESS],
+                            fee_payer: None,
+                            signers_auth: vec![],
+                            balances: vec![],
                             gas: 100000000,
                             expected_result: ExecutionResult::Success,
                             block: 100,
                             time: 1593017400,
                             oracle_price_list: vec![],
+                            oracle_price_time_list: vec![],
                         },
                         "main1".to_owned()
                     )),
                     TestStep::ExecuteScript((
                         TestMeta {
                             senders: vec![CORE_CODE_ADDRESS],
+                            fee_payer: None,
+                            signers_auth: vec![],
+                            balances: vec![],
                             gas: 100000000,
                             expected_result: ExecutionResult::Error {
                                 main_status: None,
@@ -426,23 +542,31 @@ pub mod tests {
                             block: 100,
                             time: 1593017400,
                             oracle_price_list: vec![],
+                            oracle_price_time_list: vec![],
                         },
                         "main2".to_owned()
                     )),
                     TestStep::PublishModule((
                         TestMeta {
                             senders: vec![sender("0x02"), sender("0x01")],
+                            fee_payer: None,
+                            signers_auth: vec![],
+                            balances: vec![],
                             gas: 100000000,
                             expected_result: ExecutionResult::Success,
                             block: 100,
                             time: 1593017400,
                             oracle_price_list: vec![],
+                            oracle_price_time_list: vec![],
                         },
                         "Test2".to_owned()
                     )),
                     TestStep::ExecuteScript((
                         TestMeta {
                             senders: vec![sender("0x01"), sender("0x0202")],
+                            fee_payer: None,
+                            signers_auth: vec![],
+                            balances: vec![],
                             gas: 100,
                             expected_result: ExecutionResult::Error {
                                 main_status: None,
@@ -451,23 +575,31 @@ pub mod tests {
                             block: 100,
                             time: 1593017401,
                             oracle_price_list: vec![],
+                            oracle_price_time_list: vec![],
                         },
                         "main3".to_owned()
                     )),
                     TestStep::ExecuteScript((
                         TestMeta {
                             senders: vec![CORE_CODE_ADDRESS],
+                            fee_payer: None,
+                            signers_auth: vec![],
+                            balances: vec![],
                             gas: 100000000,
                             expected_result: ExecutionResult::Success,
                             block: 1,
                             time: 1592978400,
                             oracle_price_list: vec![],
+                            oracle_price_time_list: vec![],
                         },
                         "main4".to_owned()
                     )),
                     TestStep::ExecuteScript((
                         TestMeta {
                             senders: vec![CORE_CODE_ADDRESS],
+                            fee_payer: None,
+                            signers_auth: vec![],
+                            balances: vec![],
                             gas: 100000000,
                             expected_result: ExecutionResult::Error {
                                 main_status: None,
@@ -476,9 +608,31 @@ pub mod tests {
                             block: 100,
                             time: 1593017461,
                             oracle_price_list: vec![(("USD".to_owned(), "BTC".to_owned()), 80)],
+                            oracle_price_time_list: vec![],
                         },
                         "main5".to_owned()
                     )),
+                    TestStep::ExecuteScript((
+                        TestMeta {
+                            senders: vec![sender("0x01"), sender("0x02")],
+                            fee_payer: None,
+                            signers_auth: vec![(
+                                sender("0x02"),
+                                SignerAuth {
+                                    key_scheme: 2,
+                                    threshold_satisfied: false,
+                                }
+                            )],
+                            balances: vec![(sender("0x01"), "XFI".to_owned(), 1000)],
+                            gas: 100000000,
+                            expected_result: ExecutionResult::Success,
+                            block: 100,
+                            time: 1593017461,
+                            oracle_price_list: vec![],
+                            oracle_price_time_list: vec![],
+                        },
+                        "main6".to_owned()
+                    )),
                 ]
             }
         );
@@ -506,6 +660,28 @@ pub mod tests {
             MetaTag::Address(sender("0x3")),
             MetaTag::try_from(("sender", "0x3")).unwrap()
         );
+        assert_eq!(
+            MetaTag::FeePayer(sender("0x4")),
+            MetaTag::try_from(("fee_payer", "0x4")).unwrap()
+        );
+        assert_eq!(
+            MetaTag::Senders(vec![sender("0x1"), sender("0x2")]),
+            MetaTag::try_from(("senders", "0x1,0x2")).unwrap()
+        );
+        assert_eq!(
+            MetaTag::SignerAuth((
+                sender("0x1"),
+                SignerAuth {
+                    key_scheme: 2,
+                    threshold_satisfied: true,
+                }
+            )),
+            MetaTag::try_from(("signer_auth", "0x1:2:true")).unwrap()
+        );
+        assert_eq!(
+            MetaTag::Balance((sender("0x1"), "XFI".to_owned(), 100)),
+            MetaTag::try_from(("balance", "0x1:XFI:100")).unwrap()
+        );
         assert_eq!(
             MetaTag::Block(200),
             MetaTag::try_from(("block", "200")).unwrap()
@@ -518,6 +694,14 @@ pub mod tests {
             MetaTag::Price((("USD".to_owned(), "BTC".to_owned()), 100)),
             MetaTag::try_from(("usd_btc", "100")).unwrap()
         );
+        assert_eq!(
+            MetaTag::PriceTime((("USD".to_owned(), "BTC".to_owned()), Some(1592978400))),
+            MetaTag::try_from(("usd_btc_time", "24.06.2020T06:00:0")).unwrap()
+        );
+        assert_eq!(
+            MetaTag::PriceTime((("USD".to_owned(), "BTC".to_owned()), None)),
+            MetaTag::try_from(("usd_btc_time", "unknown")).unwrap()
+        );
     }
 
     fn sender(addr: &str) -> AccountAddress {
@@ -74,17 +74,26 @@ impl TestState {
             TestStep::PublishModule(_) => {
                 vm.publish_module(gas, ModuleTx::new(unit, step.meta().senders[0]))
             }
-            TestStep::ExecuteScript((meta, _)) => vm.execute_script(
-                gas,
-                ScriptTx::new(
+            TestStep::ExecuteScript((meta, _)) => {
+                let tx = ScriptTx::new(
                     unit,
                     vec![],
                     vec![],
                     step.meta().senders.to_owned(),
                     meta.time,
                     meta.block,
-                )?,
-            ),
+                    DEFAULT_SIGNERS_LIMIT,
+                )?;
+                let tx = match meta.fee_payer {
+                    Some(payer) => tx.with_fee_payer(payer),
+                    None => tx,
+                };
+                let tx = meta
+                    .signers_auth
+                    .iter()
+                    .try_fold(tx, |tx, (signer, auth)| tx.with_signer_auth(*signer, *auth))?;
+                vm.execute_script(gas, tx)
+            }
         };
 
         Self::handle_tx_tesult(main_ds, &step.meta().expected_result, result)
@@ -98,7 +107,20 @@ impl TestState {
     /// Store mete resources.
     fn store_meta_resources(test_meta: &TestMeta, ds: &MockDataSource) -> Result<()> {
         for ((curr_1, curr_2), price) in &test_meta.oracle_price_list {
-            ds.add_price(curr_1, curr_2, *price);
+            let timestamp = test_meta
+                .oracle_price_time_list
+                .iter()
+                .find(|((first, second), _)| first == curr_1 && second == curr_2)
+                .map(|(_, timestamp)| *timestamp)
+                .unwrap_or(Some(test_meta.time));
+            match timestamp {
+                Some(timestamp) => ds.add_price_with_time(curr_1, curr_2, *price, timestamp),
+                None => ds.add_price(curr_1, curr_2, *price),
+            }
+        }
+
+        for (address, ticker, amount) in &test_meta.balances {
+            ds.set_balance(*address, ticker, *amount);
         }
 
         let block = BlockMetadata {
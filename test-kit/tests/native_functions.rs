@@ -8,6 +8,7 @@ use dvm_test_kit::TestKit;
 use libra::{lcs, prelude::*};
 use runtime::resources::*;
 use data_source::CurrencyInfo;
+use data_source::SupplyRecord;
 
 #[test]
 fn test_native_function() {
@@ -219,6 +220,81 @@ fn test_balance() {
     assert_eq!(test_kit.get_balance(&addr_2, "BTC"), None);
 }
 
+#[test]
+fn test_canonical_order() {
+    let test_kit = TestKit::new();
+    test_kit.add_std_module(include_str!("resources/store.move"));
+
+    let mut addresses = vec![AccountAddress::random(), AccountAddress::random()];
+    addresses.sort();
+    let (addr_1, addr_2) = (addresses[1], addresses[0]);
+
+    // write set entries are ordered by address and path.
+    let script = "
+        script {
+        use 0x1::Store;
+
+        fun main(account_1: &signer, account_2: &signer) {
+            Store::store_vector_u8(account_1, x\"01\");
+            Store::store_u64(account_1, 1);
+            Store::store_address(account_2, 0x1);
+            Store::store_u64(account_2, 2);
+        }
+        }
+    ";
+    let res = test_kit.execute_script(
+        script,
+        gas_meta(),
+        vec![],
+        vec![],
+        vec![addr_1, addr_2],
+        0,
+        0,
+    );
+    test_kit.assert_success(&res);
+    let paths = res
+        .write_set
+        .iter()
+        .map(|value| {
+            let path = value.path.as_ref().unwrap();
+            (path.address.clone(), path.path.clone())
+        })
+        .collect::<Vec<_>>();
+    let mut sorted_paths = paths.clone();
+    sorted_paths.sort();
+    assert_eq!(paths.len(), 4);
+    assert_eq!(paths, sorted_paths);
+
+    // balance changes are ordered by address and ticker.
+    test_kit.set_balance(addr_1, "USDT", 1024);
+    test_kit.set_balance(addr_1, "XFI", 64);
+    test_kit.set_balance(addr_1, "BTC", 13);
+    let res = test_kit.execute_script(
+        include_str!("resources/balance.move"),
+        gas_meta(),
+        vec![u128_arg(1024), u128_arg(64), u128_arg(13)],
+        vec![],
+        vec![addr_1, addr_2],
+        0,
+        0,
+    );
+    test_kit.assert_success(&res);
+    let changes = res
+        .balance_change_set
+        .iter()
+        .map(|change| (change.address.clone(), change.ticker.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        changes,
+        vec![
+            (addr_2.to_vec(), "USDT".to_owned()),
+            (addr_2.to_vec(), "XFI".to_owned()),
+            (addr_1.to_vec(), "USDT".to_owned()),
+            (addr_1.to_vec(), "XFI".to_owned()),
+        ]
+    );
+}
+
 #[test]
 fn test_coin_info() {
     let test_kit = TestKit::new();
@@ -252,3 +328,60 @@ fn test_coin_info() {
     );
     test_kit.assert_success(&res);
 }
+
+#[test]
+fn test_currency_registry() {
+    let test_kit = TestKit::new();
+    test_kit.set_currency_info(
+        "XFI",
+        CurrencyInfo {
+            denom: "xfi".as_bytes().to_vec(),
+            decimals: 18,
+            is_token: false,
+            address: CORE_CODE_ADDRESS,
+            total_supply: 42,
+        },
+    );
+    test_kit.set_currency_info(
+        "BTC",
+        CurrencyInfo {
+            denom: "btc".as_bytes().to_vec(),
+            decimals: 10,
+            is_token: true,
+            address: CORE_CODE_ADDRESS,
+            total_supply: 1024,
+        },
+    );
+    test_kit.add_supply_record(
+        "BTC",
+        SupplyRecord {
+            height: 20,
+            total_supply: 1024,
+        },
+    );
+    test_kit.add_supply_record(
+        "BTC",
+        SupplyRecord {
+            height: 10,
+            total_supply: 512,
+        },
+    );
+    test_kit.add_supply_record(
+        "BTC",
+        SupplyRecord {
+            height: 30,
+            total_supply: 2048,
+        },
+    );
+
+    let res = test_kit.execute_script(
+        include_str!("resources/currency_registry.move"),
+        gas_meta(),
+        vec![],
+        vec![],
+        vec![CORE_CODE_ADDRESS],
+        25,
+        0,
+    );
+    test_kit.assert_success(&res);
+}
@@ -0,0 +1,36 @@
+script {
+    use 0x1::Dfinance;
+    use 0x1::Coins;
+    use 0x1::Vector;
+    use 0x1::XFI;
+
+    fun main() {
+        let currencies = Dfinance::currencies();
+        assert(Vector::length(&currencies) == 2, 1);
+
+        let btc = Vector::borrow(&currencies, 0);
+        assert(Dfinance::currency_ticker(btc) == b"BTC", 2);
+        assert(Dfinance::currency_denom(btc) == b"btc", 3);
+        assert(Dfinance::currency_decimals(btc) == 10, 4);
+        assert(Dfinance::currency_is_token(btc), 5);
+        assert(Dfinance::currency_total_supply(btc) == 1024, 6);
+
+        assert(Dfinance::is_registered(&b"XFI"), 7);
+        assert(!Dfinance::is_registered(&b"ETH"), 8);
+        let xfi = Dfinance::currency(&b"XFI");
+        assert(Dfinance::currency_decimals(&xfi) == 18, 9);
+        assert(!Dfinance::currency_is_token(&xfi), 10);
+        assert(Dfinance::currency_owner(&xfi) == 0x1, 11);
+
+        assert(Dfinance::has_supply_history<Coins::BTC>(), 12);
+        assert(!Dfinance::has_supply_history<XFI::T>(), 13);
+        let history = Dfinance::supply_history<Coins::BTC>();
+        assert(Vector::length(&history) == 2, 14);
+        let first = Vector::borrow(&history, 0);
+        assert(Dfinance::record_height(first) == 10, 15);
+        assert(Dfinance::record_total_supply(first) == 512, 16);
+        let second = Vector::borrow(&history, 1);
+        assert(Dfinance::record_height(second) == 20, 17);
+        assert(Dfinance::record_total_supply(second) == 1024, 18);
+    }
+}